@@ -0,0 +1,330 @@
+use crate::matchmaker::pack_parties;
+use crate::types::TeamBalanceConfig;
+
+/// Most party-to-team assignments the exact search may enumerate (6v6 of solos is 2^11)
+const EXACT_SEARCH_LIMIT: f64 = 4096.0;
+
+/// Smallest cost reduction the local search counts as an improvement
+const MIN_IMPROVEMENT: f64 = 1e-12;
+
+/// A party (or solo player) that is always placed on one team
+pub struct PartyEntry {
+    /// The party's MMR as the matchmaker rates it, used for team means
+    pub mmr: f64,
+    /// Each member's MMR, used for team spread and top players
+    pub member_mmrs: Vec<f64>,
+}
+
+impl PartyEntry {
+    pub fn size(&self) -> usize {
+        self.member_mmrs.len()
+    }
+}
+
+/// Assign parties to `team_count` teams of at most `capacity` players, minimizing `cost`.
+/// With `exact`, lobbies with few enough assignments are searched exhaustively; otherwise a
+/// greedy draft is improved by local search. Returns each party's team, or None if they don't fit.
+pub fn partition(
+    entries: &[PartyEntry],
+    team_count: usize,
+    capacity: usize,
+    objective: &TeamBalanceConfig,
+    exact: bool,
+) -> Option<Vec<usize>> {
+    let assignments = (team_count as f64).powi(entries.len().saturating_sub(1) as i32);
+    if exact && assignments <= EXACT_SEARCH_LIMIT {
+        if let Some(assignment) = exact_search(entries, team_count, capacity, objective) {
+            return Some(assignment);
+        }
+    }
+    let mut assignment = draft(entries, team_count, capacity).or_else(|| {
+        let sizes: Vec<usize> = entries.iter().map(PartyEntry::size).collect();
+        pack_parties(&sizes, team_count, capacity)
+    })?;
+    improve(entries, &mut assignment, team_count, capacity, objective);
+    Some(assignment)
+}
+
+/// Weighted gaps between the highest and lowest team in mean MMR, MMR spread and top player
+pub fn cost(entries: &[PartyEntry], assignment: &[usize], team_count: usize, objective: &TeamBalanceConfig) -> f64 {
+    let mut skill = vec![0.0; team_count];
+    let mut sums = vec![0.0; team_count];
+    let mut squares = vec![0.0; team_count];
+    let mut tops = vec![f64::NEG_INFINITY; team_count];
+    let mut players = vec![0; team_count];
+    for (entry, &team) in entries.iter().zip(assignment) {
+        skill[team] += entry.mmr * entry.size() as f64;
+        players[team] += entry.size();
+        for &mmr in &entry.member_mmrs {
+            sums[team] += mmr;
+            squares[team] += mmr * mmr;
+            tops[team] = tops[team].max(mmr);
+        }
+    }
+
+    let mut means = Vec::with_capacity(team_count);
+    let mut spreads = Vec::with_capacity(team_count);
+    for team in 0..team_count {
+        // Empty teams count as average, zero-spread teams with no top player
+        let n = players[team].max(1) as f64;
+        means.push(skill[team] / n);
+        spreads.push((squares[team] / n - (sums[team] / n).powi(2)).max(0.0).sqrt());
+        if players[team] == 0 {
+            tops[team] = 0.0;
+        }
+    }
+    let gap = |values: &[f64]| {
+        let highest = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let lowest = values.iter().copied().fold(f64::INFINITY, f64::min);
+        highest - lowest
+    };
+    objective.mean_gap_weight * gap(&means)
+        + objective.variance_gap_weight * gap(&spreads)
+        + objective.top_player_gap_weight * gap(&tops)
+}
+
+/// Best assignment over all of them; teams are interchangeable, so each party may only open
+/// the next unused team
+fn exact_search(
+    entries: &[PartyEntry],
+    team_count: usize,
+    capacity: usize,
+    objective: &TeamBalanceConfig,
+) -> Option<Vec<usize>> {
+    let mut best = None;
+    let mut assignment = Vec::with_capacity(entries.len());
+    let mut loads = vec![0; team_count];
+    exact_search_from(entries, team_count, capacity, objective, &mut assignment, &mut loads, &mut best);
+    best.map(|(_, assignment)| assignment)
+}
+
+fn exact_search_from(
+    entries: &[PartyEntry],
+    team_count: usize,
+    capacity: usize,
+    objective: &TeamBalanceConfig,
+    assignment: &mut Vec<usize>,
+    loads: &mut [usize],
+    best: &mut Option<(f64, Vec<usize>)>,
+) {
+    let used = loads.iter().filter(|&&load| load > 0).count();
+    if assignment.len() == entries.len() {
+        // Every team gets at least one party when there are enough of them
+        if used == team_count.min(entries.len()) {
+            let cost = cost(entries, assignment, team_count, objective);
+            if best.as_ref().is_none_or(|(best_cost, _)| cost < *best_cost) {
+                *best = Some((cost, assignment.clone()));
+            }
+        }
+        return;
+    }
+    let size = entries[assignment.len()].size();
+    for team in 0..(used + 1).min(team_count) {
+        if loads[team] + size <= capacity {
+            loads[team] += size;
+            assignment.push(team);
+            exact_search_from(entries, team_count, capacity, objective, assignment, loads, best);
+            assignment.pop();
+            loads[team] -= size;
+        }
+    }
+}
+
+/// Largest, then highest-rated, parties first, each to the team with the fewest players
+/// (then the lowest total MMR) that has room
+fn draft(entries: &[PartyEntry], team_count: usize, capacity: usize) -> Option<Vec<usize>> {
+    let mut order: Vec<usize> = (0..entries.len()).collect();
+    order.sort_by(|&a, &b| {
+        entries[b]
+            .size()
+            .cmp(&entries[a].size())
+            .then(entries[b].mmr.total_cmp(&entries[a].mmr))
+    });
+    let mut loads = vec![0; team_count];
+    let mut skill = vec![0.0_f64; team_count];
+    let mut assignment = vec![0; entries.len()];
+    for i in order {
+        let size = entries[i].size();
+        let team = (0..team_count)
+            .filter(|&team| loads[team] + size <= capacity)
+            .min_by(|&a, &b| loads[a].cmp(&loads[b]).then(skill[a].total_cmp(&skill[b])))?;
+        loads[team] += size;
+        skill[team] += entries[i].mmr * size as f64;
+        assignment[i] = team;
+    }
+    Some(assignment)
+}
+
+/// Local search: move a party to another team with room, or swap two parties on different
+/// teams, whenever that lowers the cost, until a pass finds no improvement
+fn improve(
+    entries: &[PartyEntry],
+    assignment: &mut [usize],
+    team_count: usize,
+    capacity: usize,
+    objective: &TeamBalanceConfig,
+) {
+    let mut loads = vec![0; team_count];
+    for (entry, &team) in entries.iter().zip(assignment.iter()) {
+        loads[team] += entry.size();
+    }
+    let mut current = cost(entries, assignment, team_count, objective);
+
+    for _ in 0..objective.max_passes {
+        let mut improved = false;
+        for i in 0..entries.len() {
+            let size = entries[i].size();
+            for team in 0..team_count {
+                let from = assignment[i];
+                // Moves never empty a team
+                if team == from || loads[team] + size > capacity || loads[from] == size {
+                    continue;
+                }
+                assignment[i] = team;
+                let candidate = cost(entries, assignment, team_count, objective);
+                if candidate < current - MIN_IMPROVEMENT {
+                    current = candidate;
+                    loads[from] -= size;
+                    loads[team] += size;
+                    improved = true;
+                } else {
+                    assignment[i] = from;
+                }
+            }
+            for j in i + 1..entries.len() {
+                let (a, b) = (assignment[i], assignment[j]);
+                let other = entries[j].size();
+                if a == b || loads[a] - size + other > capacity || loads[b] - other + size > capacity {
+                    continue;
+                }
+                assignment[i] = b;
+                assignment[j] = a;
+                let candidate = cost(entries, assignment, team_count, objective);
+                if candidate < current - MIN_IMPROVEMENT {
+                    current = candidate;
+                    loads[a] = loads[a] - size + other;
+                    loads[b] = loads[b] - other + size;
+                    improved = true;
+                } else {
+                    assignment[i] = a;
+                    assignment[j] = b;
+                }
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn solo(mmr: f64) -> PartyEntry {
+        PartyEntry { mmr, member_mmrs: vec![mmr] }
+    }
+
+    fn party(member_mmrs: Vec<f64>) -> PartyEntry {
+        let mmr = member_mmrs.iter().sum::<f64>() / member_mmrs.len() as f64;
+        PartyEntry { mmr, member_mmrs }
+    }
+
+    fn team_loads(entries: &[PartyEntry], assignment: &[usize], team_count: usize) -> Vec<usize> {
+        let mut loads = vec![0; team_count];
+        for (entry, &team) in entries.iter().zip(assignment) {
+            loads[team] += entry.size();
+        }
+        loads
+    }
+
+    #[test]
+    fn test_exact_search_finds_best_split() {
+        let objective = TeamBalanceConfig::default();
+        let entries: Vec<PartyEntry> = [0.9, 0.7, 0.4, 0.3, 0.2, 0.1, -0.1, -0.3, -0.4, -0.5, -0.6, -0.8]
+            .into_iter()
+            .map(solo)
+            .collect();
+        let assignment = partition(&entries, 2, 6, &objective, true).unwrap();
+        assert_eq!(team_loads(&entries, &assignment, 2), vec![6, 6]);
+
+        // No 6-6 split of the lobby does better
+        let best = (0..1u32 << 12)
+            .filter(|mask| mask.count_ones() == 6)
+            .map(|mask| {
+                let split: Vec<usize> = (0..12).map(|i| (mask >> i & 1) as usize).collect();
+                cost(&entries, &split, 2, &objective)
+            })
+            .fold(f64::INFINITY, f64::min);
+        assert!((cost(&entries, &assignment, 2, &objective) - best).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_local_search_balances_ground_war_with_parties() {
+        let mut rng = StdRng::seed_from_u64(9);
+        let mut entries = Vec::new();
+        let mut players = 0;
+        while players < 64 {
+            let size = rng.gen_range(1..=4).min(64 - players);
+            entries.push(party((0..size).map(|_| rng.gen_range(-1.0..1.0)).collect()));
+            players += size;
+        }
+        let objective = TeamBalanceConfig::default();
+        let assignment = partition(&entries, 2, 32, &objective, true).unwrap();
+        assert_eq!(team_loads(&entries, &assignment, 2), vec![32, 32]);
+
+        let drafted = draft(&entries, 2, 32).unwrap();
+        let balanced = cost(&entries, &assignment, 2, &objective);
+        assert!(balanced <= cost(&entries, &drafted, 2, &objective));
+        assert!(balanced < 0.01, "mean gap {}", balanced);
+    }
+
+    #[test]
+    fn test_top_player_weight_splits_best_players() {
+        // Mean-only balance evens the means by pairing the two best players with the worst
+        let entries: Vec<PartyEntry> = [1.0, 0.9, -0.7, 0.4, 0.4, 0.4].into_iter().map(solo).collect();
+        for exact in [true, false] {
+            let assignment = partition(&entries, 2, 3, &TeamBalanceConfig::default(), exact).unwrap();
+            assert_eq!(team_loads(&entries, &assignment, 2), vec![3, 3]);
+            assert_eq!(assignment[0], assignment[1]);
+
+            let objective = TeamBalanceConfig {
+                top_player_gap_weight: 1.0,
+                ..TeamBalanceConfig::default()
+            };
+            let assignment = partition(&entries, 2, 3, &objective, exact).unwrap();
+            assert_eq!(team_loads(&entries, &assignment, 2), vec![3, 3]);
+            assert_ne!(assignment[0], assignment[1]);
+        }
+    }
+
+    #[test]
+    fn test_variance_weight_evens_out_team_spread() {
+        let spread_gap = |entries: &[PartyEntry], assignment: &[usize]| {
+            let objective = TeamBalanceConfig {
+                mean_gap_weight: 0.0,
+                variance_gap_weight: 1.0,
+                ..TeamBalanceConfig::default()
+            };
+            cost(entries, assignment, 2, &objective)
+        };
+
+        // Mean-only balance puts both extremes on one team and both average players on the other
+        let entries: Vec<PartyEntry> = [1.0, -1.0, 0.1, -0.1].into_iter().map(solo).collect();
+        for exact in [true, false] {
+            let assignment = partition(&entries, 2, 2, &TeamBalanceConfig::default(), exact).unwrap();
+            assert_eq!(assignment[0], assignment[1]);
+            assert!((spread_gap(&entries, &assignment) - 0.9).abs() < 1e-9);
+
+            let objective = TeamBalanceConfig {
+                variance_gap_weight: 2.0,
+                ..TeamBalanceConfig::default()
+            };
+            let assignment = partition(&entries, 2, 2, &objective, exact).unwrap();
+            assert_ne!(assignment[0], assignment[1]);
+            assert!(spread_gap(&entries, &assignment) < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,458 @@
+use crate::matchmaker::{reserve_server, FeasibilityResult, MatchResult, Matchmaker, QueueView};
+use crate::strategy::MatchmakingStrategy;
+use crate::types::*;
+use rand::rngs::StdRng;
+use std::collections::{HashMap, HashSet};
+
+/// Minimum quality improvement for a local search move to be accepted
+const IMPROVEMENT_EPSILON: f64 = 1e-9;
+
+/// A lobby under construction (search indices into the queue)
+struct Lobby {
+    playlist: Playlist,
+    members: Vec<usize>,
+    data_center_id: usize,
+    skill_disparity: f64,
+    quality: f64,
+}
+
+impl Lobby {
+    fn mean_percentile(&self, searches: &[SearchObject]) -> f64 {
+        self.members.iter().map(|&i| searches[i].avg_skill_percentile).sum::<f64>()
+            / self.members.len() as f64
+    }
+}
+
+/// Batch-optimal lobby assignment
+/// Starts from the greedy seed assignment for the whole queue, then runs local search
+/// (member replacements from the queue, member exchanges between neighboring lobbies,
+/// and re-seeding of freed searches) to maximize the total lobby quality of the batch.
+/// Every lobby it keeps still passes `check_feasibility`.
+pub struct BatchMatchmaker {
+    matchmaker: Matchmaker,
+    batch: BatchConfig,
+    tick_interval: f64,
+    report: Option<BatchTickReport>,
+}
+
+impl BatchMatchmaker {
+    pub fn new(config: MatchmakingConfig) -> Self {
+        let batch = config.batch_config.clone();
+        let tick_interval = config.tick_interval;
+        Self {
+            matchmaker: Matchmaker::new(config),
+            batch,
+            tick_interval,
+            report: None,
+        }
+    }
+
+    /// Feasibility and quality of a candidate lobby against the current server state
+    fn evaluate(&self, queue: &QueueView, members: &[usize], playlist: Playlist) -> Option<(FeasibilityResult, f64)> {
+        let lobby_searches: Vec<_> = members.iter().map(|&i| &queue.searches[i]).collect();
+        let feasibility = queue.check(&self.matchmaker, &lobby_searches, playlist)?;
+        let quality = self.matchmaker.calculate_quality(
+            &lobby_searches,
+            queue.players,
+            feasibility.data_center_id,
+            playlist,
+            queue.current_time,
+        );
+        Some((feasibility, quality))
+    }
+
+    /// Seed + greedy construction over all searches not yet assigned to a lobby
+    fn seed_lobbies(
+        &self,
+        searches: &[SearchObject],
+        players: &HashMap<usize, Player>,
+        data_centers: &mut [DataCenter],
+        assigned: &mut HashSet<usize>,
+        current_time: u64,
+    ) -> Vec<Lobby> {
+        let mut lobbies = Vec::new();
+        let search_order = self.matchmaker.seed_order(searches, current_time);
+
+        let unassigned: Vec<usize> = search_order
+            .into_iter()
+            .filter(|&idx| !assigned.contains(&searches[idx].id))
+            .collect();
+        let pools = self.matchmaker.playlist_pools(searches, &unassigned);
+        let health = self.matchmaker.playlist_health(searches, data_centers);
+
+        for (seeds, pool_ids) in self.matchmaker.seed_passes(&pools, &unassigned) {
+            for seed_idx in seeds {
+                if assigned.contains(&searches[seed_idx].id) {
+                    continue;
+                }
+
+                let queue = QueueView { searches, players, data_centers, current_time };
+                let Some((playlist, members, feasibility)) = self
+                    .matchmaker
+                    .grow_seed_lobby(&queue, seed_idx, &pools, &pool_ids, assigned, &health)
+                    .lobby
+                else {
+                    continue;
+                };
+
+                let lobby_searches: Vec<_> = members.iter().map(|&i| &searches[i]).collect();
+                let quality = self.matchmaker.calculate_quality(
+                    &lobby_searches,
+                    players,
+                    feasibility.data_center_id,
+                    playlist,
+                    current_time,
+                );
+
+                for &idx in &members {
+                    assigned.insert(searches[idx].id);
+                }
+                reserve_server(data_centers, feasibility.data_center_id, playlist);
+
+                lobbies.push(Lobby {
+                    playlist,
+                    members,
+                    data_center_id: feasibility.data_center_id,
+                    skill_disparity: feasibility.skill_disparity,
+                    quality,
+                });
+            }
+        }
+
+        lobbies
+    }
+
+    /// Try replacing each member of a lobby with a same-size queued search that has waited longer
+    fn improve_by_replacement(
+        &self,
+        queue_state: (&[SearchObject], &HashMap<usize, Player>, u64),
+        data_centers: &mut [DataCenter],
+        lobby: &mut Lobby,
+        assigned: &mut HashSet<usize>,
+    ) -> bool {
+        let (searches, players, current_time) = queue_state;
+        let mut improved = false;
+
+        for pos in 0..lobby.members.len() {
+            let member = &searches[lobby.members[pos]];
+            let mut candidates: Vec<(usize, f64)> = (0..searches.len())
+                .filter(|&idx| {
+                    let search = &searches[idx];
+                    !assigned.contains(&search.id)
+                        && search.size() == member.size()
+                        && search.search_start_time < member.search_start_time
+                        && search.acceptable_playlists.contains(&lobby.playlist)
+                })
+                .map(|idx| (idx, self.matchmaker.calculate_distance(member, &searches[idx])))
+                .collect();
+            candidates.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+            candidates.truncate(self.batch.swap_candidates);
+
+            for (cand_idx, _) in candidates {
+                let mut members = lobby.members.clone();
+                members[pos] = cand_idx;
+
+                release_server(data_centers, lobby.data_center_id, lobby.playlist);
+                let queue = QueueView { searches, players, data_centers, current_time };
+                match self.evaluate(&queue, &members, lobby.playlist) {
+                    Some((feasibility, quality)) if quality > lobby.quality + IMPROVEMENT_EPSILON => {
+                        assigned.remove(&searches[lobby.members[pos]].id);
+                        assigned.insert(searches[cand_idx].id);
+                        reserve_server(data_centers, feasibility.data_center_id, lobby.playlist);
+                        lobby.members = members;
+                        lobby.data_center_id = feasibility.data_center_id;
+                        lobby.skill_disparity = feasibility.skill_disparity;
+                        lobby.quality = quality;
+                        improved = true;
+                        break;
+                    }
+                    _ => reserve_server(data_centers, lobby.data_center_id, lobby.playlist),
+                }
+            }
+        }
+
+        improved
+    }
+
+    /// Try exchanging same-size members between a lobby and its nearest lobbies in skill
+    fn improve_by_exchange(
+        &self,
+        queue_state: (&[SearchObject], &HashMap<usize, Player>, u64),
+        data_centers: &mut [DataCenter],
+        lobbies: &mut [Lobby],
+        a: usize,
+    ) -> bool {
+        let (searches, players, current_time) = queue_state;
+        let playlist = lobbies[a].playlist;
+        let mean_a = lobbies[a].mean_percentile(searches);
+
+        let mut neighbors: Vec<(usize, f64)> = lobbies
+            .iter()
+            .enumerate()
+            .filter(|&(b, lobby)| b != a && lobby.playlist == playlist)
+            .map(|(b, lobby)| (b, (lobby.mean_percentile(searches) - mean_a).abs()))
+            .collect();
+        neighbors.sort_by(|x, y| x.1.partial_cmp(&y.1).unwrap());
+        neighbors.truncate(self.batch.neighbor_lobbies);
+
+        let mut improved = false;
+        for (b, _) in neighbors {
+            for pos_a in 0..lobbies[a].members.len() {
+                let member_a = &searches[lobbies[a].members[pos_a]];
+                // Best exchange partner: the same-size member of B closest to A's mean skill
+                let Some(pos_b) = (0..lobbies[b].members.len())
+                    .filter(|&pos| searches[lobbies[b].members[pos]].size() == member_a.size())
+                    .min_by(|&x, &y| {
+                        let dx = (searches[lobbies[b].members[x]].avg_skill_percentile - mean_a).abs();
+                        let dy = (searches[lobbies[b].members[y]].avg_skill_percentile - mean_a).abs();
+                        dx.partial_cmp(&dy).unwrap()
+                    })
+                else {
+                    continue;
+                };
+
+                let mut members_a = lobbies[a].members.clone();
+                let mut members_b = lobbies[b].members.clone();
+                std::mem::swap(&mut members_a[pos_a], &mut members_b[pos_b]);
+
+                release_server(data_centers, lobbies[a].data_center_id, playlist);
+                release_server(data_centers, lobbies[b].data_center_id, playlist);
+
+                let queue = QueueView { searches, players, data_centers, current_time };
+                let new_a = self.evaluate(&queue, &members_a, playlist);
+                if let Some((feasibility_a, _)) = &new_a {
+                    reserve_server(data_centers, feasibility_a.data_center_id, playlist);
+                }
+                let queue = QueueView { searches, players, data_centers, current_time };
+                let new_b = new_a.as_ref().and_then(|_| self.evaluate(&queue, &members_b, playlist));
+
+                match (new_a, new_b) {
+                    (Some((feasibility_a, quality_a)), Some((feasibility_b, quality_b)))
+                        if quality_a + quality_b
+                            > lobbies[a].quality + lobbies[b].quality + IMPROVEMENT_EPSILON =>
+                    {
+                        reserve_server(data_centers, feasibility_b.data_center_id, playlist);
+                        for (lobby, members, feasibility, quality) in [
+                            (a, members_a, feasibility_a, quality_a),
+                            (b, members_b, feasibility_b, quality_b),
+                        ] {
+                            lobbies[lobby].members = members;
+                            lobbies[lobby].data_center_id = feasibility.data_center_id;
+                            lobbies[lobby].skill_disparity = feasibility.skill_disparity;
+                            lobbies[lobby].quality = quality;
+                        }
+                        improved = true;
+                    }
+                    (new_a, _) => {
+                        if let Some((feasibility_a, _)) = new_a {
+                            release_server(data_centers, feasibility_a.data_center_id, playlist);
+                        }
+                        reserve_server(data_centers, lobbies[a].data_center_id, playlist);
+                        reserve_server(data_centers, lobbies[b].data_center_id, playlist);
+                    }
+                }
+            }
+        }
+
+        improved
+    }
+}
+
+/// Return a server reserved for a lobby that is being re-evaluated
+fn release_server(data_centers: &mut [DataCenter], dc_id: usize, playlist: Playlist) {
+    if let Some(dc) = data_centers.iter_mut().find(|dc| dc.id == dc_id) {
+        if let Some(busy) = dc.busy_servers.get_mut(&playlist) {
+            *busy = busy.saturating_sub(1);
+        }
+    }
+}
+
+/// Total quality, matched players and remaining queue wait (player-seconds) of an assignment
+fn assignment_totals(
+    lobbies: &[Lobby],
+    searches: &[SearchObject],
+    assigned: &HashSet<usize>,
+    current_time: u64,
+    tick_interval: f64,
+) -> (f64, usize, f64) {
+    let quality = lobbies.iter().map(|l| l.quality).sum();
+    let players_matched = lobbies
+        .iter()
+        .flat_map(|l| l.members.iter())
+        .map(|&i| searches[i].size())
+        .sum();
+    let queue_wait = searches
+        .iter()
+        .filter(|s| !assigned.contains(&s.id))
+        .map(|s| s.wait_time(current_time, tick_interval) * s.size() as f64)
+        .sum();
+    (quality, players_matched, queue_wait)
+}
+
+impl MatchmakingStrategy for BatchMatchmaker {
+    fn run_tick(
+        &mut self,
+        searches: &mut Vec<SearchObject>,
+        players: &mut HashMap<usize, Player>,
+        data_centers: &mut [DataCenter],
+        parties: &HashMap<usize, Party>,
+        current_time: u64,
+        rng: &mut StdRng,
+    ) -> Vec<MatchResult> {
+        let tick_interval = self.tick_interval;
+        self.matchmaker.refresh_acceptable_dcs(searches, players, data_centers, current_time);
+
+        // Greedy assignment (identical to SeedGreedy) is the starting point and the baseline
+        let mut assigned: HashSet<usize> = HashSet::new();
+        let mut lobbies = self.seed_lobbies(searches, players, data_centers, &mut assigned, current_time);
+        let (greedy_quality, greedy_players_matched, greedy_queue_wait) =
+            assignment_totals(&lobbies, searches, &assigned, current_time, tick_interval);
+
+        for _ in 0..self.batch.improvement_passes {
+            let mut improved = false;
+            let queue_state = (searches.as_slice(), &*players, current_time);
+
+            for a in 0..lobbies.len() {
+                improved |= self.improve_by_replacement(queue_state, data_centers, &mut lobbies[a], &mut assigned);
+                improved |= self.improve_by_exchange(queue_state, data_centers, &mut lobbies, a);
+            }
+
+            // Searches freed by replacements may now form lobbies of their own
+            let new_lobbies = self.seed_lobbies(searches, players, data_centers, &mut assigned, current_time);
+            improved |= !new_lobbies.is_empty();
+            lobbies.extend(new_lobbies);
+
+            if !improved {
+                break;
+            }
+        }
+
+        let (batch_quality, batch_players_matched, batch_queue_wait) =
+            assignment_totals(&lobbies, searches, &assigned, current_time, tick_interval);
+        self.report = Some(BatchTickReport {
+            greedy_quality,
+            batch_quality,
+            greedy_players_matched,
+            batch_players_matched,
+            greedy_queue_wait,
+            batch_queue_wait,
+        });
+
+        let results = lobbies
+            .iter()
+            .map(|lobby| {
+                let lobby_searches: Vec<_> = lobby.members.iter().map(|&i| &searches[i]).collect();
+                let feasibility = FeasibilityResult {
+                    data_center_id: lobby.data_center_id,
+                    skill_disparity: lobby.skill_disparity,
+                };
+                let mut result = self.matchmaker.build_match_result(
+                    &lobby_searches,
+                    &feasibility,
+                    lobby.playlist,
+                    players,
+                    current_time,
+                );
+                result.teams = self.matchmaker.balance_teams(&result.player_ids, players, parties, lobby.playlist, rng);
+                result
+            })
+            .collect();
+
+        searches.retain(|s| !assigned.contains(&s.id));
+        results
+    }
+
+    fn batch_report(&self) -> Option<BatchTickReport> {
+        self.report.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    /// Solo searches at one location, given as (skill percentile, search start tick)
+    fn queue(specs: &[(f64, u64)]) -> (Vec<SearchObject>, HashMap<usize, Player>, Vec<DataCenter>) {
+        let mut players = HashMap::new();
+        let mut searches = Vec::new();
+        for (id, &(percentile, start)) in specs.iter().enumerate() {
+            let mut player = Player::new(id, Location::new(0.0, 0.0), 0.0);
+            player.skill_percentile = percentile;
+            player.dc_pings.insert(0, 20.0);
+            player.best_dc = Some(0);
+            player.best_ping = 20.0;
+            players.insert(id, player);
+
+            searches.push(SearchObject {
+                id,
+                player_ids: vec![id],
+                avg_skill_percentile: percentile,
+                skill_disparity: 0.0,
+                avg_location: Location::new(0.0, 0.0),
+                platforms: HashMap::new(),
+                input_devices: HashMap::new(),
+                acceptable_playlists: [Playlist::TEAM_DEATHMATCH].into_iter().collect(),
+                search_start_time: start,
+                acceptable_dcs: HashSet::new(),
+                placement: false,
+            });
+        }
+        let data_centers = vec![DataCenter::new(0, "Test", Location::new(0.0, 0.0), Region::Other, &PlaylistDefinition::defaults())];
+        (searches, players, data_centers)
+    }
+
+    fn wide_skill_config() -> MatchmakingConfig {
+        MatchmakingConfig {
+            skill_similarity_initial: 0.3,
+            max_skill_disparity_initial: 0.3,
+            ..MatchmakingConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_batch_never_worse_than_greedy_and_stays_feasible() {
+        // Two interleaved skill clusters with mixed wait times
+        let specs: Vec<(f64, u64)> = (0..24)
+            .map(|id| {
+                let base = if id % 2 == 0 { 0.40 } else { 0.55 };
+                (base + id as f64 * 0.001, id as u64 % 3)
+            })
+            .collect();
+        let (mut searches, mut players, mut data_centers) = queue(&specs);
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let mut batch = BatchMatchmaker::new(wide_skill_config());
+        let results = batch.run_tick(&mut searches, &mut players, &mut data_centers, &HashMap::new(), 10, &mut rng);
+        let report = batch.batch_report().unwrap();
+
+        assert!(report.batch_quality >= report.greedy_quality);
+        assert!(report.batch_players_matched >= report.greedy_players_matched);
+        assert_eq!(results.len(), 2);
+        // Every player is used at most once and the server count matches the lobbies
+        let all: HashSet<usize> = results.iter().flat_map(|r| r.player_ids.iter().copied()).collect();
+        assert_eq!(all.len(), 24);
+        assert_eq!(data_centers[0].busy_servers[&Playlist::TEAM_DEATHMATCH], 2);
+        assert!(searches.is_empty());
+    }
+
+    #[test]
+    fn test_batch_prefers_long_waiters_greedy_skips() {
+        // Seed waited 60s; greedy fills with 11 identical-skill fresh searches and leaves
+        // 11 long-waiting, near-identical-skill searches in the queue
+        let mut specs = vec![(0.50, 0)];
+        specs.extend(std::iter::repeat_n((0.50, 12), 11));
+        specs.extend(std::iter::repeat_n((0.52, 0), 11));
+        let (mut searches, mut players, mut data_centers) = queue(&specs);
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let mut batch = BatchMatchmaker::new(wide_skill_config());
+        let results = batch.run_tick(&mut searches, &mut players, &mut data_centers, &HashMap::new(), 12, &mut rng);
+        let report = batch.batch_report().unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert!(report.batch_quality > report.greedy_quality);
+        assert!(report.batch_queue_wait < report.greedy_queue_wait);
+        assert!(results[0].player_ids.iter().all(|&id| id == 0 || id >= 12));
+    }
+}
@@ -0,0 +1,342 @@
+use cod_matchmaking_sim::{run_sweep, ExperimentConfig, Scenario, ScenarioFormat, Simulation, SimulationStats, SweepPoint};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Headless runner for matchmaking scenarios
+
+Usage:
+  okqueue-sim run <scenario.json|scenario.toml> [options]
+  okqueue-sim sweep <scenario.json|scenario.toml> [options]
+
+Options:
+  --ticks <n>            Override the scenario's run length (ticks per run for sweeps)
+  --seed <n>             Override the scenario's seed
+  --out <path>           Output file (default: stdout)
+  --format <json|csv>    Output format (default: from the --out extension, else json)
+  --param <name>         Sweep parameter (overrides the scenario's experiment)
+  --values <a,b,...>     Sweep values (overrides the scenario's experiment)
+  --runs <n>             Runs per sweep value
+  --sample-every <n>     Time-series sampling interval in ticks for `run` (default: 10)
+  --progress-every <n>   Progress report interval in ticks for `run` (default: 100)
+  --quiet                No progress output on stderr
+  -h, --help             Show this help";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Json,
+    Csv,
+}
+
+struct Args {
+    command: String,
+    scenario: PathBuf,
+    ticks: Option<u64>,
+    seed: Option<u64>,
+    out: Option<PathBuf>,
+    format: Option<Format>,
+    param: Option<String>,
+    values: Option<Vec<f64>>,
+    runs: Option<usize>,
+    sample_every: u64,
+    progress_every: u64,
+    quiet: bool,
+}
+
+/// One row of the `run` time series
+#[derive(serde::Serialize)]
+struct Sample {
+    tick: u64,
+    players_searching: usize,
+    players_in_match: usize,
+    active_matches: usize,
+    total_matches: usize,
+    avg_search_time: f64,
+    avg_delta_ping: f64,
+    avg_skill_disparity: f64,
+    avg_match_quality: f64,
+    blowout_rate: f64,
+}
+
+impl Sample {
+    const CSV_HEADER: &'static str = "tick,players_searching,players_in_match,active_matches,total_matches,avg_search_time,avg_delta_ping,avg_skill_disparity,avg_match_quality,blowout_rate";
+
+    fn from_stats(tick: u64, stats: &SimulationStats) -> Self {
+        Self {
+            tick,
+            players_searching: stats.players_searching,
+            players_in_match: stats.players_in_match,
+            active_matches: stats.active_matches,
+            total_matches: stats.total_matches,
+            avg_search_time: stats.avg_search_time,
+            avg_delta_ping: stats.avg_delta_ping,
+            avg_skill_disparity: stats.avg_skill_disparity,
+            avg_match_quality: stats.avg_match_quality,
+            blowout_rate: stats.blowout_rate,
+        }
+    }
+
+    fn csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{}",
+            self.tick,
+            self.players_searching,
+            self.players_in_match,
+            self.active_matches,
+            self.total_matches,
+            self.avg_search_time,
+            self.avg_delta_ping,
+            self.avg_skill_disparity,
+            self.avg_match_quality,
+            self.blowout_rate
+        )
+    }
+}
+
+fn main() -> ExitCode {
+    let argv: Vec<String> = std::env::args().skip(1).collect();
+    if argv.is_empty() || argv.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+    match parse_args(argv).and_then(execute) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_args(argv: Vec<String>) -> Result<Args, String> {
+    let mut iter = argv.into_iter();
+    let command = iter.next().ok_or("Missing command")?;
+    if command != "run" && command != "sweep" {
+        return Err(format!("Unknown command: {} (expected \"run\" or \"sweep\")", command));
+    }
+    let scenario = PathBuf::from(iter.next().ok_or("Missing scenario file")?);
+
+    let mut args = Args {
+        command,
+        scenario,
+        ticks: None,
+        seed: None,
+        out: None,
+        format: None,
+        param: None,
+        values: None,
+        runs: None,
+        sample_every: 10,
+        progress_every: 100,
+        quiet: false,
+    };
+
+    while let Some(flag) = iter.next() {
+        if flag == "--quiet" {
+            args.quiet = true;
+            continue;
+        }
+        let value = iter.next().ok_or_else(|| format!("Missing value for {}", flag))?;
+        match flag.as_str() {
+            "--ticks" => args.ticks = Some(parse_number(&flag, &value)?),
+            "--seed" => args.seed = Some(parse_number(&flag, &value)?),
+            "--out" => args.out = Some(PathBuf::from(value)),
+            "--format" => args.format = Some(parse_format(&value)?),
+            "--param" => args.param = Some(value),
+            "--values" => {
+                let values = value
+                    .split(',')
+                    .map(|v| parse_number(&flag, v.trim()))
+                    .collect::<Result<Vec<f64>, String>>()?;
+                args.values = Some(values);
+            }
+            "--runs" => args.runs = Some(parse_number(&flag, &value)?),
+            "--sample-every" => args.sample_every = parse_number::<u64>(&flag, &value)?.max(1),
+            "--progress-every" => args.progress_every = parse_number::<u64>(&flag, &value)?.max(1),
+            _ => return Err(format!("Unknown option: {}", flag)),
+        }
+    }
+    Ok(args)
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value for {}: {}", flag, value))
+}
+
+fn parse_format(name: &str) -> Result<Format, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "json" => Ok(Format::Json),
+        "csv" => Ok(Format::Csv),
+        _ => Err(format!("Unknown output format: {} (expected \"json\" or \"csv\")", name)),
+    }
+}
+
+fn execute(args: Args) -> Result<(), String> {
+    let text = std::fs::read_to_string(&args.scenario)
+        .map_err(|e| format!("Cannot read {}: {}", args.scenario.display(), e))?;
+    let scenario_format = match args.scenario.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("toml") => ScenarioFormat::Toml,
+        _ => ScenarioFormat::Json,
+    };
+    let mut scenario = Scenario::parse(&text, scenario_format)?;
+    if let Some(seed) = args.seed {
+        scenario.seed = seed;
+    }
+
+    let format = args
+        .format
+        .or_else(|| {
+            let ext = args.out.as_deref()?.extension()?.to_str()?;
+            parse_format(ext).ok()
+        })
+        .unwrap_or(Format::Json);
+
+    let output = if args.command == "run" {
+        run_single(&args, &mut scenario, format)?
+    } else {
+        run_experiment(&args, &scenario, format)?
+    };
+
+    match &args.out {
+        Some(path) => write_output(path, &output),
+        None => {
+            print!("{}", output);
+            Ok(())
+        }
+    }
+}
+
+fn run_single(args: &Args, scenario: &mut Scenario, format: Format) -> Result<String, String> {
+    if let Some(ticks) = args.ticks {
+        scenario.ticks = ticks;
+    }
+    let mut sim: Simulation = scenario.build();
+    let mut samples = Vec::new();
+
+    for t in 1..=scenario.ticks {
+        sim.tick();
+        if t % args.sample_every == 0 || t == scenario.ticks {
+            samples.push(Sample::from_stats(t, &sim.stats));
+        }
+        if !args.quiet && (t % args.progress_every == 0 || t == scenario.ticks) {
+            eprintln!(
+                "[{}/{}] matches={} searching={} avg_search={:.1}s",
+                t, scenario.ticks, sim.stats.total_matches, sim.stats.players_searching, sim.stats.avg_search_time
+            );
+        }
+    }
+
+    match format {
+        Format::Csv => Ok(csv(Sample::CSV_HEADER, samples.iter().map(Sample::csv_row))),
+        Format::Json => {
+            let report = serde_json::json!({
+                "scenario": scenario.name,
+                "seed": scenario.seed,
+                "ticks": scenario.ticks,
+                "stats": sim.stats,
+                "time_series": samples,
+            });
+            serde_json::to_string_pretty(&report)
+                .map(|s| s + "\n")
+                .map_err(|e| format!("Serialization error: {}", e))
+        }
+    }
+}
+
+fn run_experiment(args: &Args, scenario: &Scenario, format: Format) -> Result<String, String> {
+    let mut experiment = scenario.experiment.clone().unwrap_or(ExperimentConfig {
+        name: scenario.name.clone(),
+        description: String::new(),
+        parameter: String::new(),
+        values: Vec::new(),
+        runs_per_value: 1,
+        ticks_per_run: scenario.ticks,
+    });
+    if let Some(param) = &args.param {
+        experiment.parameter = param.clone();
+    }
+    if let Some(values) = &args.values {
+        experiment.values = values.clone();
+    }
+    if let Some(runs) = args.runs {
+        experiment.runs_per_value = runs;
+    }
+    if let Some(ticks) = args.ticks {
+        experiment.ticks_per_run = ticks;
+    }
+    if experiment.parameter.is_empty() || experiment.values.is_empty() {
+        return Err("Sweep needs a parameter and values (scenario \"experiment\" or --param/--values)".to_string());
+    }
+
+    let total = experiment.values.len() * experiment.runs_per_value.max(1);
+    let mut done = 0;
+    let points = run_sweep(
+        &scenario.config,
+        &experiment,
+        scenario.seed,
+        |config, seed| scenario.build_with(config, seed),
+        |point| {
+            done += 1;
+            if !args.quiet {
+                eprintln!(
+                    "[{}/{}] {}={} run={} matches={} avg_search={:.1}s",
+                    done, total, experiment.parameter, point.parameter_value, point.run, point.total_matches, point.avg_search_time
+                );
+            }
+        },
+    )?;
+
+    match format {
+        Format::Csv => Ok(csv(SweepPoint::CSV_HEADER, points.iter().map(SweepPoint::csv_row))),
+        Format::Json => {
+            let report = serde_json::json!({
+                "scenario": scenario.name,
+                "experiment": experiment,
+                "results": points,
+            });
+            serde_json::to_string_pretty(&report)
+                .map(|s| s + "\n")
+                .map_err(|e| format!("Serialization error: {}", e))
+        }
+    }
+}
+
+fn csv(header: &str, rows: impl Iterator<Item = String>) -> String {
+    let mut out = format!("{}\n", header);
+    for row in rows {
+        out.push_str(&row);
+        out.push('\n');
+    }
+    out
+}
+
+fn write_output(path: &Path, output: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+    }
+    std::fs::write(path, output).map_err(|e| format!("Cannot write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_exports_measured_match_quality() {
+        let args = parse_args(["run", "scenario.json", "--quiet", "--sample-every", "50"].map(String::from).to_vec()).unwrap();
+        let mut scenario = Scenario {
+            population: 1000,
+            ticks: 100,
+            arrival_rate: 30.0,
+            ..Scenario::default()
+        };
+        let output = run_single(&args, &mut scenario, Format::Csv).unwrap();
+
+        let mut lines = output.lines();
+        let column = lines.next().unwrap().split(',').position(|name| name == "avg_match_quality").unwrap();
+        let last: f64 = lines.last().unwrap().split(',').nth(column).unwrap().parse().unwrap();
+        assert!(last > 0.0, "avg_match_quality {}", last);
+    }
+}
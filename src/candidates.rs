@@ -0,0 +1,223 @@
+use crate::types::SearchObject;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+/// Target number of searches per skill bucket
+const SEARCHES_PER_BUCKET: usize = 32;
+/// Upper bound on skill buckets per index
+const MAX_BUCKETS: usize = 1024;
+/// Slack on the skill lower bound so floating-point rounding never prunes a true neighbor
+const BOUND_SLACK: f64 = 1e-12;
+
+/// Searches in one skill bucket that share an acceptable DC set
+struct DcGroup {
+    /// Sorted acceptable DC ids shared by every member
+    dcs: Vec<usize>,
+    /// (search index, position in the playlist's queue order, skill percentile)
+    members: Vec<(usize, usize, f64)>,
+}
+
+/// Candidate distance with a total order, for the running top-k heap
+#[derive(PartialEq)]
+struct HeapDistance(f64);
+
+impl Eq for HeapDistance {}
+
+impl PartialOrd for HeapDistance {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HeapDistance {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Index over one playlist's searches, bucketed by skill percentile and acceptable DC set
+/// Candidate generation visits buckets outward from the seed's percentile and stops once the
+/// skill gap alone rules out beating the current k-th nearest candidate, so it only touches
+/// the seed's skill neighborhood instead of the whole queue. The same bound skips the full
+/// distance computation for individual searches inside a visited bucket.
+pub struct CandidateIndex {
+    buckets: Vec<Vec<DcGroup>>,
+}
+
+impl CandidateIndex {
+    /// Build the index for a playlist's searches (given in queue order)
+    pub fn build(searches: &[SearchObject], playlist_searches: &[usize]) -> Self {
+        let bucket_count = (playlist_searches.len() / SEARCHES_PER_BUCKET).clamp(1, MAX_BUCKETS);
+        let mut buckets: Vec<Vec<DcGroup>> = (0..bucket_count).map(|_| Vec::new()).collect();
+        let mut group_lookup: Vec<HashMap<Vec<usize>, usize>> = vec![HashMap::new(); bucket_count];
+
+        for (pos, &idx) in playlist_searches.iter().enumerate() {
+            let search = &searches[idx];
+            let bucket = bucket_of(search.avg_skill_percentile, bucket_count);
+
+            let mut dcs: Vec<usize> = search.acceptable_dcs.iter().copied().collect();
+            dcs.sort_unstable();
+
+            let groups = &mut buckets[bucket];
+            let group = *group_lookup[bucket].entry(dcs.clone()).or_insert_with(|| {
+                groups.push(DcGroup { dcs, members: Vec::new() });
+                groups.len() - 1
+            });
+            groups[group].members.push((idx, pos, search.avg_skill_percentile));
+        }
+
+        Self { buckets }
+    }
+
+    /// The `k` nearest candidates to a seed, sorted by (distance, queue position)
+    /// `distance(search_idx, queue_pos)` returns `None` for searches that must be skipped
+    /// (the seed itself, already-matched searches); it must be at least
+    /// `skill_weight * |Δ percentile|` for the pruning to be exact. With `require_shared_dc`,
+    /// groups whose acceptable DC set is disjoint from the seed's are skipped whole; `distance`
+    /// must reject those searches too, so the result matches a scan of the whole queue.
+    pub fn nearest(
+        &self,
+        seed: &SearchObject,
+        k: usize,
+        skill_weight: f64,
+        require_shared_dc: bool,
+        mut distance: impl FnMut(usize, usize) -> Option<f64>,
+    ) -> Vec<(usize, f64)> {
+        let bucket_count = self.buckets.len();
+        let seed_pct = seed.avg_skill_percentile;
+        let seed_bucket = bucket_of(seed_pct, bucket_count);
+        let width = 1.0 / bucket_count as f64;
+
+        // Skill gap between the seed and the closest edge of a bucket
+        let gap = |bucket: usize| -> f64 {
+            if bucket < seed_bucket {
+                (seed_pct - (bucket + 1) as f64 * width).max(0.0)
+            } else if bucket > seed_bucket {
+                (bucket as f64 * width - seed_pct).max(0.0)
+            } else {
+                0.0
+            }
+        };
+
+        let mut found: Vec<(usize, f64, usize)> = Vec::new();
+        // The k smallest distances seen so far (max-heap: top is the current k-th nearest)
+        let mut top_k: BinaryHeap<HeapDistance> = BinaryHeap::with_capacity(k + 1);
+        let kth = |top_k: &BinaryHeap<HeapDistance>| -> Option<f64> {
+            (k > 0 && top_k.len() >= k).then(|| top_k.peek().map_or(f64::MAX, |d| d.0))
+        };
+        let mut below = seed_bucket.checked_sub(1);
+        let mut above = seed_bucket + 1;
+        let mut next = Some(seed_bucket);
+
+        while let Some(bucket) = next {
+            for group in &self.buckets[bucket] {
+                if require_shared_dc && !group.dcs.iter().any(|dc| seed.acceptable_dcs.contains(dc)) {
+                    continue;
+                }
+                for &(idx, pos, percentile) in &group.members {
+                    if let Some(bound) = kth(&top_k) {
+                        if skill_weight * (percentile - seed_pct).abs() > bound + BOUND_SLACK {
+                            continue;
+                        }
+                    }
+                    if let Some(dist) = distance(idx, pos) {
+                        found.push((idx, dist, pos));
+                        top_k.push(HeapDistance(dist));
+                        if top_k.len() > k {
+                            top_k.pop();
+                        }
+                    }
+                }
+            }
+
+            // Next bucket outward: whichever side is closer in skill
+            next = match (below, above < bucket_count) {
+                (Some(b), true) if gap(b) <= gap(above) => {
+                    below = b.checked_sub(1);
+                    Some(b)
+                }
+                (_, true) => {
+                    above += 1;
+                    Some(above - 1)
+                }
+                (Some(b), false) => {
+                    below = b.checked_sub(1);
+                    Some(b)
+                }
+                (None, false) => None,
+            };
+
+            // Stop once no remaining bucket can beat the current k-th candidate
+            if let (Some(bucket), Some(bound)) = (next, kth(&top_k)) {
+                if skill_weight * gap(bucket) > bound + BOUND_SLACK {
+                    break;
+                }
+            }
+        }
+
+        found.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap().then(a.2.cmp(&b.2)));
+        found.truncate(k);
+        found.into_iter().map(|(idx, dist, _)| (idx, dist)).collect()
+    }
+}
+
+fn bucket_of(percentile: f64, bucket_count: usize) -> usize {
+    ((percentile.max(0.0) * bucket_count as f64) as usize).min(bucket_count - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Location, Playlist};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use std::collections::HashSet;
+
+    fn random_searches(n: usize, rng: &mut StdRng) -> Vec<SearchObject> {
+        (0..n)
+            .map(|id| SearchObject {
+                id,
+                player_ids: vec![id],
+                avg_skill_percentile: rng.gen(),
+                skill_disparity: 0.0,
+                avg_location: Location::new(rng.gen_range(-60.0..60.0), rng.gen_range(-180.0..180.0)),
+                platforms: HashMap::new(),
+                input_devices: HashMap::new(),
+                acceptable_playlists: [Playlist::TEAM_DEATHMATCH].into_iter().collect(),
+                search_start_time: 0,
+                acceptable_dcs: (0..3).filter(|_| rng.gen_bool(0.5)).collect::<HashSet<usize>>(),
+                placement: false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_nearest_matches_brute_force_top_k() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let searches = random_searches(2000, &mut rng);
+        let order: Vec<usize> = (0..searches.len()).collect();
+        let index = CandidateIndex::build(&searches, &order);
+        let distance = |a: &SearchObject, b: &SearchObject| {
+            0.4 * (a.avg_skill_percentile - b.avg_skill_percentile).abs()
+                + 0.3 * a.avg_location.distance_km(&b.avg_location) / 20000.0
+        };
+
+        for seed_idx in [0, 17, 999, 1999] {
+            let seed = &searches[seed_idx];
+            let shares_dc = |s: &SearchObject| !s.acceptable_dcs.is_disjoint(&seed.acceptable_dcs);
+
+            let mut brute: Vec<(usize, f64)> = order
+                .iter()
+                .filter(|&&idx| idx != seed_idx && shares_dc(&searches[idx]))
+                .map(|&idx| (idx, distance(seed, &searches[idx])))
+                .collect();
+            brute.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+            brute.truncate(50);
+
+            let indexed = index.nearest(seed, 50, 0.4, true, |idx, _| {
+                (idx != seed_idx).then(|| distance(seed, &searches[idx]))
+            });
+            assert_eq!(indexed, brute);
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use crate::types::{BlowoutSeverity, Playlist};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::Write;
+
+/// A simulation state transition, stamped with the tick it happened on
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SimEvent {
+    pub tick: u64,
+    #[serde(flatten)]
+    pub kind: SimEventKind,
+}
+
+/// What happened (serialized with a `type` tag)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum SimEventKind {
+    /// A new account was created mid-simulation (followed by its `PlayerArrived`)
+    PlayerAcquired { player_id: usize },
+    /// A smurf abandoned their account after a losing streak and started over on a fresh one
+    SmurfNewAccount { player_id: usize },
+    /// Player came online for their first session
+    PlayerArrived { player_id: usize },
+    /// Player came back online after quitting an earlier session
+    PlayerReturned { player_id: usize, offline_ticks: u64 },
+    /// A solo player or party entered the queue
+    SearchStarted {
+        search_id: usize,
+        player_ids: Vec<usize>,
+        party_id: Option<usize>,
+    },
+    /// A waiting search moved to a wider region backoff tier
+    SearchExpanded {
+        search_id: usize,
+        tier: usize,
+        wait_time: f64,
+    },
+    /// A lobby was formed and its match started
+    MatchFormed {
+        match_id: usize,
+        playlist: Playlist,
+        data_center_id: usize,
+        teams: Vec<Vec<usize>>,
+        quality_score: f64,
+        skill_disparity: f64,
+        map: Option<String>,
+    },
+    /// Player left a lobby over its map before the match started (and went back to the menus)
+    PlayerQuitLobby { player_id: usize, map: String },
+    /// A match finished (ranked matches also give each team's rank, 0 = first)
+    MatchCompleted {
+        match_id: usize,
+        winning_team: usize,
+        blowout_severity: Option<BlowoutSeverity>,
+        placements: Vec<usize>,
+        /// Final score per team
+        #[serde(default)]
+        scores: Vec<usize>,
+    },
+    /// Player left a match before it ended (and went offline)
+    PlayerAbandoned { player_id: usize, match_id: usize },
+    /// Searching players joined a match in progress
+    MatchBackfilled {
+        match_id: usize,
+        team: usize,
+        player_ids: Vec<usize>,
+    },
+    /// Player stayed online after a match
+    PlayerContinued {
+        player_id: usize,
+        match_id: usize,
+        continue_prob: f64,
+    },
+    /// Player went offline after a match
+    PlayerQuit {
+        player_id: usize,
+        match_id: usize,
+        matches_in_session: usize,
+    },
+}
+
+impl SimEventKind {
+    /// Players this event refers to
+    pub fn player_ids(&self) -> Vec<usize> {
+        match self {
+            SimEventKind::PlayerAcquired { player_id }
+            | SimEventKind::SmurfNewAccount { player_id }
+            | SimEventKind::PlayerArrived { player_id }
+            | SimEventKind::PlayerReturned { player_id, .. }
+            | SimEventKind::PlayerContinued { player_id, .. }
+            | SimEventKind::PlayerQuit { player_id, .. }
+            | SimEventKind::PlayerAbandoned { player_id, .. }
+            | SimEventKind::PlayerQuitLobby { player_id, .. } => vec![*player_id],
+            SimEventKind::SearchStarted { player_ids, .. } | SimEventKind::MatchBackfilled { player_ids, .. } => {
+                player_ids.clone()
+            }
+            SimEventKind::MatchFormed { teams, .. } => teams.iter().flatten().copied().collect(),
+            SimEventKind::SearchExpanded { .. } | SimEventKind::MatchCompleted { .. } => Vec::new(),
+        }
+    }
+
+    /// Search this event refers to, if any
+    pub fn search_id(&self) -> Option<usize> {
+        match self {
+            SimEventKind::SearchStarted { search_id, .. } | SimEventKind::SearchExpanded { search_id, .. } => {
+                Some(*search_id)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Receives simulation events as they are emitted
+pub trait EventSink {
+    fn record(&mut self, event: &SimEvent);
+}
+
+/// Keeps the most recent `capacity` events in memory
+pub struct RingBufferSink {
+    capacity: usize,
+    events: VecDeque<SimEvent>,
+}
+
+impl RingBufferSink {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            events: VecDeque::with_capacity(capacity.min(4096)),
+        }
+    }
+
+    /// Remove and return the buffered events, oldest first
+    pub fn drain(&mut self) -> Vec<SimEvent> {
+        self.events.drain(..).collect()
+    }
+}
+
+impl EventSink for RingBufferSink {
+    fn record(&mut self, event: &SimEvent) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(event.clone());
+    }
+}
+
+/// Writes one JSON object per line
+pub struct JsonlSink<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> JsonlSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> EventSink for JsonlSink<W> {
+    fn record(&mut self, event: &SimEvent) {
+        // Sinks are fire-and-forget; a failed write must not stop the simulation
+        if serde_json::to_writer(&mut self.writer, event).is_ok() {
+            let _ = self.writer.write_all(b"\n");
+        }
+    }
+}
+
+/// Events emitted during a tick, held until the owner forwards them to its sinks
+/// Disabled by default so runs without sinks pay nothing for event construction.
+#[derive(Default)]
+pub struct EventLog {
+    pending: Option<Vec<SimEvent>>,
+}
+
+impl EventLog {
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.pending = enabled.then(Vec::new);
+    }
+
+    pub fn emit(&mut self, tick: u64, kind: impl FnOnce() -> SimEventKind) {
+        if let Some(pending) = &mut self.pending {
+            pending.push(SimEvent { tick, kind: kind() });
+        }
+    }
+
+    /// Remove and return the pending events, in emission order
+    pub fn take(&mut self) -> Vec<SimEvent> {
+        self.pending.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arrived(tick: u64, player_id: usize) -> SimEvent {
+        SimEvent {
+            tick,
+            kind: SimEventKind::PlayerArrived { player_id },
+        }
+    }
+
+    #[test]
+    fn test_ring_buffer_keeps_most_recent() {
+        let mut sink = RingBufferSink::new(3);
+        for tick in 0..5 {
+            sink.record(&arrived(tick, tick as usize));
+        }
+        let ticks: Vec<u64> = sink.drain().iter().map(|e| e.tick).collect();
+        assert_eq!(ticks, vec![2, 3, 4]);
+        assert!(sink.drain().is_empty());
+    }
+
+    #[test]
+    fn test_jsonl_round_trip() {
+        let events = vec![
+            arrived(1, 7),
+            SimEvent {
+                tick: 4,
+                kind: SimEventKind::MatchCompleted {
+                    match_id: 2,
+                    winning_team: 1,
+                    blowout_severity: Some(BlowoutSeverity::Mild),
+                    placements: Vec::new(),
+                    scores: vec![41, 75],
+                },
+            },
+        ];
+        let mut sink = JsonlSink::new(Vec::new());
+        for event in &events {
+            sink.record(event);
+        }
+        let text = String::from_utf8(sink.into_inner()).unwrap();
+        assert!(text.starts_with("{\"tick\":1,\"type\":\"PlayerArrived\",\"player_id\":7}\n"));
+        let parsed: Vec<SimEvent> = text.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(parsed, events);
+    }
+}
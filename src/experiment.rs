@@ -0,0 +1,119 @@
+use crate::simulation::Simulation;
+use crate::types::{ExperimentConfig, MatchmakingConfig, SimulationStats};
+use serde::Serialize;
+
+/// Headline metrics of one experiment run
+#[derive(Clone, Debug, Serialize)]
+pub struct SweepPoint {
+    pub parameter_value: f64,
+    pub run: usize,
+    pub seed: u64,
+    pub avg_search_time: f64,
+    pub search_time_p90: f64,
+    pub avg_delta_ping: f64,
+    pub delta_ping_p90: f64,
+    pub avg_skill_disparity: f64,
+    pub blowout_rate: f64,
+    pub total_matches: usize,
+}
+
+impl SweepPoint {
+    /// Column names, in `csv_row` order
+    pub const CSV_HEADER: &'static str = "parameter_value,run,seed,avg_search_time,search_time_p90,avg_delta_ping,delta_ping_p90,avg_skill_disparity,blowout_rate,total_matches";
+
+    pub fn from_stats(parameter_value: f64, run: usize, seed: u64, stats: &SimulationStats) -> Self {
+        Self {
+            parameter_value,
+            run,
+            seed,
+            avg_search_time: stats.avg_search_time,
+            search_time_p90: stats.search_time_p90,
+            avg_delta_ping: stats.avg_delta_ping,
+            delta_ping_p90: stats.delta_ping_p90,
+            avg_skill_disparity: stats.avg_skill_disparity,
+            blowout_rate: stats.blowout_rate,
+            total_matches: stats.total_matches,
+        }
+    }
+
+    pub fn csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{}",
+            self.parameter_value,
+            self.run,
+            self.seed,
+            self.avg_search_time,
+            self.search_time_p90,
+            self.avg_delta_ping,
+            self.delta_ping_p90,
+            self.avg_skill_disparity,
+            self.blowout_rate,
+            self.total_matches
+        )
+    }
+}
+
+/// Run every (value, run) combination of a parameter sweep
+/// Run `r` of value `i` uses seed `seed + i * runs_per_value + r`. `build` turns a config and
+/// seed into a ready-to-run simulation; `on_run` is called after each run completes.
+pub fn run_sweep(
+    base_config: &MatchmakingConfig,
+    experiment: &ExperimentConfig,
+    seed: u64,
+    build: impl Fn(MatchmakingConfig, u64) -> Simulation,
+    mut on_run: impl FnMut(&SweepPoint),
+) -> Result<Vec<SweepPoint>, String> {
+    let runs_per_value = experiment.runs_per_value.max(1);
+    let mut points = Vec::with_capacity(experiment.values.len() * runs_per_value);
+
+    for (i, &value) in experiment.values.iter().enumerate() {
+        let mut config = base_config.clone();
+        config.set_parameter(&experiment.parameter, value)?;
+
+        for run in 0..runs_per_value {
+            let run_seed = seed + (i * runs_per_value + run) as u64;
+            let mut sim = build(config.clone(), run_seed);
+            sim.run(experiment.ticks_per_run);
+
+            let point = SweepPoint::from_stats(value, run, run_seed, &sim.stats);
+            on_run(&point);
+            points.push(point);
+        }
+    }
+
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sweep_runs_every_value_and_seed() {
+        let experiment = ExperimentConfig {
+            name: String::new(),
+            description: String::new(),
+            parameter: "skill_similarity_initial".to_string(),
+            values: vec![0.05, 0.2],
+            runs_per_value: 2,
+            ticks_per_run: 5,
+        };
+        let build = |config: MatchmakingConfig, seed: u64| {
+            let mut sim = Simulation::new(config, seed);
+            sim.init_default_data_centers();
+            sim.generate_population(200, None);
+            sim
+        };
+
+        let mut completed = 0;
+        let points = run_sweep(&MatchmakingConfig::default(), &experiment, 100, build, |_| completed += 1).unwrap();
+        assert_eq!(completed, 4);
+        let seeds: Vec<u64> = points.iter().map(|p| p.seed).collect();
+        assert_eq!(seeds, vec![100, 101, 102, 103]);
+        assert_eq!(points[2].parameter_value, 0.2);
+        assert_eq!(points[0].csv_row().split(',').count(), SweepPoint::CSV_HEADER.split(',').count());
+
+        let unknown = ExperimentConfig { parameter: "nope".to_string(), ..experiment };
+        assert!(run_sweep(&MatchmakingConfig::default(), &unknown, 0, build, |_| {}).is_err());
+    }
+}
@@ -1,11 +1,30 @@
+mod balance;
+mod batch;
+mod candidates;
+mod events;
+mod experiment;
 mod matchmaker;
+mod par;
+mod rating;
+mod replay;
+mod scenario;
+mod scoring;
 mod simulation;
+mod snapshot;
+mod strategy;
 mod types;
 
-use simulation::Simulation;
+use events::{EventSink, JsonlSink, RingBufferSink, SimEvent};
+pub use simulation::Simulation;
+use snapshot::SnapshotFormat;
 use types::*;
 use wasm_bindgen::prelude::*;
 
+// Native API used by the `okqueue-sim` runner
+pub use experiment::{run_sweep, SweepPoint};
+pub use scenario::{Scenario, ScenarioFormat};
+pub use types::{ExperimentConfig, MatchmakingConfig, SimulationStats};
+
 /// Initialize panic hook for better error messages
 #[wasm_bindgen(start)]
 pub fn init() {
@@ -13,10 +32,25 @@ pub fn init() {
     console_error_panic_hook::set_once();
 }
 
+/// Forwards each event to a JavaScript callback as a JSON string
+struct JsCallbackSink {
+    callback: js_sys::Function,
+}
+
+impl EventSink for JsCallbackSink {
+    fn record(&mut self, event: &SimEvent) {
+        if let Ok(json) = serde_json::to_string(event) {
+            let _ = self.callback.call1(&JsValue::NULL, &JsValue::from_str(&json));
+        }
+    }
+}
+
 /// WASM-exposed simulation wrapper
 #[wasm_bindgen]
 pub struct SimulationEngine {
     sim: Simulation,
+    event_buffer: Option<RingBufferSink>,
+    event_callback: Option<JsCallbackSink>,
 }
 
 #[wasm_bindgen]
@@ -27,16 +61,35 @@ impl SimulationEngine {
         let config = MatchmakingConfig::default();
         let mut sim = Simulation::new(config, seed);
         sim.init_default_data_centers();
-        SimulationEngine { sim }
+        SimulationEngine::wrap(sim)
     }
 
     /// Create with custom config
     pub fn new_with_config(seed: u64, config_json: &str) -> Result<SimulationEngine, JsValue> {
-        let config: MatchmakingConfig = serde_json::from_str(config_json)
-            .map_err(|e| JsValue::from_str(&format!("Config parse error: {}", e)))?;
+        let config = parse_config(config_json, "Config")?;
         let mut sim = Simulation::new(config, seed);
         sim.init_default_data_centers();
-        Ok(SimulationEngine { sim })
+        Ok(SimulationEngine::wrap(sim))
+    }
+
+    /// Create a simulation from a scenario document ("json" or "toml"), population included
+    /// Validation errors name the offending field by path.
+    pub fn from_scenario(text: &str, format: &str) -> Result<SimulationEngine, JsValue> {
+        let format = ScenarioFormat::from_name(format).map_err(|e| JsValue::from_str(&e))?;
+        let scenario = Scenario::parse(text, format).map_err(|e| JsValue::from_str(&e))?;
+        Ok(SimulationEngine::wrap(scenario.build()))
+    }
+
+    /// Save the full simulation state as a versioned snapshot ("json" or "binary")
+    pub fn save_snapshot(&self, format: &str) -> Result<Vec<u8>, JsValue> {
+        let format = SnapshotFormat::from_name(format).map_err(|e| JsValue::from_str(&e))?;
+        snapshot::save(&self.sim, format).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Restore a simulation from a snapshot produced by `save_snapshot` (either format)
+    pub fn load_snapshot(bytes: &[u8]) -> Result<SimulationEngine, JsValue> {
+        let sim = snapshot::load(bytes).map_err(|e| JsValue::from_str(&e))?;
+        Ok(SimulationEngine::wrap(sim))
     }
 
     /// Generate player population
@@ -47,11 +100,47 @@ impl SimulationEngine {
     /// Run single tick
     pub fn tick(&mut self) {
         self.sim.tick();
+        self.dispatch_events();
     }
 
     /// Run multiple ticks
     pub fn run(&mut self, ticks: u64) {
-        self.sim.run(ticks);
+        for _ in 0..ticks {
+            self.tick();
+        }
+    }
+
+    /// Keep the most recent `capacity` events for `take_events` (0 disables the buffer)
+    pub fn set_event_buffer(&mut self, capacity: usize) {
+        self.event_buffer = (capacity > 0).then(|| RingBufferSink::new(capacity));
+        self.update_event_recording();
+    }
+
+    /// Remove and return the buffered events as a JSON array
+    pub fn take_events(&mut self) -> String {
+        let events = self.event_buffer.as_mut().map(|b| b.drain()).unwrap_or_default();
+        serde_json::to_string(&events).unwrap_or_default()
+    }
+
+    /// Remove and return the buffered events as JSON Lines
+    pub fn take_events_jsonl(&mut self) -> String {
+        let mut sink = JsonlSink::new(Vec::new());
+        for event in self.event_buffer.as_mut().map(|b| b.drain()).unwrap_or_default() {
+            sink.record(&event);
+        }
+        String::from_utf8(sink.into_inner()).unwrap_or_default()
+    }
+
+    /// Call `callback(event_json)` for every event as it is emitted
+    pub fn set_event_callback(&mut self, callback: js_sys::Function) {
+        self.event_callback = Some(JsCallbackSink { callback });
+        self.update_event_recording();
+    }
+
+    /// Stop calling the event callback
+    pub fn clear_event_callback(&mut self) {
+        self.event_callback = None;
+        self.update_event_recording();
     }
 
     /// Get current simulation state as JSON
@@ -94,12 +183,49 @@ impl SimulationEngine {
         self.sim.set_arrival_rate(rate);
     }
 
+    /// Set per-region time-of-day arrival curves from JSON (see `get_default_diurnal_arrivals`)
+    pub fn set_diurnal_arrivals(&mut self, diurnal_json: &str) -> Result<(), JsValue> {
+        let diurnal: DiurnalArrivals = serde_json::from_str(diurnal_json)
+            .map_err(|e| JsValue::from_str(&format!("Diurnal arrivals parse error: {}", e)))?;
+        let errors = diurnal.validate("diurnal_arrivals");
+        if !errors.is_empty() {
+            return Err(JsValue::from_str(&errors.join("\n")));
+        }
+        self.sim.set_diurnal_arrivals(Some(diurnal));
+        Ok(())
+    }
+
+    /// Go back to a single global arrival rate
+    pub fn clear_diurnal_arrivals(&mut self) {
+        self.sim.set_diurnal_arrivals(None);
+    }
+
+    /// Typical evening-peaking curves for NA, EU, APAC and SA as JSON
+    pub fn get_default_diurnal_arrivals() -> String {
+        serde_json::to_string(&DiurnalArrivals::typical()).unwrap_or_default()
+    }
+
+    /// Configure new-account acquisition from JSON (see `get_default_acquisition`)
+    pub fn set_acquisition(&mut self, acquisition_json: &str) -> Result<(), JsValue> {
+        let acquisition: AcquisitionConfig = serde_json::from_str(acquisition_json)
+            .map_err(|e| JsValue::from_str(&format!("Acquisition parse error: {}", e)))?;
+        let errors = acquisition.validate("acquisition");
+        if !errors.is_empty() {
+            return Err(JsValue::from_str(&errors.join("\n")));
+        }
+        self.sim.set_acquisition(acquisition);
+        Ok(())
+    }
+
+    /// Default acquisition settings (rate 0, i.e. no new accounts) as JSON
+    pub fn get_default_acquisition() -> String {
+        serde_json::to_string(&AcquisitionConfig::default()).unwrap_or_default()
+    }
+
     /// Update matchmaking config
     pub fn update_config(&mut self, config_json: &str) -> Result<(), JsValue> {
-        let config: MatchmakingConfig = serde_json::from_str(config_json)
-            .map_err(|e| JsValue::from_str(&format!("Config parse error: {}", e)))?;
-        self.sim.update_config(config);
-        Ok(())
+        let config = parse_config(config_json, "Config")?;
+        self.sim.update_config(config).map_err(|e| JsValue::from_str(&e))
     }
 
     /// Get default config as JSON
@@ -107,6 +233,33 @@ impl SimulationEngine {
         serde_json::to_string(&MatchmakingConfig::default()).unwrap_or_default()
     }
 
+    /// Get the playlist definitions (teams, match length, party cap, server cost) as JSON
+    pub fn get_playlists(&self) -> String {
+        serde_json::to_string(&self.sim.config.playlists).unwrap_or_default()
+    }
+
+    /// Select the matchmaking strategy by name (e.g. "SeedGreedy", "Fifo")
+    pub fn set_matchmaking_strategy(&mut self, name: &str) -> Result<(), JsValue> {
+        self.sim.set_matchmaking_strategy(name)
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Get the active matchmaking strategy name
+    pub fn get_matchmaking_strategy(&self) -> String {
+        self.sim.config.matchmaking_strategy.name().to_string()
+    }
+
+    /// Get available matchmaking strategy names as JSON
+    pub fn get_matchmaking_strategies() -> String {
+        let names: Vec<&str> = MatchmakingStrategyKind::ALL.iter().map(|k| k.name()).collect();
+        serde_json::to_string(&names).unwrap_or_default()
+    }
+
+    /// Get cumulative batch-optimal vs. greedy comparison as JSON
+    pub fn get_batch_comparison(&self) -> String {
+        serde_json::to_string(&self.sim.stats.batch_comparison).unwrap_or_default()
+    }
+
     /// Get search time histogram (for visualization)
     pub fn get_search_time_histogram(&self, num_bins: usize) -> String {
         let samples = &self.sim.stats.search_time_samples;
@@ -186,6 +339,7 @@ impl SimulationEngine {
                     "lat": dc.location.lat,
                     "lon": dc.location.lon,
                     "busy_servers": dc.busy_servers,
+                    "server_capacity": dc.server_capacity,
                 })
             })
             .collect();
@@ -236,6 +390,7 @@ impl SimulationEngine {
                     "leader_id": party.leader_id,
                     "size": party.size(),
                     "avg_skill": party.avg_skill,
+                    "avg_mmr": party.avg_mmr,
                     "avg_skill_percentile": party.avg_skill_percentile,
                     "skill_disparity": party.skill_disparity,
                 })
@@ -258,6 +413,7 @@ impl SimulationEngine {
                 serde_json::json!({
                     "id": id,
                     "skill": p.skill,
+                    "mmr": p.mmr,
                     "skill_percentile": p.skill_percentile,
                     "party_id": p.party_id,
                 })
@@ -404,6 +560,46 @@ impl SimulationEngine {
     }
 }
 
+impl SimulationEngine {
+    fn wrap(sim: Simulation) -> SimulationEngine {
+        SimulationEngine {
+            sim,
+            event_buffer: None,
+            event_callback: None,
+        }
+    }
+
+    fn update_event_recording(&mut self) {
+        self.sim
+            .set_event_recording(self.event_buffer.is_some() || self.event_callback.is_some());
+    }
+
+    /// Forward events from the last tick to the active sinks
+    fn dispatch_events(&mut self) {
+        let mut sinks: Vec<&mut dyn EventSink> = Vec::new();
+        if let Some(buffer) = &mut self.event_buffer {
+            sinks.push(buffer);
+        }
+        if let Some(callback) = &mut self.event_callback {
+            sinks.push(callback);
+        }
+        if !sinks.is_empty() {
+            self.sim.flush_events(&mut sinks);
+        }
+    }
+}
+
+/// Parse a matchmaking config from JSON and run the same checks as scenario files
+fn parse_config(config_json: &str, name: &str) -> Result<MatchmakingConfig, JsValue> {
+    let config: MatchmakingConfig = serde_json::from_str(config_json)
+        .map_err(|e| JsValue::from_str(&format!("{} parse error: {}", name, e)))?;
+    let errors = config.validate("config");
+    if !errors.is_empty() {
+        return Err(JsValue::from_str(&format!("Invalid {}:\n  {}", name.to_lowercase(), errors.join("\n  "))));
+    }
+    Ok(config)
+}
+
 /// Run a parameter sweep experiment
 #[wasm_bindgen]
 pub fn run_experiment(
@@ -414,49 +610,27 @@ pub fn run_experiment(
     ticks_per_run: u64,
     seed: u64,
 ) -> Result<String, JsValue> {
-    let base_config: MatchmakingConfig = serde_json::from_str(base_config_json)
-        .map_err(|e| JsValue::from_str(&format!("Config parse error: {}", e)))?;
+    let base_config = parse_config(base_config_json, "Config")?;
     
     let values: Vec<f64> = serde_json::from_str(values_json)
         .map_err(|e| JsValue::from_str(&format!("Values parse error: {}", e)))?;
 
-    let mut results = Vec::new();
-
-    for (i, &value) in values.iter().enumerate() {
-        let mut config = base_config.clone();
-        
-        // Update the specified parameter
-        match parameter {
-            "skill_similarity_initial" => config.skill_similarity_initial = value,
-            "skill_similarity_rate" => config.skill_similarity_rate = value,
-            "skill_similarity_max" => config.skill_similarity_max = value,
-            "max_skill_disparity_initial" => config.max_skill_disparity_initial = value,
-            "max_skill_disparity_rate" => config.max_skill_disparity_rate = value,
-            "delta_ping_initial" => config.delta_ping_initial = value,
-            "delta_ping_rate" => config.delta_ping_rate = value,
-            "weight_skill" => config.weight_skill = value,
-            "weight_geo" => config.weight_geo = value,
-            _ => {
-                return Err(JsValue::from_str(&format!("Unknown parameter: {}", parameter)));
-            }
-        }
-
-        let mut sim = Simulation::new(config, seed + i as u64);
+    let experiment = ExperimentConfig {
+        name: String::new(),
+        description: String::new(),
+        parameter: parameter.to_string(),
+        values,
+        runs_per_value: 1,
+        ticks_per_run,
+    };
+    let build = |config: MatchmakingConfig, run_seed: u64| {
+        let mut sim = Simulation::new(config, run_seed);
         sim.init_default_data_centers();
         sim.generate_population(population, None);
-        sim.run(ticks_per_run);
-
-        results.push(serde_json::json!({
-            "parameter_value": value,
-            "avg_search_time": sim.stats.avg_search_time,
-            "search_time_p90": sim.stats.search_time_p90,
-            "avg_delta_ping": sim.stats.avg_delta_ping,
-            "delta_ping_p90": sim.stats.delta_ping_p90,
-            "avg_skill_disparity": sim.stats.avg_skill_disparity,
-            "blowout_rate": sim.stats.blowout_rate,
-            "total_matches": sim.stats.total_matches,
-        }));
-    }
+        sim
+    };
+    let results = experiment::run_sweep(&base_config, &experiment, seed, build, |_| {})
+        .map_err(|e| JsValue::from_str(&e))?;
 
     serde_json::to_string(&results)
         .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
@@ -471,10 +645,8 @@ pub fn compare_configs(
     ticks: u64,
     seed: u64,
 ) -> Result<String, JsValue> {
-    let config_a: MatchmakingConfig = serde_json::from_str(config_a_json)
-        .map_err(|e| JsValue::from_str(&format!("Config A parse error: {}", e)))?;
-    let config_b: MatchmakingConfig = serde_json::from_str(config_b_json)
-        .map_err(|e| JsValue::from_str(&format!("Config B parse error: {}", e)))?;
+    let config_a = parse_config(config_a_json, "Config A")?;
+    let config_b = parse_config(config_b_json, "Config B")?;
 
     // Run simulation A
     let mut sim_a = Simulation::new(config_a, seed);
@@ -502,3 +674,13 @@ pub fn compare_configs(
     serde_json::to_string(&comparison)
         .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
 }
+
+/// Replay a recorded event log from its initial snapshot and report the first divergence
+#[wasm_bindgen]
+pub fn replay_event_log(snapshot_bytes: &[u8], event_log: &str) -> Result<String, JsValue> {
+    let sim = snapshot::load(snapshot_bytes).map_err(|e| JsValue::from_str(&e))?;
+    let events = replay::parse_event_log(event_log).map_err(|e| JsValue::from_str(&e))?;
+    let report = replay::replay(sim, &events);
+    serde_json::to_string(&report)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
@@ -1,15 +1,59 @@
+use crate::balance::{self, PartyEntry};
+use crate::candidates::CandidateIndex;
+use crate::par;
 use crate::types::*;
 use rand::Rng;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Seeds grown speculatively per batch (one at a time without the `parallel` feature)
+const SPECULATION_WINDOW: usize = if par::ENABLED { 64 } else { 1 };
+
+/// How candidates are ranked around a seed when building a lobby
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CandidateOrder {
+    /// Weighted distance metric (geo, skill, input, platform)
+    Distance,
+    /// Queue order: longest-waiting searches first
+    WaitTime,
+    /// Geographic distance only
+    Geographic,
+    /// Skill percentile distance only
+    Skill,
+}
+
+/// Lobby construction rules: candidate ranking plus which constraints are enforced
+#[derive(Clone, Copy, Debug)]
+pub struct LobbyPolicy {
+    pub candidate_order: CandidateOrder,
+    /// Enforce the skill similarity and disparity windows
+    pub enforce_skill: bool,
+    /// Restrict data centers to every search's acceptable (ping backoff) set
+    pub enforce_ping: bool,
+}
+
+impl LobbyPolicy {
+    /// The default seed + greedy matchmaker: weighted distance, all constraints
+    pub const SEED_GREEDY: Self = Self {
+        candidate_order: CandidateOrder::Distance,
+        enforce_skill: true,
+        enforce_ping: true,
+    };
+}
 
 /// The matchmaking engine
 pub struct Matchmaker {
     config: MatchmakingConfig,
+    policy: LobbyPolicy,
 }
 
 impl Matchmaker {
     pub fn new(config: MatchmakingConfig) -> Self {
-        Self { config }
+        Self::with_policy(config, LobbyPolicy::SEED_GREEDY)
+    }
+
+    /// Create a matchmaker that builds lobbies under a non-default policy
+    pub fn with_policy(config: MatchmakingConfig, policy: LobbyPolicy) -> Self {
+        Self { config, policy }
     }
 
     /// Calculate distance between two search objects
@@ -82,20 +126,34 @@ impl Matchmaker {
 
         // 2. Check total size
         let total_size: usize = searches.iter().map(|s| s.size()).sum();
-        if total_size > playlist.required_players() {
+        let definition = self.config.playlist(playlist);
+        let required_players = definition.required_players();
+        if total_size > required_players {
             #[cfg(feature = "debug")]
-            eprintln!("Feasibility failed: total size {} exceeds required {} for playlist {:?}", total_size, playlist.required_players(), playlist);
+            eprintln!("Feasibility failed: total size {} exceeds required {} for playlist {:?}", total_size, required_players, playlist);
             return None;
         }
+        // Parties must be splittable into the playlist's teams
+        if searches.iter().any(|s| s.size() > 1) {
+            let sizes: Vec<usize> = searches.iter().map(|s| s.size()).collect();
+            pack_parties(&sizes, definition.team_count, definition.team_size)?;
+        }
 
         // 3. Check skill similarity
         // Per whitepaper §3.3: [π_min(M), π_max(M)] ⊆ [ℓ_j(t), u_j(t)] for all j
         let pi_min = searches.iter().map(|s| s.avg_skill_percentile).fold(f64::MAX, f64::min);
         let pi_max = searches.iter().map(|s| s.avg_skill_percentile).fold(f64::MIN, f64::max);
+        let delta_pi_m = pi_max - pi_min;  // Lobby skill disparity
         
-        for search in searches {
+        // Skill windows are skipped entirely when the policy ignores skill (e.g., FIFO, ping-only)
+        let skill_checked: &[&SearchObject] = if self.policy.enforce_skill { searches } else { &[] };
+        for search in skill_checked {
             let wait_time = search.wait_time(current_time, self.config.tick_interval);
-            let f_skill = self.config.skill_similarity_backoff(wait_time);
+            let mut f_skill = self.config.skill_similarity_backoff(wait_time);
+            // Placement players' ratings are provisional, so accept a wider skill range
+            if search.placement {
+                f_skill += self.config.placement_skill_widening;
+            }
             
             // Compute acceptable range for this search: [ℓ_j(t), u_j(t)]
             let ell_j = search.avg_skill_percentile - f_skill;
@@ -113,25 +171,27 @@ impl Matchmaker {
 
         // 4. Check skill disparity
         // Per whitepaper §3.3: Δπ_M <= Δπ^max_j(t) for all j
-        let delta_pi_m = pi_max - pi_min;  // Lobby skill disparity
-        
         let max_disparity_allowed = searches
             .iter()
             .map(|s| {
                 let wait_time = s.wait_time(current_time, self.config.tick_interval);
-                self.config.skill_disparity_backoff(wait_time)
+                let widening = if s.placement { self.config.placement_disparity_widening } else { 0.0 };
+                self.config.skill_disparity_backoff(wait_time) + widening
             })
             .fold(f64::MAX, f64::min);
         
-        if delta_pi_m > max_disparity_allowed {
+        if self.policy.enforce_skill && delta_pi_m > max_disparity_allowed {
             #[cfg(feature = "debug")]
             eprintln!("Feasibility failed: skill disparity {} exceeds max allowed {} for searches {:?}", 
                 delta_pi_m, max_disparity_allowed, searches.iter().map(|s| s.id).collect::<Vec<_>>());
             return None;
         }
 
-        // 5. Find common acceptable data centers
-        let common_dcs: HashSet<usize> = searches
+        // 5. Find common acceptable data centers (any DC if ping is not enforced)
+        let common_dcs: HashSet<usize> = if !self.policy.enforce_ping {
+            data_centers.iter().map(|dc| dc.id).collect()
+        } else {
+            searches
             .iter()
             .map(|s| &s.acceptable_dcs)
             .fold(None::<HashSet<usize>>, |acc, dcs| {
@@ -140,7 +200,8 @@ impl Matchmaker {
                     Some(common) => common.intersection(dcs).copied().collect(),
                 })
             })
-            .unwrap_or_default();
+            .unwrap_or_default()
+        };
 
         if common_dcs.is_empty() {
             #[cfg(feature = "debug")]
@@ -160,10 +221,13 @@ impl Matchmaker {
             }
         }
         
-        let primary_region = region_counts
-            .iter()
-            .max_by_key(|(_, &count)| count)
-            .map(|(region, _)| *region)
+        // Ties go to the region listed first in `Region::ALL`
+        let primary_region = Region::ALL
+            .into_iter()
+            .filter_map(|region| region_counts.get(&region).map(|&count| (region, count)))
+            .rev()
+            .max_by_key(|&(_, count)| count)
+            .map(|(region, _)| region)
             .unwrap_or(Region::Other);
         
         let adjacent_regions: HashSet<Region> = primary_region.adjacent_regions().into_iter().collect();
@@ -173,15 +237,13 @@ impl Matchmaker {
         let mut adjacent_dcs: Vec<usize> = Vec::new();
         let mut other_dcs: Vec<usize> = Vec::new();
         
-        for &dc_id in &common_dcs {
-            if let Some(dc) = data_centers.iter().find(|dc| dc.id == dc_id) {
-                if dc.region == primary_region {
-                    prioritized_dcs.push(dc_id);
-                } else if adjacent_regions.contains(&dc.region) {
-                    adjacent_dcs.push(dc_id);
-                } else {
-                    other_dcs.push(dc_id);
-                }
+        for dc in data_centers.iter().filter(|dc| common_dcs.contains(&dc.id)) {
+            if dc.region == primary_region {
+                prioritized_dcs.push(dc.id);
+            } else if adjacent_regions.contains(&dc.region) {
+                adjacent_dcs.push(dc.id);
+            } else {
+                other_dcs.push(dc.id);
             }
         }
         
@@ -215,6 +277,7 @@ impl Matchmaker {
         searches: &[&SearchObject],
         players: &HashMap<usize, Player>,
         dc_id: usize,
+        playlist: Playlist,
         current_time: u64,
     ) -> f64 {
         // Ping quality (lower delta ping = higher quality)
@@ -257,26 +320,36 @@ impl Matchmaker {
             .sum::<f64>() / searches.len() as f64;
         let wait_quality = (avg_wait / 60.0).min(1.0); // Bonus for reducing long waits
 
+        // Map diversity (share of the playlist's map pool the lobby hasn't played recently)
+        let maps = &self.config.map_config;
+        let map_diversity_quality = match maps.pools.get(&playlist) {
+            Some(pool) if maps.enabled && !pool.is_empty() => {
+                let fresh: Vec<f64> = searches
+                    .iter()
+                    .flat_map(|s| &s.player_ids)
+                    .filter_map(|id| players.get(id))
+                    .map(|p| pool.iter().filter(|m| !p.recent_maps.contains(m)).count() as f64 / pool.len() as f64)
+                    .collect();
+                if fresh.is_empty() { 1.0 } else { fresh.iter().sum::<f64>() / fresh.len() as f64 }
+            }
+            _ => 1.0,
+        };
+
         self.config.quality_weight_ping * ping_quality
             + self.config.quality_weight_skill_balance * skill_balance_quality
             + self.config.quality_weight_wait_time * wait_quality
+            + self.config.quality_weight_map_diversity * map_diversity_quality
     }
 
-    /// Run one matchmaking tick
-    pub fn run_tick(
+    /// Update acceptable DCs for all searches based on current wait time
+    pub fn refresh_acceptable_dcs(
         &self,
-        searches: &mut Vec<SearchObject>,
-        players: &mut HashMap<usize, Player>,
-        data_centers: &mut [DataCenter],
-        parties: &HashMap<usize, Party>,
+        searches: &mut [SearchObject],
+        players: &HashMap<usize, Player>,
+        data_centers: &[DataCenter],
         current_time: u64,
-        rng: &mut impl Rng,
-    ) -> Vec<MatchResult> {
-        let mut results = Vec::new();
-        let mut matched_search_ids: HashSet<usize> = HashSet::new();
-
-        // Update acceptable DCs for all searches based on current wait time
-        for search in searches.iter_mut() {
+    ) {
+        par::for_each_mut(searches, |search| {
             let wait_time = search.wait_time(current_time, self.config.tick_interval);
             let mut acceptable = HashSet::new();
             
@@ -295,170 +368,418 @@ impl Matchmaker {
                 }
             }
             search.acceptable_dcs = acceptable;
-        }
+        });
+    }
 
-        // Sort searches by wait time (longest waiting = highest priority as seeds)
+    /// Search indices ordered by wait time (longest waiting = highest priority as seeds)
+    pub fn seed_order(&self, searches: &[SearchObject], current_time: u64) -> Vec<usize> {
         let mut search_order: Vec<usize> = (0..searches.len()).collect();
         search_order.sort_by(|&a, &b| {
             let wait_a = searches[a].wait_time(current_time, self.config.tick_interval);
             let wait_b = searches[b].wait_time(current_time, self.config.tick_interval);
             wait_b.partial_cmp(&wait_a).unwrap()
         });
+        search_order
+    }
+
+    /// Collect a playlist's searches (in queue order) into a candidate pool
+    /// Builds a skill/DC index when the policy's candidate ordering allows exact pruning on skill.
+    pub fn candidate_pool(&self, searches: &[SearchObject], playlist_searches: Vec<usize>) -> CandidatePool {
+        let indexable = match self.policy.candidate_order {
+            CandidateOrder::Distance => {
+                self.config.weight_geo >= 0.0
+                    && self.config.weight_input >= 0.0
+                    && self.config.weight_platform >= 0.0
+            }
+            CandidateOrder::Skill => true,
+            CandidateOrder::WaitTime | CandidateOrder::Geographic => false,
+        };
+        let index = (self.config.use_candidate_index && indexable)
+            .then(|| CandidateIndex::build(searches, &playlist_searches));
+
+        CandidatePool {
+            searches: playlist_searches,
+            index,
+        }
+    }
+
+    /// Top-K unmatched candidates around a seed, ordered by the policy's candidate ordering
+    fn lobby_candidates(
+        &self,
+        searches: &[SearchObject],
+        seed_idx: usize,
+        pool: &CandidatePool,
+        playlist: Playlist,
+        matched_search_ids: &HashSet<usize>,
+    ) -> Vec<(usize, f64)> {
+        let seed = &searches[seed_idx];
+        // Enough candidates to fill the largest lobbies (Battle Royale) on solos alone
+        let top_k = self.config.top_k_candidates.max(self.config.playlist(playlist).required_players() - 1);
 
-        // Process each playlist separately
-        for playlist in [
-            Playlist::TeamDeathmatch,
-            Playlist::SearchAndDestroy,
-            Playlist::Domination,
-            Playlist::GroundWar,
-            Playlist::FreeForAll,
-        ] {
-            let required_size = playlist.required_players();
-
-            // Get searches for this playlist
-            let playlist_searches: Vec<usize> = search_order
+        let candidate_distance = |idx: usize, queue_pos: usize| -> Option<f64> {
+            if idx == seed_idx || matched_search_ids.contains(&searches[idx].id) {
+                return None;
+            }
+            let candidate = &searches[idx];
+            // A candidate with no DC in common with the seed can never share its lobby
+            if self.policy.enforce_ping && candidate.acceptable_dcs.is_disjoint(&seed.acceptable_dcs) {
+                return None;
+            }
+            Some(match self.policy.candidate_order {
+                CandidateOrder::Distance => self.calculate_distance(seed, candidate),
+                CandidateOrder::WaitTime => queue_pos as f64,
+                CandidateOrder::Geographic => seed.avg_location.distance_km(&candidate.avg_location),
+                CandidateOrder::Skill => (seed.avg_skill_percentile - candidate.avg_skill_percentile).abs(),
+            })
+        };
+        
+        // Find candidates (sorted by the policy's ordering around the seed)
+        match &pool.index {
+            Some(index) => {
+                let skill_weight = match self.policy.candidate_order {
+                    CandidateOrder::Distance => self.config.weight_skill,
+                    _ => 1.0,
+                };
+                index.nearest(
+                    seed,
+                    top_k,
+                    skill_weight,
+                    self.policy.enforce_ping,
+                    candidate_distance,
+                )
+            }
+            None => {
+                let mut candidates: Vec<(usize, f64)> = pool
+                    .searches
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(queue_pos, &idx)| candidate_distance(idx, queue_pos).map(|dist| (idx, dist)))
+                    .collect();
+                candidates.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+                candidates.truncate(top_k);
+                candidates
+            }
+        }
+    }
+
+    /// Greedy lobby construction from a seed's ordered candidate list
+    fn grow_lobby_from(
+        &self,
+        queue: &QueueView,
+        seed_idx: usize,
+        candidates: &[(usize, f64)],
+        playlist: Playlist,
+    ) -> Option<(Vec<usize>, FeasibilityResult)> {
+        let searches = queue.searches;
+        let required_size = self.config.playlist(playlist).required_players();
+        let seed = &searches[seed_idx];
+
+        // Only a full lobby becomes a match, unless the playlist's fill policy allows an
+        // under-full start for a seed that has waited long enough
+        let seed_wait = seed.wait_time(queue.current_time, self.config.tick_interval);
+        let min_size = self.config.min_lobby_size(playlist, seed_wait);
+
+        // Greedy lobby construction
+        let mut lobby_indices = vec![seed_idx];
+        let mut lobby_size = seed.size();
+        let mut remaining_size: usize = candidates.iter().map(|&(idx, _)| searches[idx].size()).sum();
+
+        for &(cand_idx, _) in candidates {
+            if lobby_size >= required_size {
+                break;
+            }
+            // Give up once the remaining candidates can no longer make a startable lobby
+            if lobby_size + remaining_size < min_size {
+                return None;
+            }
+
+            let candidate = &searches[cand_idx];
+            remaining_size -= candidate.size();
+            if lobby_size + candidate.size() > required_size {
+                continue;
+            }
+
+            // Check feasibility
+            let lobby_searches: Vec<_> = lobby_indices
                 .iter()
-                .copied()
-                .filter(|&idx| {
-                    !matched_search_ids.contains(&searches[idx].id)
-                        && searches[idx].acceptable_playlists.contains(&playlist)
-                })
+                .map(|&i| &searches[i])
+                .chain(std::iter::once(candidate))
                 .collect();
 
-            if playlist_searches.is_empty() {
-                continue;
+            if queue.check(self, &lobby_searches, playlist).is_some() {
+                lobby_indices.push(cand_idx);
+                lobby_size += candidate.size();
             }
+        }
 
-            // Use each unmatched search as a potential seed
-            for &seed_idx in &playlist_searches {
-                if matched_search_ids.contains(&searches[seed_idx].id) {
-                    continue;
-                }
+        if lobby_size < min_size || (lobby_size < required_size && lobby_indices.len() < 2) {
+            return None;
+        }
 
-                let seed = &searches[seed_idx];
-                
-                // Find candidates (sorted by distance from seed)
-                let mut candidates: Vec<(usize, f64)> = playlist_searches
+        let lobby_searches: Vec<_> = lobby_indices
+            .iter()
+            .map(|&i| &searches[i])
+            .collect();
+        queue.check(self, &lobby_searches, playlist)
+            .map(|feasibility| (lobby_indices, feasibility))
+    }
+
+    /// Build the match result for a feasible lobby (teams are balanced separately)
+    pub fn build_match_result(
+        &self,
+        lobby_searches: &[&SearchObject],
+        feasibility: &FeasibilityResult,
+        playlist: Playlist,
+        players: &HashMap<usize, Player>,
+        current_time: u64,
+    ) -> MatchResult {
+        let quality = self.calculate_quality(
+            lobby_searches,
+            players,
+            feasibility.data_center_id,
+            playlist,
+            current_time,
+        );
+
+        // Collect all player IDs
+        let all_players: Vec<usize> = lobby_searches
+            .iter()
+            .flat_map(|s| s.player_ids.iter().copied())
+            .collect();
+
+        // Calculate average delta ping
+        let avg_delta_ping = all_players
+            .iter()
+            .filter_map(|&pid| {
+                players.get(&pid).and_then(|p| {
+                    p.dc_pings.get(&feasibility.data_center_id)
+                        .map(|ping| ping - p.best_ping)
+                })
+            })
+            .sum::<f64>() / all_players.len() as f64;
+
+        // Calculate search times
+        let search_times: Vec<f64> = lobby_searches
+            .iter()
+            .map(|s| s.wait_time(current_time, self.config.tick_interval))
+            .collect();
+
+        // Detect cross-region match (players from multiple regions)
+        let mut regions_in_match: HashSet<Region> = HashSet::new();
+        for &player_id in &all_players {
+            if let Some(player) = players.get(&player_id) {
+                regions_in_match.insert(player.region);
+            }
+        }
+        let is_cross_region = regions_in_match.len() > 1;
+
+        MatchResult {
+            player_ids: all_players,
+            teams: Vec::new(),
+            playlist,
+            data_center_id: feasibility.data_center_id,
+            quality_score: quality,
+            skill_disparity: feasibility.skill_disparity,
+            avg_delta_ping,
+            search_times,
+            is_cross_region,
+        }
+    }
+
+    /// Candidate pools for every playlist with queued searches, in definition order
+    pub fn playlist_pools(&self, searches: &[SearchObject], search_order: &[usize]) -> Vec<(Playlist, CandidatePool)> {
+        self.config
+            .playlist_names()
+            .filter_map(|playlist| {
+                let playlist_searches: Vec<usize> = search_order
                     .iter()
-                    .filter(|&&idx| {
-                        idx != seed_idx && !matched_search_ids.contains(&searches[idx].id)
-                    })
-                    .map(|&idx| {
-                        let dist = self.calculate_distance(seed, &searches[idx]);
-                        (idx, dist)
-                    })
+                    .copied()
+                    .filter(|&idx| searches[idx].acceptable_playlists.contains(&playlist))
                     .collect();
-                
-                candidates.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
-                candidates.truncate(self.config.top_k_candidates);
+                (!playlist_searches.is_empty()).then(|| (playlist, self.candidate_pool(searches, playlist_searches)))
+            })
+            .collect()
+    }
 
-                // Greedy lobby construction
-                let mut lobby_indices = vec![seed_idx];
-                let mut lobby_size = seed.size();
+    /// Seeding passes over the pools: (seeds, pools each seed may use)
+    /// `FixedOrder` fills one playlist at a time; `BestOverlap` makes a single pass in which each
+    /// seed chooses among all of its playlists.
+    pub fn seed_passes(&self, pools: &[(Playlist, CandidatePool)], search_order: &[usize]) -> Vec<(Vec<usize>, Vec<usize>)> {
+        match self.config.playlist_selection {
+            PlaylistSelection::FixedOrder => pools
+                .iter()
+                .enumerate()
+                .map(|(pool_id, (_, pool))| (pool.searches.clone(), vec![pool_id]))
+                .collect(),
+            PlaylistSelection::BestOverlap => vec![(search_order.to_vec(), (0..pools.len()).collect())],
+        }
+    }
 
-                for (cand_idx, _) in candidates {
-                    if lobby_size >= required_size {
-                        break;
+    /// Queue health per playlist: its share of queued players' preferences minus its share of
+    /// players in matches (from busy servers). Positive means the playlist is under-served.
+    /// Empty under `FixedOrder`, which ignores health.
+    pub fn playlist_health(&self, searches: &[SearchObject], data_centers: &[DataCenter]) -> HashMap<Playlist, f64> {
+        if self.config.playlist_selection == PlaylistSelection::FixedOrder {
+            return HashMap::new();
+        }
+        let mut demand: HashMap<Playlist, f64> = HashMap::new();
+        let mut total_demand = 0.0;
+        for search in searches {
+            let count = search.acceptable_playlists.len();
+            for playlist in &search.acceptable_playlists {
+                *demand.entry(*playlist).or_insert(0.0) += search.size() as f64 / count as f64;
+            }
+            if count > 0 {
+                total_demand += search.size() as f64;
+            }
+        }
+        let in_match = |playlist: Playlist| -> f64 {
+            data_centers
+                .iter()
+                .map(|dc| dc.busy_servers.get(&playlist).copied().unwrap_or(0) * self.config.playlist(playlist).required_players())
+                .sum::<usize>() as f64
+        };
+        let total_in_match: f64 = self.config.playlist_names().map(in_match).sum();
+        let share = |value: f64, total: f64| if total > 0.0 { value / total } else { 0.0 };
+
+        self.config
+            .playlist_names()
+            .map(|playlist| {
+                let preference = share(demand.get(&playlist).copied().unwrap_or(0.0), total_demand);
+                (playlist, preference - share(in_match(playlist), total_in_match))
+            })
+            .collect()
+    }
+
+    /// Grow a lobby around a seed in the best of the pools it may use
+    /// Each accepted playlist is scored by overlap (the seed plus its candidates, as a fraction
+    /// of the lobby size, capped at 1) plus `playlist_health_weight` times its queue health;
+    /// playlists are tried best first and the first lobby that forms wins. Also returns every
+    /// candidate considered, so speculative results can be checked for staleness.
+    pub fn grow_seed_lobby(
+        &self,
+        queue: &QueueView,
+        seed_idx: usize,
+        pools: &[(Playlist, CandidatePool)],
+        pool_ids: &[usize],
+        matched_search_ids: &HashSet<usize>,
+        health: &HashMap<Playlist, f64>,
+    ) -> SeedLobby {
+        let seed = &queue.searches[seed_idx];
+        let mut options: Vec<_> = pool_ids
+            .iter()
+            .map(|&pool_id| &pools[pool_id])
+            .filter(|(playlist, _)| seed.acceptable_playlists.contains(playlist))
+            .map(|(playlist, pool)| {
+                let candidates = self.lobby_candidates(queue.searches, seed_idx, pool, *playlist, matched_search_ids);
+                let available = seed.size() + candidates.iter().map(|&(idx, _)| queue.searches[idx].size()).sum::<usize>();
+                let overlap = (available as f64 / self.config.playlist(*playlist).required_players() as f64).min(1.0);
+                let score = overlap + self.config.playlist_health_weight * health.get(playlist).copied().unwrap_or(0.0);
+                (*playlist, candidates, score)
+            })
+            .collect();
+        options.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+        let lobby = options.iter().find_map(|(playlist, candidates, _)| {
+            self.grow_lobby_from(queue, seed_idx, candidates, *playlist)
+                .map(|(members, feasibility)| (*playlist, members, feasibility))
+        });
+        SeedLobby {
+            candidates: options.into_iter().flat_map(|(_, candidates, _)| candidates).map(|(idx, _)| idx).collect(),
+            lobby,
+        }
+    }
+
+    /// Run one matchmaking tick: seed + greedy lobby construction under this matchmaker's policy
+    pub fn run_tick(
+        &self,
+        searches: &mut Vec<SearchObject>,
+        players: &mut HashMap<usize, Player>,
+        data_centers: &mut [DataCenter],
+        parties: &HashMap<usize, Party>,
+        current_time: u64,
+        rng: &mut impl Rng,
+    ) -> Vec<MatchResult> {
+        let mut results = Vec::new();
+        let mut matched_search_ids: HashSet<usize> = HashSet::new();
+
+        self.refresh_acceptable_dcs(searches, players, data_centers, current_time);
+        let search_order = self.seed_order(searches, current_time);
+        let pools = self.playlist_pools(searches, &search_order);
+        let health = self.playlist_health(searches, data_centers);
+
+        for (seeds, pool_ids) in self.seed_passes(&pools, &search_order) {
+            // Use each unmatched search as a potential seed, a window of seeds at a time
+            for window in seeds.chunks(SPECULATION_WINDOW) {
+                let queue = QueueView {
+                    searches,
+                    players,
+                    data_centers,
+                    current_time,
+                };
+                // Speculatively grow every seed in the window against the state at its start
+                let speculative = par::map(window, |&seed_idx| {
+                    if matched_search_ids.contains(&searches[seed_idx].id) {
+                        return None;
                     }
+                    Some(self.grow_seed_lobby(&queue, seed_idx, &pools, &pool_ids, &matched_search_ids, &health))
+                });
 
-                    let candidate = &searches[cand_idx];
-                    if lobby_size + candidate.size() > required_size {
+                // Commit in seed order. A speculation stays exact unless an earlier commit in this
+                // window matched one of its candidates (changing its top-K) or filled a server pool
+                // (changing DC availability); those seeds are regrown against the current state.
+                let mut committed_in_window: HashSet<usize> = HashSet::new();
+                let mut server_pool_filled = false;
+                for (&seed_idx, speculation) in window.iter().zip(speculative) {
+                    let Some(speculation) = speculation else {
+                        continue;
+                    };
+                    if matched_search_ids.contains(&searches[seed_idx].id) {
                         continue;
                     }
 
-                    // Check feasibility
-                    let lobby_searches: Vec<_> = lobby_indices
-                        .iter()
-                        .map(|&i| &searches[i])
-                        .chain(std::iter::once(candidate))
-                        .collect();
-
-                    if self.check_feasibility(&lobby_searches, playlist, current_time, data_centers, players).is_some() {
-                        lobby_indices.push(cand_idx);
-                        lobby_size += candidate.size();
-                    }
-                }
+                    let stale = server_pool_filled
+                        || speculation
+                            .candidates
+                            .iter()
+                            .any(|&idx| committed_in_window.contains(&searches[idx].id));
+                    let lobby = if stale {
+                        let queue = QueueView {
+                            searches,
+                            players,
+                            data_centers,
+                            current_time,
+                        };
+                        self.grow_seed_lobby(&queue, seed_idx, &pools, &pool_ids, &matched_search_ids, &health).lobby
+                    } else {
+                        speculation.lobby
+                    };
+                    let Some((playlist, lobby_indices, feasibility)) = lobby else {
+                        continue;
+                    };
 
-                // If we have a full lobby, create the match
-                if lobby_size == required_size {
                     let lobby_searches: Vec<_> = lobby_indices
                         .iter()
                         .map(|&i| &searches[i])
                         .collect();
+                    let mut result = self.build_match_result(&lobby_searches, &feasibility, playlist, players, current_time);
 
-                    if let Some(feasibility) = self.check_feasibility(
-                        &lobby_searches,
-                        playlist,
-                        current_time,
-                        data_centers,
-                        players,
-                    ) {
-                        let quality = self.calculate_quality(
-                            &lobby_searches,
-                            players,
-                            feasibility.data_center_id,
-                            current_time,
-                        );
+                    // Create teams using skill-based balancing
+                    result.teams = self.balance_teams(&result.player_ids, players, parties, playlist, rng);
 
-                        // Collect all player IDs
-                        let all_players: Vec<usize> = lobby_searches
-                            .iter()
-                            .flat_map(|s| s.player_ids.iter().copied())
-                            .collect();
-
-                        // Calculate average delta ping
-                        let avg_delta_ping = all_players
-                            .iter()
-                            .filter_map(|&pid| {
-                                players.get(&pid).and_then(|p| {
-                                    p.dc_pings.get(&feasibility.data_center_id)
-                                        .map(|ping| ping - p.best_ping)
-                                })
-                            })
-                            .sum::<f64>() / all_players.len() as f64;
-
-                        // Calculate search times
-                        let search_times: Vec<f64> = lobby_searches
-                            .iter()
-                            .map(|s| s.wait_time(current_time, self.config.tick_interval))
-                            .collect();
-
-                        // Detect cross-region match (players from multiple regions)
-                        let mut regions_in_match: HashSet<Region> = HashSet::new();
-                        for &player_id in &all_players {
-                            if let Some(player) = players.get(&player_id) {
-                                regions_in_match.insert(player.region);
-                            }
-                        }
-                        let is_cross_region = regions_in_match.len() > 1;
-
-                        // Create teams using skill-based balancing
-                        let teams = self.balance_teams(&all_players, players, parties, playlist, rng);
-
-                        // Mark searches as matched
-                        for &idx in &lobby_indices {
-                            matched_search_ids.insert(searches[idx].id);
-                        }
-
-                        // Reserve server
-                        if let Some(dc) = data_centers.iter_mut().find(|dc| dc.id == feasibility.data_center_id) {
-                            if let Some(busy) = dc.busy_servers.get_mut(&playlist) {
-                                *busy += 1;
-                            }
-                        }
-
-                        results.push(MatchResult {
-                            player_ids: all_players,
-                            teams,
-                            playlist,
-                            data_center_id: feasibility.data_center_id,
-                            quality_score: quality,
-                            skill_disparity: feasibility.skill_disparity,
-                            avg_delta_ping,
-                            search_times,
-                            is_cross_region,
-                        });
+                    // Mark searches as matched
+                    for &idx in &lobby_indices {
+                        matched_search_ids.insert(searches[idx].id);
+                        committed_in_window.insert(searches[idx].id);
                     }
+
+                    // Reserve server
+                    reserve_server(data_centers, feasibility.data_center_id, playlist);
+                    server_pool_filled |= data_centers.iter().any(|dc| {
+                        dc.id == feasibility.data_center_id && dc.available_servers(&playlist) == 0
+                    });
+
+                    results.push(result);
                 }
             }
         }
@@ -469,8 +790,52 @@ impl Matchmaker {
         results
     }
 
+    /// Place waiting searches into in-progress matches with open slots (join in progress)
+    /// Searches are tried longest-waiting first against openings in order; a search joins the
+    /// first opening whose playlist, data center and skill window it accepts, on the team with
+    /// the most open slots that fits it whole.
+    pub fn backfill(
+        &self,
+        searches: &[SearchObject],
+        openings: &mut [BackfillOpening],
+        current_time: u64,
+    ) -> Vec<BackfillAssignment> {
+        let mut assignments = Vec::new();
+        for idx in self.seed_order(searches, current_time) {
+            let search = &searches[idx];
+            let wait_time = search.wait_time(current_time, self.config.tick_interval);
+            let mut f_skill = self.config.skill_similarity_backoff(wait_time);
+            if search.placement {
+                f_skill += self.config.placement_skill_widening;
+            }
+
+            for opening in openings.iter_mut() {
+                if !search.acceptable_playlists.contains(&opening.playlist)
+                    || (self.policy.enforce_ping && !search.acceptable_dcs.contains(&opening.data_center_id))
+                    || (self.policy.enforce_skill
+                        && (search.avg_skill_percentile - opening.avg_skill_percentile).abs() > f_skill)
+                {
+                    continue;
+                }
+                let team = (0..opening.open_slots.len())
+                    .filter(|&t| opening.open_slots[t] >= search.size())
+                    .max_by_key(|&t| (opening.open_slots[t], std::cmp::Reverse(t)));
+                if let Some(team) = team {
+                    opening.open_slots[team] -= search.size();
+                    assignments.push(BackfillAssignment {
+                        search_id: search.id,
+                        match_id: opening.match_id,
+                        team,
+                    });
+                    break;
+                }
+            }
+        }
+        assignments
+    }
+
     /// Balance teams based on skill, respecting party boundaries
-    fn balance_teams(
+    pub fn balance_teams(
         &self,
         player_ids: &[usize],
         players: &HashMap<usize, Player>,
@@ -478,228 +843,82 @@ impl Matchmaker {
         playlist: Playlist,
         _rng: &mut impl Rng,
     ) -> Vec<Vec<usize>> {
-        let team_count = playlist.team_count();
+        let definition = self.config.playlist(playlist);
+        let team_count = definition.team_count;
         
-        if team_count == player_ids.len() {
+        if definition.is_free_for_all() {
             // FFA - each player is their own team
             return player_ids.iter().map(|&id| vec![id]).collect();
         }
 
         // Group players by party_id (solo players are their own "party")
-        let mut party_groups: HashMap<Option<usize>, Vec<usize>> = HashMap::new();
+        // (kept in order of first appearance so balancing is deterministic)
+        let mut party_groups: Vec<(Option<usize>, Vec<usize>)> = Vec::new();
         for &player_id in player_ids {
             let party_id = players.get(&player_id).and_then(|p| p.party_id);
-            party_groups.entry(party_id).or_insert_with(Vec::new).push(player_id);
-        }
-
-        // Compute party aggregates and create party entries for balancing
-        let mut party_entries: Vec<(Option<usize>, Vec<usize>, f64, usize)> = Vec::new();
-        for (party_id, member_ids) in party_groups {
-            let avg_skill = if let Some(pid) = party_id {
-                // Get avg_skill from party
-                parties.get(&pid)
-                    .map(|p| p.avg_skill)
-                    .unwrap_or_else(|| {
-                        // Fallback: compute from members
-                        member_ids.iter()
-                            .filter_map(|id| players.get(id).map(|p| p.skill))
-                            .sum::<f64>() / member_ids.len() as f64
-                    })
-            } else {
-                // Solo player: use individual skill
-                member_ids.first()
-                    .and_then(|id| players.get(id).map(|p| p.skill))
-                    .unwrap_or(0.0)
-            };
-            let party_size = member_ids.len();
-            party_entries.push((party_id, member_ids, avg_skill, party_size));
-        }
-
-        // For small playlists (6v6) with exact balancing enabled, use exact partitioning
-        let required_players = playlist.required_players();
-        let is_small_playlist = required_players <= 12 && team_count == 2;
-        
-        if is_small_playlist && self.config.use_exact_team_balancing {
-            if let Some(best_teams) = self.exact_partition_teams(&party_entries, required_players) {
-                return best_teams;
-            }
-            // Fall through to snake draft if exact partitioning fails
-        }
-
-        // Snake draft: assign entire parties to teams (fallback for large playlists or if exact fails)
-        party_entries.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap());
-
-        let mut teams: Vec<Vec<usize>> = vec![Vec::new(); team_count];
-        let mut forward = true;
-        let mut team_idx = 0;
-
-        for (_, member_ids, _, _) in party_entries {
-            // Assign all members of this party to the same team
-            for &player_id in &member_ids {
-                teams[team_idx].push(player_id);
-            }
-            
-            // Move to next team in snake draft
-            if forward {
-                if team_idx == team_count - 1 {
-                    forward = false;
-                } else {
-                    team_idx += 1;
-                }
-            } else {
-                if team_idx == 0 {
-                    forward = true;
-                } else {
-                    team_idx -= 1;
-                }
+            match party_groups.iter_mut().find(|(id, _)| party_id.is_some() && *id == party_id) {
+                Some((_, members)) => members.push(player_id),
+                None => party_groups.push((party_id, vec![player_id])),
             }
         }
 
-        teams
-    }
-
-    /// Exact partitioning algorithm for small playlists (6v6)
-    /// Finds partition minimizing |sum(skills_team1) - sum(skills_team2)| while respecting party boundaries
-    fn exact_partition_teams(
-        &self,
-        party_entries: &[(Option<usize>, Vec<usize>, f64, usize)],
-        required_players: usize,
-    ) -> Option<Vec<Vec<usize>>> {
-        let target_team_size = required_players / 2;
-        
-        // Calculate total size
-        let total_size: usize = party_entries.iter().map(|(_, _, _, size)| *size).sum();
-        
-        if total_size != required_players {
-            return None; // Invalid input
-        }
-
-        // Use branch-and-bound to find best partition
-        let mut best_diff = f64::MAX;
-        let mut best_partition: Option<Vec<Vec<usize>>> = None;
-        
-        // Recursive backtracking with early termination
-        let mut team1_indices = Vec::new();
-        let mut team1_size = 0;
-        let mut team1_skill = 0.0;
-        
-        self.exact_partition_recursive(
-            party_entries,
-            target_team_size,
-            0,
-            &mut team1_indices,
-            &mut team1_size,
-            &mut team1_skill,
-            &mut best_diff,
-            &mut best_partition,
-            0,
-        );
-
-        best_partition
-    }
-
-    /// Recursive helper for exact partitioning
-    fn exact_partition_recursive(
-        &self,
-        party_entries: &[(Option<usize>, Vec<usize>, f64, usize)],
-        target_team_size: usize,
-        idx: usize,
-        team1_indices: &mut Vec<usize>,
-        team1_size: &mut usize,
-        team1_skill: &mut f64,
-        best_diff: &mut f64,
-        best_partition: &mut Option<Vec<Vec<usize>>>,
-        depth: usize,
-    ) {
-        // Timeout after reasonable depth (prevent infinite loops)
-        if depth > 1000 {
-            return;
-        }
+        // Compute party aggregates and create party entries for balancing
+        let entries: Vec<PartyEntry> = party_groups
+            .iter()
+            .map(|(party_id, member_ids)| {
+                // Balance on MMR: the matchmaker never sees true skill
+                let member_mmrs: Vec<f64> = member_ids
+                    .iter()
+                    .map(|id| players.get(id).map_or(0.0, |p| p.mmr))
+                    .collect();
+                let mmr = party_id
+                    .and_then(|pid| parties.get(&pid))
+                    .map(|p| p.avg_mmr)
+                    .unwrap_or_else(|| member_mmrs.iter().sum::<f64>() / member_mmrs.len() as f64);
+                PartyEntry { mmr, member_mmrs }
+            })
+            .collect();
 
-        // Base case: all parties assigned
-        if idx >= party_entries.len() {
-            if *team1_size == target_team_size {
-                let team2_skill: f64 = party_entries.iter()
-                    .enumerate()
-                    .filter(|(i, _)| !team1_indices.contains(i))
-                    .map(|(_, (_, _, skill, size))| skill * *size as f64)
-                    .sum();
-                
-                let diff = (*team1_skill - team2_skill).abs();
-                if diff < *best_diff {
-                    *best_diff = diff;
-                    
-                    // Build the actual team assignment
-                    let mut teams = vec![Vec::new(), Vec::new()];
-                    for (i, (_, member_ids, _, _)) in party_entries.iter().enumerate() {
-                        if team1_indices.contains(&i) {
-                            teams[0].extend_from_slice(member_ids);
-                        } else {
-                            teams[1].extend_from_slice(member_ids);
-                        }
-                    }
-                    *best_partition = Some(teams);
+        if definition.ranks_placements() {
+            // Squads are filled from whole parties, with solos topping them up; empty squads are dropped
+            let sizes: Vec<usize> = entries.iter().map(PartyEntry::size).collect();
+            if let Some(assignment) = pack_parties(&sizes, team_count, definition.team_size) {
+                let mut squads = vec![Vec::new(); team_count];
+                for ((_, member_ids), squad) in party_groups.iter().zip(assignment) {
+                    squads[squad].extend_from_slice(member_ids);
                 }
+                squads.retain(|squad| !squad.is_empty());
+                return squads;
             }
-            return;
         }
 
-        let (_, _, skill, size) = &party_entries[idx];
+        // Teams as even as the lobby allows, up to the playlist's team size if parties don't fit evenly
+        let even_size = player_ids.len().div_ceil(team_count);
+        let assignment = [even_size, definition.team_size.max(even_size), player_ids.len()]
+            .into_iter()
+            .find_map(|capacity| {
+                balance::partition(
+                    &entries,
+                    team_count,
+                    capacity,
+                    &self.config.team_balance,
+                    self.config.use_exact_team_balancing,
+                )
+            })
+            .expect("a team the size of the lobby always fits");
 
-        // Try adding this party to team 1
-        if *team1_size + size <= target_team_size {
-            team1_indices.push(idx);
-            *team1_size += size;
-            *team1_skill += skill * *size as f64;
-            
-            // Early termination: if current diff is already worse than best, skip
-            let remaining_skill: f64 = party_entries.iter()
-                .enumerate()
-                .filter(|(i, _)| *i > idx && !team1_indices.contains(i))
-                .map(|(_, (_, _, s, sz))| s * *sz as f64)
-                .sum();
-            let current_diff = (*team1_skill - remaining_skill).abs();
-            
-            if current_diff < *best_diff {
-                self.exact_partition_recursive(
-                    party_entries,
-                    target_team_size,
-                    idx + 1,
-                    team1_indices,
-                    team1_size,
-                    team1_skill,
-                    best_diff,
-                    best_partition,
-                    depth + 1,
-                );
-            }
-            
-            // Backtrack
-            team1_indices.pop();
-            *team1_size -= size;
-            *team1_skill -= skill * *size as f64;
-        }
-
-        // Try adding this party to team 2 (skip if team1 is already full)
-        if *team1_size < target_team_size {
-            self.exact_partition_recursive(
-                party_entries,
-                target_team_size,
-                idx + 1,
-                team1_indices,
-                team1_size,
-                team1_skill,
-                best_diff,
-                best_partition,
-                depth + 1,
-            );
+        let mut teams: Vec<Vec<usize>> = vec![Vec::new(); team_count];
+        for ((_, member_ids), team) in party_groups.iter().zip(assignment) {
+            teams[team].extend_from_slice(member_ids);
         }
+        teams
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::SeedableRng;
     use std::collections::HashSet;
 
     #[test]
@@ -718,7 +937,7 @@ mod tests {
             input_devices: HashMap::new(),
             acceptable_playlists: {
                 let mut s = HashSet::new();
-                s.insert(Playlist::TeamDeathmatch);
+                s.insert(Playlist::TEAM_DEATHMATCH);
                 s
             },
             search_start_time: 0,
@@ -727,6 +946,7 @@ mod tests {
                 s.insert(0);
                 s
             },
+            placement: false,
         };
         
         let search2 = SearchObject {
@@ -739,7 +959,7 @@ mod tests {
             input_devices: HashMap::new(),
             acceptable_playlists: {
                 let mut s = HashSet::new();
-                s.insert(Playlist::TeamDeathmatch);
+                s.insert(Playlist::TEAM_DEATHMATCH);
                 s
             },
             search_start_time: 0,
@@ -748,11 +968,12 @@ mod tests {
                 s.insert(0);
                 s
             },
+            placement: false,
         };
         
         let searches = vec![&search1, &search2];
-        let mut data_center = DataCenter::new(0, "Test", Location::new(0.0, 0.0), Region::Other);
-        data_center.busy_servers.insert(Playlist::TeamDeathmatch, 0);
+        let mut data_center = DataCenter::new(0, "Test", Location::new(0.0, 0.0), Region::Other, &PlaylistDefinition::defaults());
+        data_center.busy_servers.insert(Playlist::TEAM_DEATHMATCH, 0);
         let data_centers = vec![data_center];
         
         // Create test players for the searches
@@ -771,9 +992,358 @@ mod tests {
         // For search1 (0.4): [0.4 - 0.05, 0.4 + 0.05] = [0.35, 0.45]
         // For search2 (0.6): [0.6 - 0.05, 0.6 + 0.05] = [0.55, 0.65]
         // Match range [0.4, 0.6] is NOT contained in either range, so should fail
-        let result = matchmaker.check_feasibility(&searches, Playlist::TeamDeathmatch, 0, &data_centers, &players);
+        let result = matchmaker.check_feasibility(&searches, Playlist::TEAM_DEATHMATCH, 0, &data_centers, &players);
         assert!(result.is_none(), "Should fail skill similarity check");
     }
+
+    #[test]
+    fn test_placement_searches_get_wider_skill_window() {
+        let matchmaker = Matchmaker::new(MatchmakingConfig::default());
+        let search_at = |id: usize, percentile: f64, placement: bool| SearchObject {
+            id,
+            player_ids: vec![id],
+            avg_skill_percentile: percentile,
+            skill_disparity: 0.0,
+            avg_location: Location::new(0.0, 0.0),
+            platforms: HashMap::new(),
+            input_devices: HashMap::new(),
+            acceptable_playlists: [Playlist::TEAM_DEATHMATCH].into_iter().collect(),
+            search_start_time: 0,
+            acceptable_dcs: [0].into_iter().collect(),
+            placement,
+        };
+        let data_centers = vec![DataCenter::new(0, "Test", Location::new(0.0, 0.0), Region::Other, &PlaylistDefinition::defaults())];
+        let players: HashMap<usize, Player> = (1..=2)
+            .map(|id| (id, Player::new(id, Location::new(0.0, 0.0), 0.0)))
+            .collect();
+        let feasible = |a: &SearchObject, b: &SearchObject| {
+            matchmaker
+                .check_feasibility(&[a, b], Playlist::TEAM_DEATHMATCH, 0, &data_centers, &players)
+                .is_some()
+        };
+
+        // 0.15 apart: outside the default 0.05 window, inside it once widened by placement
+        assert!(!feasible(&search_at(1, 0.45, false), &search_at(2, 0.6, false)));
+        assert!(feasible(&search_at(1, 0.45, true), &search_at(2, 0.6, true)));
+        // An established player's own window is not widened
+        assert!(!feasible(&search_at(1, 0.45, false), &search_at(2, 0.6, true)));
+    }
+
+    #[test]
+    fn test_backfill_fills_open_slots_within_skill_window() {
+        let matchmaker = Matchmaker::new(MatchmakingConfig::default());
+        let search = |id: usize, size: usize, percentile: f64| SearchObject {
+            id,
+            player_ids: (0..size).map(|i| id * 10 + i).collect(),
+            avg_skill_percentile: percentile,
+            skill_disparity: 0.0,
+            avg_location: Location::new(0.0, 0.0),
+            platforms: HashMap::new(),
+            input_devices: HashMap::new(),
+            acceptable_playlists: [Playlist::TEAM_DEATHMATCH].into_iter().collect(),
+            search_start_time: id as u64,
+            acceptable_dcs: [0].into_iter().collect(),
+            placement: false,
+        };
+        let mut openings = vec![BackfillOpening {
+            match_id: 7,
+            playlist: Playlist::TEAM_DEATHMATCH,
+            data_center_id: 0,
+            open_slots: vec![1, 2],
+            avg_skill_percentile: 0.5,
+        }];
+
+        // Longest-waiting first: the pair takes team 1's two slots, the solo takes team 0's,
+        // and the search outside the skill window is left in the queue
+        let searches = vec![search(1, 2, 0.52), search(2, 1, 0.48), search(3, 1, 0.9)];
+        let assignments = matchmaker.backfill(&searches, &mut openings, 10);
+        assert_eq!(
+            assignments,
+            vec![
+                BackfillAssignment { search_id: 1, match_id: 7, team: 1 },
+                BackfillAssignment { search_id: 2, match_id: 7, team: 0 },
+            ]
+        );
+        assert_eq!(openings[0].open_slots, vec![0, 0]);
+    }
+
+    #[test]
+    fn test_underfull_start_after_wait_threshold() {
+        let config = MatchmakingConfig {
+            fill_policies: [(Playlist::TEAM_DEATHMATCH, FillPolicy { min_fill_ratio: 0.8, wait_threshold: 60.0 })]
+                .into_iter()
+                .collect(),
+            ..MatchmakingConfig::default()
+        };
+        let matchmaker = Matchmaker::new(config);
+        let mut players = HashMap::new();
+        let mut queue = Vec::new();
+        for id in 0..10 {
+            let mut player = Player::new(id, Location::new(0.0, 0.0), 0.0);
+            player.region = Region::Other;
+            player.dc_pings.insert(0, 20.0);
+            player.best_dc = Some(0);
+            player.best_ping = 20.0;
+            players.insert(id, player);
+            queue.push(SearchObject {
+                id,
+                player_ids: vec![id],
+                avg_skill_percentile: 0.5,
+                skill_disparity: 0.0,
+                avg_location: Location::new(0.0, 0.0),
+                platforms: HashMap::new(),
+                input_devices: HashMap::new(),
+                acceptable_playlists: [Playlist::TEAM_DEATHMATCH].into_iter().collect(),
+                search_start_time: 0,
+                acceptable_dcs: HashSet::new(),
+                placement: false,
+            });
+        }
+        let mut data_centers = vec![DataCenter::new(0, "Test", Location::new(0.0, 0.0), Region::Other, &PlaylistDefinition::defaults())];
+        let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+
+        // 10 of 12 players: too few after 25s, enough once the seed has waited 60s
+        let mut searches = queue.clone();
+        let early = matchmaker.run_tick(&mut searches, &mut players, &mut data_centers, &HashMap::new(), 5, &mut rng);
+        assert!(early.is_empty());
+        let mut searches = queue;
+        let late = matchmaker.run_tick(&mut searches, &mut players, &mut data_centers, &HashMap::new(), 12, &mut rng);
+        assert_eq!(late.len(), 1);
+        assert_eq!(late[0].player_ids.len(), 10);
+        assert_eq!(late[0].teams.iter().map(Vec::len).collect::<Vec<_>>(), vec![5, 5]);
+        assert!(searches.is_empty());
+    }
+
+    #[test]
+    fn test_solo_players_are_balanced_individually() {
+        let matchmaker = Matchmaker::new(MatchmakingConfig::default());
+        let players: HashMap<usize, Player> = (0..12)
+            .map(|id| (id, Player::new(id, Location::new(0.0, 0.0), id as f64 / 12.0)))
+            .collect();
+        let player_ids: Vec<usize> = (0..12).collect();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+
+        // Solos share no party (party_id None); grouping them together would put all 12 on one team
+        let teams = matchmaker.balance_teams(&player_ids, &players, &HashMap::new(), Playlist::TEAM_DEATHMATCH, &mut rng);
+        assert_eq!(teams.iter().map(Vec::len).collect::<Vec<_>>(), vec![6, 6]);
+    }
+
+    #[test]
+    fn test_best_overlap_steers_multi_playlist_searches_to_underserved_playlist() {
+        let mut players = HashMap::new();
+        let mut queue = Vec::new();
+        for id in 0..12 {
+            let mut player = Player::new(id, Location::new(0.0, 0.0), 0.0);
+            player.region = Region::Other;
+            player.dc_pings.insert(0, 20.0);
+            player.best_dc = Some(0);
+            player.best_ping = 20.0;
+            players.insert(id, player);
+            queue.push(SearchObject {
+                id,
+                player_ids: vec![id],
+                avg_skill_percentile: 0.5,
+                skill_disparity: 0.0,
+                avg_location: Location::new(0.0, 0.0),
+                platforms: HashMap::new(),
+                input_devices: HashMap::new(),
+                acceptable_playlists: [Playlist::TEAM_DEATHMATCH, Playlist::DOMINATION].into_iter().collect(),
+                search_start_time: 0,
+                acceptable_dcs: HashSet::new(),
+                placement: false,
+            });
+        }
+        // Every running match is TDM, so Domination is under-served
+        let mut data_center = DataCenter::new(0, "Test", Location::new(0.0, 0.0), Region::Other, &PlaylistDefinition::defaults());
+        data_center.busy_servers.insert(Playlist::TEAM_DEATHMATCH, 5);
+
+        let playlist_for = |playlist_selection: PlaylistSelection| {
+            let matchmaker = Matchmaker::new(MatchmakingConfig { playlist_selection, ..MatchmakingConfig::default() });
+            let mut searches = queue.clone();
+            let mut data_centers = vec![data_center.clone()];
+            let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+            let results = matchmaker.run_tick(&mut searches, &mut players.clone(), &mut data_centers, &HashMap::new(), 1, &mut rng);
+            assert_eq!(results.len(), 1);
+            results[0].playlist
+        };
+        assert_eq!(playlist_for(PlaylistSelection::FixedOrder), Playlist::TEAM_DEATHMATCH);
+        assert_eq!(playlist_for(PlaylistSelection::BestOverlap), Playlist::DOMINATION);
+    }
+
+    fn run_small_queue(use_candidate_index: bool) -> Vec<Vec<usize>> {
+        let config = MatchmakingConfig {
+            use_candidate_index,
+            top_k_candidates: 15,
+            skill_similarity_initial: 0.3,
+            max_skill_disparity_initial: 0.3,
+            ..MatchmakingConfig::default()
+        };
+        let matchmaker = Matchmaker::new(config);
+
+        let mut players = HashMap::new();
+        let mut searches = Vec::new();
+        for id in 0..60 {
+            let percentile = (id * 37 % 60) as f64 / 60.0;
+            let location = Location::new((id % 7) as f64, (id % 5) as f64);
+            let mut player = Player::new(id, location, 0.0);
+            player.region = Region::Other;
+            player.skill_percentile = percentile;
+            player.dc_pings.insert(0, 20.0);
+            player.best_dc = Some(0);
+            player.best_ping = 20.0;
+            players.insert(id, player);
+
+            searches.push(SearchObject {
+                id,
+                player_ids: vec![id],
+                avg_skill_percentile: percentile,
+                skill_disparity: 0.0,
+                avg_location: location,
+                platforms: HashMap::new(),
+                input_devices: HashMap::new(),
+                acceptable_playlists: [Playlist::TEAM_DEATHMATCH].into_iter().collect(),
+                search_start_time: (id % 4) as u64,
+                acceptable_dcs: HashSet::new(),
+                placement: false,
+            });
+        }
+        let mut data_centers = vec![DataCenter::new(0, "Test", Location::new(0.0, 0.0), Region::Other, &PlaylistDefinition::defaults())];
+        let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+
+        matchmaker
+            .run_tick(&mut searches, &mut players, &mut data_centers, &HashMap::new(), 20, &mut rng)
+            .into_iter()
+            .map(|result| result.player_ids)
+            .collect()
+    }
+
+    #[test]
+    fn test_candidate_index_matches_full_scan() {
+        let indexed = run_small_queue(true);
+        assert!(!indexed.is_empty());
+        assert_eq!(indexed, run_small_queue(false));
+    }
+
+    #[test]
+    fn test_candidate_index_and_scan_agree_on_dc_disjoint_searches() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        let searches: Vec<SearchObject> = (0..400)
+            .map(|id| {
+                let home_dc = rng.gen_range(0..4);
+                SearchObject {
+                    id,
+                    player_ids: vec![id],
+                    avg_skill_percentile: rng.gen(),
+                    skill_disparity: 0.0,
+                    avg_location: Location::new(rng.gen_range(-60.0..60.0), rng.gen_range(-180.0..180.0)),
+                    platforms: HashMap::new(),
+                    input_devices: HashMap::new(),
+                    acceptable_playlists: [Playlist::TEAM_DEATHMATCH].into_iter().collect(),
+                    search_start_time: 0,
+                    // Many searches share no DC with a given seed
+                    acceptable_dcs: (0..4).filter(|&dc| dc == home_dc || rng.gen_bool(0.2)).collect(),
+                    placement: false,
+                }
+            })
+            .collect();
+        let order: Vec<usize> = (0..searches.len()).collect();
+        let candidates = |use_candidate_index: bool, seed_idx: usize| {
+            let matchmaker = Matchmaker::new(MatchmakingConfig {
+                use_candidate_index,
+                top_k_candidates: 30,
+                ..MatchmakingConfig::default()
+            });
+            let pool = matchmaker.candidate_pool(&searches, order.clone());
+            assert_eq!(pool.index.is_some(), use_candidate_index);
+            matchmaker.lobby_candidates(&searches, seed_idx, &pool, Playlist::TEAM_DEATHMATCH, &HashSet::new())
+        };
+
+        for seed_idx in [0, 57, 203, 399] {
+            let scanned = candidates(false, seed_idx);
+            assert!(!scanned.is_empty());
+            assert_eq!(candidates(true, seed_idx), scanned);
+        }
+    }
+}
+
+/// One playlist's searches in queue order, optionally indexed for candidate search
+pub struct CandidatePool {
+    pub searches: Vec<usize>,
+    index: Option<CandidateIndex>,
+}
+
+/// A seed's lobby in its chosen playlist (if one formed), plus every candidate considered
+pub struct SeedLobby {
+    pub candidates: Vec<usize>,
+    pub lobby: Option<(Playlist, Vec<usize>, FeasibilityResult)>,
+}
+
+/// Read-only view of the queue while lobbies are being built
+pub struct QueueView<'a> {
+    pub searches: &'a [SearchObject],
+    pub players: &'a HashMap<usize, Player>,
+    pub data_centers: &'a [DataCenter],
+    pub current_time: u64,
+}
+
+impl QueueView<'_> {
+    /// Check feasibility of a candidate lobby against this queue state
+    pub fn check(
+        &self,
+        matchmaker: &Matchmaker,
+        lobby_searches: &[&SearchObject],
+        playlist: Playlist,
+    ) -> Option<FeasibilityResult> {
+        matchmaker.check_feasibility(lobby_searches, playlist, self.current_time, self.data_centers, self.players)
+    }
+}
+
+/// Mark one server busy for a new match
+pub fn reserve_server(data_centers: &mut [DataCenter], dc_id: usize, playlist: Playlist) {
+    if let Some(dc) = data_centers.iter_mut().find(|dc| dc.id == dc_id) {
+        if let Some(busy) = dc.busy_servers.get_mut(&playlist) {
+            *busy += 1;
+        }
+    }
+}
+
+/// Assign parties (by size) to `team_count` teams of at most `team_size` players: largest party
+/// first, into the fullest team it still fits. Returns each party's team, or None if they don't fit.
+pub fn pack_parties(sizes: &[usize], team_count: usize, team_size: usize) -> Option<Vec<usize>> {
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(sizes[i]));
+    // Team indices grouped by how many players they already hold
+    let mut by_load: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); team_size + 1];
+    by_load[0].extend(0..team_count);
+    let mut assignment = vec![0; sizes.len()];
+    for i in order {
+        let size = sizes[i];
+        let load = (0..=team_size.checked_sub(size)?).rev().find(|&load| !by_load[load].is_empty())?;
+        let team = by_load[load].pop_first()?;
+        by_load[load + size].insert(team);
+        assignment[i] = team;
+    }
+    Some(assignment)
+}
+
+/// An in-progress match with open slots
+#[derive(Debug)]
+pub struct BackfillOpening {
+    pub match_id: usize,
+    pub playlist: Playlist,
+    pub data_center_id: usize,
+    /// Open slots per team
+    pub open_slots: Vec<usize>,
+    /// Mean skill percentile of the players still in the match
+    pub avg_skill_percentile: f64,
+}
+
+/// A search placed into an in-progress match
+#[derive(Debug, PartialEq)]
+pub struct BackfillAssignment {
+    pub search_id: usize,
+    pub match_id: usize,
+    pub team: usize,
 }
 
 #[derive(Debug)]
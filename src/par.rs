@@ -0,0 +1,44 @@
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::cmp::Ordering;
+
+/// Whether the `parallel` feature is compiled in
+/// Every helper below preserves input order and the serial fallback runs the same closure,
+/// so a given seed produces identical results with or without the feature, at any thread count.
+pub const ENABLED: bool = cfg!(feature = "parallel");
+
+/// Map over a slice; results are returned in input order
+pub fn map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync + Send) -> Vec<R> {
+    #[cfg(feature = "parallel")]
+    {
+        items.par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        items.iter().map(f).collect()
+    }
+}
+
+/// Apply `f` to every element of a slice
+pub fn for_each_mut<T: Send>(items: &mut [T], f: impl Fn(&mut T) + Sync + Send) {
+    #[cfg(feature = "parallel")]
+    {
+        items.par_iter_mut().for_each(f);
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        items.iter_mut().for_each(f);
+    }
+}
+
+/// Sort a slice by a comparator that must be a total order (no equal elements)
+pub fn sort_unstable_by<T: Send>(items: &mut [T], compare: impl Fn(&T, &T) -> Ordering + Sync) {
+    #[cfg(feature = "parallel")]
+    {
+        items.par_sort_unstable_by(compare);
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        items.sort_unstable_by(compare);
+    }
+}
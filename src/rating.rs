@@ -0,0 +1,415 @@
+use crate::types::*;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+/// A completed match as seen by a rating model
+pub struct RatedMatch<'a> {
+    /// Teams: team index -> player IDs
+    pub teams: &'a [Vec<usize>],
+    /// Finishing rank per team (0 = first); tied teams share a rank
+    pub team_ranks: &'a [usize],
+    /// Observed performance index per player
+    pub performances: &'a HashMap<usize, f64>,
+    /// Expected performance per player, as predicted from MMR
+    pub expected_performances: &'a HashMap<usize, f64>,
+}
+
+/// A skill rating system applied after every completed match
+/// Models only ever update the observed MMR; true skill is never visible to them.
+pub trait SkillRatingModel {
+    /// Update the MMR (and any per-player rating state) of everyone in the match
+    fn update(&self, rated: &RatedMatch, players: &mut HashMap<usize, Player>);
+}
+
+/// Build the rating model selected in the config
+pub fn rating_model(config: &MatchmakingConfig) -> Box<dyn SkillRatingModel> {
+    let params = config.rating_config.clone();
+    match config.rating_system {
+        RatingSystem::PerformanceDelta => Box::new(PerformanceDeltaModel {
+            learning_rate: config.skill_learning_rate,
+        }),
+        RatingSystem::Elo => Box::new(EloModel {
+            k_factor: params.elo_k_factor,
+            gamma: config.gamma,
+        }),
+        RatingSystem::Glicko2 => Box::new(Glicko2Model {
+            params,
+            gamma: config.gamma,
+        }),
+        RatingSystem::TrueSkill => Box::new(TrueSkillModel { params }),
+    }
+}
+
+/// Score of team `a` against team `b` from their ranks (1 = win, 0.5 = tie, 0 = loss)
+fn pairwise_score(rank_a: usize, rank_b: usize) -> f64 {
+    match rank_a.cmp(&rank_b) {
+        std::cmp::Ordering::Less => 1.0,
+        std::cmp::Ordering::Equal => 0.5,
+        std::cmp::Ordering::Greater => 0.0,
+    }
+}
+
+fn team_mean<F: Fn(&Player) -> f64>(team: &[usize], players: &HashMap<usize, Player>, f: F) -> f64 {
+    let values: Vec<f64> = team.iter().filter_map(|id| players.get(id).map(&f)).collect();
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
+
+/// The original performance-vs-expectation rule
+/// Per whitepaper §3.7: s_i^+ = s_i^- + α(ŷ_i - E[Y_i])
+pub struct PerformanceDeltaModel {
+    learning_rate: f64,
+}
+
+impl SkillRatingModel for PerformanceDeltaModel {
+    fn update(&self, rated: &RatedMatch, players: &mut HashMap<usize, Player>) {
+        for &player_id in rated.teams.iter().flatten() {
+            let (Some(&observed), Some(&expected)) = (
+                rated.performances.get(&player_id),
+                rated.expected_performances.get(&player_id),
+            ) else {
+                continue;
+            };
+
+            if let Some(player) = players.get_mut(&player_id) {
+                let skill_update = self.learning_rate * (observed - expected);
+                player.mmr = (player.mmr + skill_update).clamp(-1.0, 1.0);
+                player.rating_state.rated_matches += 1;
+            }
+        }
+    }
+}
+
+/// Team Elo: every member moves by K(S - E) using the team average rating
+/// against each opposing team, with E from the same logistic as win probability
+pub struct EloModel {
+    k_factor: f64,
+    gamma: f64,
+}
+
+impl SkillRatingModel for EloModel {
+    fn update(&self, rated: &RatedMatch, players: &mut HashMap<usize, Player>) {
+        if rated.teams.len() < 2 {
+            return;
+        }
+
+        let team_ratings: Vec<f64> = rated.teams
+            .iter()
+            .map(|team| team_mean(team, players, |p| p.mmr))
+            .collect();
+
+        let opponents = (rated.teams.len() - 1) as f64;
+        for (team_idx, team) in rated.teams.iter().enumerate() {
+            let mut delta = 0.0;
+            for other_idx in 0..rated.teams.len() {
+                if other_idx == team_idx {
+                    continue;
+                }
+                let expected = 1.0
+                    / (1.0 + (-self.gamma * (team_ratings[team_idx] - team_ratings[other_idx])).exp());
+                let score = pairwise_score(rated.team_ranks[team_idx], rated.team_ranks[other_idx]);
+                delta += score - expected;
+            }
+            let delta = self.k_factor * delta / opponents;
+
+            for player_id in team {
+                if let Some(player) = players.get_mut(player_id) {
+                    player.mmr = (player.mmr + delta).clamp(-1.0, 1.0);
+                    player.rating_state.rated_matches += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Glicko-2 with each match treated as one rating period
+/// Each player is rated individually against a composite opponent per opposing team.
+/// The internal scale is μ = γ·MMR so expected scores agree with the win-probability logistic.
+pub struct Glicko2Model {
+    params: RatingConfig,
+    gamma: f64,
+}
+
+impl Glicko2Model {
+    fn g(phi: f64) -> f64 {
+        1.0 / (1.0 + 3.0 * phi * phi / (PI * PI)).sqrt()
+    }
+
+    /// New volatility via the Illinois algorithm (Glickman 2012, step 5)
+    fn new_volatility(&self, phi: f64, sigma: f64, v: f64, delta: f64) -> f64 {
+        let tau = self.params.glicko_tau;
+        let a = (sigma * sigma).ln();
+        let f = |x: f64| {
+            let ex = x.exp();
+            let denom = phi * phi + v + ex;
+            ex * (delta * delta - phi * phi - v - ex) / (2.0 * denom * denom) - (x - a) / (tau * tau)
+        };
+
+        let mut big_a = a;
+        let mut big_b = if delta * delta > phi * phi + v {
+            (delta * delta - phi * phi - v).ln()
+        } else {
+            let mut k = 1.0;
+            while f(a - k * tau) < 0.0 {
+                k += 1.0;
+            }
+            a - k * tau
+        };
+
+        let mut f_a = f(big_a);
+        let mut f_b = f(big_b);
+        let mut iterations = 0;
+        while (big_b - big_a).abs() > 1e-6 && iterations < 100 {
+            let big_c = big_a + (big_a - big_b) * f_a / (f_b - f_a);
+            let f_c = f(big_c);
+            if f_c * f_b <= 0.0 {
+                big_a = big_b;
+                f_a = f_b;
+            } else {
+                f_a /= 2.0;
+            }
+            big_b = big_c;
+            f_b = f_c;
+            iterations += 1;
+        }
+
+        (big_a / 2.0).exp()
+    }
+}
+
+impl SkillRatingModel for Glicko2Model {
+    fn update(&self, rated: &RatedMatch, players: &mut HashMap<usize, Player>) {
+        if rated.teams.len() < 2 {
+            return;
+        }
+
+        // Composite opponent per team: mean μ and RMS φ of its members
+        let scale = self.gamma.max(f64::EPSILON);
+        let composites: Vec<(f64, f64)> = rated.teams
+            .iter()
+            .map(|team| {
+                let mu = team_mean(team, players, |p| p.mmr * scale);
+                let phi = team_mean(team, players, |p| (p.rating_state.deviation * scale).powi(2)).sqrt();
+                (mu, phi)
+            })
+            .collect();
+
+        let mut updates: Vec<(usize, f64, f64, f64)> = Vec::new();
+        for (team_idx, team) in rated.teams.iter().enumerate() {
+            for &player_id in team {
+                let Some(player) = players.get(&player_id) else {
+                    continue;
+                };
+                let mu = player.mmr * scale;
+                let phi = player.rating_state.deviation * scale;
+                let sigma = player.rating_state.volatility;
+
+                let mut v_inv = 0.0;
+                let mut delta_sum = 0.0;
+                for (other_idx, &(mu_j, phi_j)) in composites.iter().enumerate() {
+                    if other_idx == team_idx {
+                        continue;
+                    }
+                    let g = Self::g(phi_j);
+                    let expected = 1.0 / (1.0 + (-g * (mu - mu_j)).exp());
+                    let score = pairwise_score(rated.team_ranks[team_idx], rated.team_ranks[other_idx]);
+                    v_inv += g * g * expected * (1.0 - expected);
+                    delta_sum += g * (score - expected);
+                }
+                if v_inv <= 0.0 {
+                    continue;
+                }
+
+                let v = 1.0 / v_inv;
+                let new_sigma = self.new_volatility(phi, sigma, v, v * delta_sum);
+                let phi_star = (phi * phi + new_sigma * new_sigma).sqrt();
+                let new_phi = 1.0 / (1.0 / (phi_star * phi_star) + 1.0 / v).sqrt();
+                let new_mu = mu + new_phi * new_phi * delta_sum;
+
+                updates.push((player_id, new_mu / scale, new_phi / scale, new_sigma));
+            }
+        }
+
+        for (player_id, mmr, deviation, volatility) in updates {
+            if let Some(player) = players.get_mut(&player_id) {
+                player.mmr = mmr.clamp(-1.0, 1.0);
+                player.rating_state.deviation = deviation.min(self.params.initial_deviation);
+                player.rating_state.volatility = volatility;
+                player.rating_state.rated_matches += 1;
+            }
+        }
+    }
+}
+
+/// TrueSkill-style Gaussian team rating (no draws)
+/// Multi-team matches are approximated by pairwise updates between adjacent finishing positions.
+pub struct TrueSkillModel {
+    params: RatingConfig,
+}
+
+/// Standard normal density
+fn normal_pdf(x: f64) -> f64 {
+    (-0.5 * x * x).exp() / (2.0 * PI).sqrt()
+}
+
+/// Standard normal CDF (Abramowitz & Stegun 7.1.26 erf approximation)
+fn normal_cdf(x: f64) -> f64 {
+    let z = x.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.3275911 * z);
+    let poly = t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - poly * (-z * z).exp();
+    if x >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+impl SkillRatingModel for TrueSkillModel {
+    fn update(&self, rated: &RatedMatch, players: &mut HashMap<usize, Player>) {
+        if rated.teams.len() < 2 {
+            return;
+        }
+
+        let beta_sq = self.params.trueskill_beta.powi(2);
+        let tau_sq = self.params.trueskill_tau.powi(2);
+
+        // Prior variance after dynamics: σ² + τ²
+        let mut variances: HashMap<usize, f64> = HashMap::new();
+        for &player_id in rated.teams.iter().flatten() {
+            if let Some(player) = players.get(&player_id) {
+                variances.insert(player_id, player.rating_state.deviation.powi(2) + tau_sq);
+            }
+        }
+
+        // Order teams by finishing rank and compare adjacent pairs
+        let mut order: Vec<usize> = (0..rated.teams.len()).collect();
+        order.sort_by_key(|&idx| rated.team_ranks[idx]);
+
+        let mut mean_deltas: HashMap<usize, f64> = HashMap::new();
+        let mut variance_factors: HashMap<usize, f64> = HashMap::new();
+        for pair in order.windows(2) {
+            let (winner, loser) = (pair[0], pair[1]);
+            if rated.team_ranks[winner] == rated.team_ranks[loser] {
+                continue;
+            }
+
+            let members = |team: usize| rated.teams[team].iter().filter(|id| variances.contains_key(id));
+            let team_mu = |team: usize| -> f64 {
+                members(team).filter_map(|id| players.get(id)).map(|p| p.mmr).sum()
+            };
+            let c_sq: f64 = members(winner)
+                .chain(members(loser))
+                .map(|id| variances[id] + beta_sq)
+                .sum();
+            if c_sq <= 0.0 {
+                continue;
+            }
+            let c = c_sq.sqrt();
+
+            let t = (team_mu(winner) - team_mu(loser)) / c;
+            let cdf = normal_cdf(t).max(1e-12);
+            let v = normal_pdf(t) / cdf;
+            let w = v * (v + t);
+
+            for (team, sign) in [(winner, 1.0), (loser, -1.0)] {
+                for &player_id in &rated.teams[team] {
+                    let Some(&var) = variances.get(&player_id) else {
+                        continue;
+                    };
+                    *mean_deltas.entry(player_id).or_insert(0.0) += sign * var / c * v;
+                    *variance_factors.entry(player_id).or_insert(1.0) *= (1.0 - var / c_sq * w).max(1e-4);
+                }
+            }
+        }
+
+        for (&player_id, &var) in &variances {
+            if let Some(player) = players.get_mut(&player_id) {
+                let mean_delta = mean_deltas.get(&player_id).copied().unwrap_or(0.0);
+                let factor = variance_factors.get(&player_id).copied().unwrap_or(1.0);
+                player.mmr = (player.mmr + mean_delta).clamp(-1.0, 1.0);
+                player.rating_state.deviation = (var * factor).sqrt();
+                player.rating_state.rated_matches += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_team_match(config: &MatchmakingConfig) -> HashMap<usize, Player> {
+        let mut players = HashMap::new();
+        for id in 0..4 {
+            let mut player = Player::new(id, Location::new(0.0, 0.0), 0.0);
+            player.rating_state = RatingState::initial(&config.rating_config);
+            players.insert(id, player);
+        }
+        players
+    }
+
+    fn run_model(system: RatingSystem) -> HashMap<usize, Player> {
+        let config = MatchmakingConfig {
+            rating_system: system,
+            ..MatchmakingConfig::default()
+        };
+        let mut players = two_team_match(&config);
+        let teams = vec![vec![0, 1], vec![2, 3]];
+        let ranks = vec![0, 1];
+        let performances: HashMap<usize, f64> = (0..4).map(|id| (id, if id < 2 { 0.7 } else { 0.3 })).collect();
+        let expected: HashMap<usize, f64> = (0..4).map(|id| (id, 0.5)).collect();
+
+        rating_model(&config).update(
+            &RatedMatch {
+                teams: &teams,
+                team_ranks: &ranks,
+                performances: &performances,
+                expected_performances: &expected,
+            },
+            &mut players,
+        );
+        players
+    }
+
+    #[test]
+    fn test_winners_gain_and_losers_drop_for_every_system() {
+        for system in [
+            RatingSystem::PerformanceDelta,
+            RatingSystem::Elo,
+            RatingSystem::Glicko2,
+            RatingSystem::TrueSkill,
+        ] {
+            let players = run_model(system);
+            assert!(players[&0].mmr > 0.0, "{:?} should raise the winner", system);
+            assert!(players[&2].mmr < 0.0, "{:?} should lower the loser", system);
+            assert_eq!(players[&0].rating_state.rated_matches, 1);
+        }
+    }
+
+    #[test]
+    fn test_uncertainty_shrinks_after_a_match() {
+        let initial = RatingConfig::default().initial_deviation;
+        for system in [RatingSystem::Glicko2, RatingSystem::TrueSkill] {
+            let players = run_model(system);
+            for player in players.values() {
+                assert!(
+                    player.rating_state.deviation < initial,
+                    "{:?} deviation should shrink: {}",
+                    system,
+                    player.rating_state.deviation
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_normal_cdf_matches_known_values() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-7);
+        assert!((normal_cdf(1.96) - 0.975).abs() < 1e-3);
+        assert!((normal_cdf(-1.96) - 0.025).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,222 @@
+use crate::events::{RingBufferSink, SimEvent};
+use crate::simulation::Simulation;
+use crate::types::{Player, SearchObject};
+use serde::Serialize;
+use serde_json::Value;
+
+/// A field that differs between the recorded and the replayed event
+#[derive(Clone, Debug, Serialize)]
+pub struct FieldDiff {
+    /// JSON path inside the event (e.g. `teams[1][3]`); empty when a whole event is missing
+    pub path: String,
+    pub expected: Value,
+    pub actual: Value,
+}
+
+/// First point where the replay departs from the recorded log
+#[derive(Clone, Debug, Serialize)]
+pub struct Divergence {
+    /// Index of the event in the recorded log
+    pub event_index: usize,
+    pub tick: u64,
+    /// Recorded event (None if the replay produced extra events)
+    pub expected: Option<SimEvent>,
+    /// Replayed event (None if the replay ended before the log)
+    pub actual: Option<SimEvent>,
+    pub field_diffs: Vec<FieldDiff>,
+    /// Replay state at the end of the divergent tick for the players either event refers to
+    pub players: Vec<Player>,
+    /// Replay state of the searches involved that are still queued
+    pub searches: Vec<SearchObject>,
+}
+
+/// Outcome of replaying a recorded event log
+#[derive(Clone, Debug, Serialize)]
+pub struct ReplayReport {
+    pub ticks_replayed: u64,
+    /// Recorded events that matched before the divergence (all of them if there was none)
+    pub events_matched: usize,
+    pub divergence: Option<Divergence>,
+}
+
+/// Re-run `sim` (the snapshot the log was recorded from) through the log's last tick,
+/// comparing every emitted event with the recorded one
+pub fn replay(mut sim: Simulation, recorded: &[SimEvent]) -> ReplayReport {
+    let start_time = sim.current_time;
+    let last_tick = recorded.last().map_or(start_time, |e| e.tick + 1);
+    let mut buffer = RingBufferSink::new(usize::MAX);
+    let mut matched = 0;
+
+    sim.set_event_recording(true);
+    while sim.current_time < last_tick {
+        sim.tick();
+        sim.flush_events(&mut [&mut buffer]);
+        for actual in buffer.drain() {
+            let expected = recorded.get(matched);
+            if expected != Some(&actual) {
+                return report(&sim, start_time, matched, expected.cloned(), Some(actual));
+            }
+            matched += 1;
+        }
+    }
+
+    let missing = recorded.get(matched).cloned();
+    if missing.is_some() {
+        return report(&sim, start_time, matched, missing, None);
+    }
+    ReplayReport {
+        ticks_replayed: sim.current_time - start_time,
+        events_matched: matched,
+        divergence: None,
+    }
+}
+
+fn report(
+    sim: &Simulation,
+    start_time: u64,
+    event_index: usize,
+    expected: Option<SimEvent>,
+    actual: Option<SimEvent>,
+) -> ReplayReport {
+    let events = || expected.iter().chain(actual.iter());
+
+    let mut player_ids: Vec<usize> = events().flat_map(|e| e.kind.player_ids()).collect();
+    player_ids.sort_unstable();
+    player_ids.dedup();
+    let search_ids: Vec<usize> = events().filter_map(|e| e.kind.search_id()).collect();
+
+    let mut field_diffs = Vec::new();
+    diff_values(
+        "",
+        &serde_json::to_value(&expected).unwrap_or(Value::Null),
+        &serde_json::to_value(&actual).unwrap_or(Value::Null),
+        &mut field_diffs,
+    );
+
+    let divergence = Divergence {
+        event_index,
+        tick: events().map(|e| e.tick).min().unwrap_or(sim.current_time),
+        players: player_ids.iter().filter_map(|id| sim.players.get(id).cloned()).collect(),
+        searches: sim
+            .searches
+            .iter()
+            .filter(|s| search_ids.contains(&s.id) || s.player_ids.iter().any(|id| player_ids.contains(id)))
+            .cloned()
+            .collect(),
+        expected,
+        actual,
+        field_diffs,
+    };
+    ReplayReport {
+        ticks_replayed: sim.current_time - start_time,
+        events_matched: event_index,
+        divergence: Some(divergence),
+    }
+}
+
+/// Collect the leaf paths where two JSON values differ
+fn diff_values(path: &str, expected: &Value, actual: &Value, out: &mut Vec<FieldDiff>) {
+    match (expected, actual) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, a_value) in a {
+                let child = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                diff_values(&child, a_value, b.get(key).unwrap_or(&Value::Null), out);
+            }
+            for (key, b_value) in b.iter().filter(|(key, _)| !a.contains_key(*key)) {
+                let child = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                diff_values(&child, &Value::Null, b_value, out);
+            }
+        }
+        (Value::Array(a), Value::Array(b)) if a.len() == b.len() => {
+            for (i, (a_value, b_value)) in a.iter().zip(b).enumerate() {
+                diff_values(&format!("{}[{}]", path, i), a_value, b_value, out);
+            }
+        }
+        _ if expected != actual => out.push(FieldDiff {
+            path: path.to_string(),
+            expected: expected.clone(),
+            actual: actual.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// Parse an event log written as JSON Lines (blank lines are ignored) or as a JSON array
+pub fn parse_event_log(text: &str) -> Result<Vec<SimEvent>, String> {
+    if text.trim_start().starts_with('[') {
+        return serde_json::from_str(text).map_err(|e| format!("Event log parse error: {}", e));
+    }
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| format!("Event log parse error on line {}: {}", i + 1, e))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::SimEventKind;
+    use crate::snapshot::{self, SnapshotFormat};
+    use crate::types::MatchmakingConfig;
+
+    const WARMUP_TICKS: u64 = 150;
+
+    /// Snapshot of a warmed-up simulation plus the events of its next 60 ticks
+    fn recorded_run() -> (Vec<u8>, Vec<SimEvent>) {
+        let mut sim = Simulation::new(MatchmakingConfig::default(), 5);
+        sim.init_default_data_centers();
+        sim.generate_population(1500, None);
+        sim.set_arrival_rate(100.0);
+        sim.run(WARMUP_TICKS);
+
+        let initial = snapshot::save(&sim, SnapshotFormat::Binary).unwrap();
+        let mut log = RingBufferSink::new(usize::MAX);
+        sim.set_event_recording(true);
+        for _ in 0..60 {
+            sim.tick();
+            sim.flush_events(&mut [&mut log]);
+        }
+        (initial, log.drain())
+    }
+
+    #[test]
+    fn test_replay_of_unchanged_engine_has_no_divergence() {
+        let (initial, events) = recorded_run();
+        let report = replay(snapshot::load(&initial).unwrap(), &events);
+        assert!(report.divergence.is_none());
+        assert_eq!(report.events_matched, events.len());
+        // The replay stops after the last tick that emitted a recorded event
+        assert_eq!(report.ticks_replayed, events.last().unwrap().tick + 1 - WARMUP_TICKS);
+    }
+
+    #[test]
+    fn test_replay_reports_first_divergent_event() {
+        let (initial, events) = recorded_run();
+        let mut sim = snapshot::load(&initial).unwrap();
+        // A behavior change in the matchmaker: tighter skill windows
+        sim.config.skill_similarity_initial /= 4.0;
+        sim.config.skill_similarity_max /= 4.0;
+
+        let report = replay(sim, &events);
+        let divergence = report.divergence.expect("tighter skill windows should change the run");
+        assert_eq!(&events[..divergence.event_index], &events[..report.events_matched]);
+        assert_eq!(divergence.expected.as_ref(), events.get(divergence.event_index));
+        assert!(!divergence.field_diffs.is_empty());
+
+        let involved = divergence.expected.iter().chain(divergence.actual.iter())
+            .any(|e| !e.kind.player_ids().is_empty());
+        assert_eq!(involved, !divergence.players.is_empty());
+    }
+
+    #[test]
+    fn test_parse_event_log_formats() {
+        let event = SimEvent { tick: 3, kind: SimEventKind::PlayerArrived { player_id: 9 } };
+        let line = serde_json::to_string(&event).unwrap();
+        assert_eq!(parse_event_log(&format!("{}\n\n{}\n", line, line)).unwrap(), vec![event.clone(), event.clone()]);
+        assert_eq!(parse_event_log(&format!("[{}]", line)).unwrap(), vec![event]);
+        assert!(parse_event_log("{}\nnot json").unwrap_err().contains("line 1"));
+    }
+}
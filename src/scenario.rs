@@ -0,0 +1,376 @@
+use crate::simulation::Simulation;
+use crate::types::{AcquisitionConfig, ArrivalSchedule, DataCenter, DiurnalArrivals, ExperimentConfig, Location, MatchmakingConfig, Playlist, Region};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Scenario document encoding
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScenarioFormat {
+    Json,
+    Toml,
+}
+
+impl ScenarioFormat {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Ok(ScenarioFormat::Json),
+            "toml" => Ok(ScenarioFormat::Toml),
+            _ => Err(format!("Unknown scenario format: {} (expected \"json\" or \"toml\")", name)),
+        }
+    }
+}
+
+/// A data center in a scenario file
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DataCenterSpec {
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub region: Region,
+    /// Servers per playlist; playlists left out get `DataCenter::default_capacity` from their definition
+    #[serde(default)]
+    pub capacity: HashMap<Playlist, usize>,
+}
+
+/// A population centroid: players are scattered around (lat, lon) in proportion to `weight`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PopulationRegion {
+    #[serde(default)]
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub weight: f64,
+}
+
+/// A self-contained simulation setup: data centers, population, arrivals and matchmaking config
+/// Fields missing from a scenario file take their default values; omitted `data_centers`
+/// and `population_mix` mean the built-in defaults.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Scenario {
+    pub name: String,
+    pub description: String,
+    pub seed: u64,
+    /// Initial player population
+    pub population: usize,
+    /// Simulation length for a single run (ticks)
+    pub ticks: u64,
+    /// Expected player arrivals per tick
+    pub arrival_rate: f64,
+    /// Time-varying arrival rate (overrides `arrival_rate` from its first step on)
+    pub arrival_schedule: Option<ArrivalSchedule>,
+    /// Per-region time-of-day arrival curves
+    pub diurnal_arrivals: Option<DiurnalArrivals>,
+    /// New accounts joining during the run (off unless `rate` or `schedule` is set)
+    pub acquisition: AcquisitionConfig,
+    pub data_centers: Option<Vec<DataCenterSpec>>,
+    pub population_mix: Option<Vec<PopulationRegion>>,
+    pub config: MatchmakingConfig,
+    /// Parameter sweep to run instead of a single simulation
+    pub experiment: Option<ExperimentConfig>,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            description: String::new(),
+            seed: 42,
+            population: 10000,
+            ticks: 500,
+            arrival_rate: 10.0,
+            arrival_schedule: None,
+            diurnal_arrivals: None,
+            acquisition: AcquisitionConfig::default(),
+            data_centers: None,
+            population_mix: None,
+            config: MatchmakingConfig::default(),
+            experiment: None,
+        }
+    }
+}
+
+impl Scenario {
+    /// Parse and validate a scenario document
+    /// Errors name the offending field by path (e.g. `data_centers[2].lat`).
+    pub fn parse(text: &str, format: ScenarioFormat) -> Result<Self, String> {
+        let scenario: Scenario = match format {
+            ScenarioFormat::Json => {
+                let mut de = serde_json::Deserializer::from_str(text);
+                serde_path_to_error::deserialize(&mut de).map_err(|e| parse_error(e.path(), e.inner()))?
+            }
+            ScenarioFormat::Toml => {
+                let de = toml::Deserializer::new(text);
+                serde_path_to_error::deserialize(de).map_err(|e| parse_error(e.path(), e.inner().message()))?
+            }
+        };
+
+        let errors = scenario.validate();
+        if errors.is_empty() {
+            Ok(scenario)
+        } else {
+            Err(format!("Invalid scenario:\n  {}", errors.join("\n  ")))
+        }
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        Self::parse(text, ScenarioFormat::Json)
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        Self::parse(text, ScenarioFormat::Toml)
+    }
+
+    /// Semantic checks, one `path: message` entry per problem
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        let mut check = |ok: bool, path: String, message: &str| {
+            if !ok {
+                errors.push(format!("{}: {}", path, message));
+            }
+        };
+
+        check(non_negative(self.arrival_rate), "arrival_rate".into(), "must be a non-negative number");
+
+        if let Some(dcs) = &self.data_centers {
+            check(!dcs.is_empty(), "data_centers".into(), "must list at least one data center");
+            for (i, dc) in dcs.iter().enumerate() {
+                let path = format!("data_centers[{}]", i);
+                check(!dc.name.trim().is_empty(), format!("{}.name", path), "must not be empty");
+                check(
+                    dcs[..i].iter().all(|other| other.name != dc.name),
+                    format!("{}.name", path),
+                    "duplicates an earlier data center",
+                );
+                check(valid_lat(dc.lat), format!("{}.lat", path), "must be between -90 and 90");
+                check(valid_lon(dc.lon), format!("{}.lon", path), "must be between -180 and 180");
+                let mut capacity_playlists: Vec<&Playlist> = dc.capacity.keys().collect();
+                capacity_playlists.sort_unstable();
+                for playlist in capacity_playlists {
+                    check(
+                        self.config.playlists.iter().any(|definition| definition.name == *playlist),
+                        format!("{}.capacity.{}", path, playlist),
+                        "playlist is not defined",
+                    );
+                }
+            }
+        }
+
+        if let Some(mix) = &self.population_mix {
+            check(!mix.is_empty(), "population_mix".into(), "must list at least one region");
+            for (i, region) in mix.iter().enumerate() {
+                let path = format!("population_mix[{}]", i);
+                check(valid_lat(region.lat), format!("{}.lat", path), "must be between -90 and 90");
+                check(valid_lon(region.lon), format!("{}.lon", path), "must be between -180 and 180");
+                check(non_negative(region.weight), format!("{}.weight", path), "must be a non-negative number");
+            }
+            check(
+                mix.is_empty() || mix.iter().map(|r| r.weight).sum::<f64>() > 0.0,
+                "population_mix".into(),
+                "weights must not all be zero",
+            );
+        }
+
+        if let Some(experiment) = &self.experiment {
+            check(
+                MatchmakingConfig::SWEEP_PARAMETERS.contains(&experiment.parameter.as_str()),
+                "experiment.parameter".into(),
+                &format!("must be one of {}", MatchmakingConfig::SWEEP_PARAMETERS.join(", ")),
+            );
+            check(!experiment.values.is_empty(), "experiment.values".into(), "must not be empty");
+        }
+
+        if let Some(schedule) = &self.arrival_schedule {
+            errors.extend(schedule.validate("arrival_schedule"));
+        }
+        if let Some(diurnal) = &self.diurnal_arrivals {
+            errors.extend(diurnal.validate("diurnal_arrivals"));
+        }
+        errors.extend(self.acquisition.validate("acquisition"));
+        errors.extend(self.config.validate("config"));
+
+        errors
+    }
+
+    /// Create the simulation this scenario describes, using `config` and `seed` in place of its own
+    pub fn build_with(&self, config: MatchmakingConfig, seed: u64) -> Simulation {
+        let mut sim = Simulation::new(config, seed);
+
+        match &self.data_centers {
+            Some(specs) => {
+                for spec in specs {
+                    let mut dc = DataCenter::new(
+                        sim.data_centers.len(),
+                        &spec.name,
+                        Location::new(spec.lat, spec.lon),
+                        spec.region,
+                        &sim.config.playlists,
+                    );
+                    dc.server_capacity.extend(&spec.capacity);
+                    sim.add_data_center(dc);
+                }
+            }
+            None => sim.init_default_data_centers(),
+        }
+
+        // Weights are normalized so the mix need not sum to 1
+        let region_weights = self.population_mix.as_ref().map(|mix| {
+            let total: f64 = mix.iter().map(|r| r.weight).sum();
+            mix.iter()
+                .map(|r| (Location::new(r.lat, r.lon), r.weight / total))
+                .collect()
+        });
+        sim.generate_population(self.population, region_weights);
+
+        sim.set_arrival_rate(self.arrival_rate);
+        sim.set_arrival_schedule(self.arrival_schedule.clone());
+        sim.set_diurnal_arrivals(self.diurnal_arrivals.clone());
+        sim.set_acquisition(self.acquisition.clone());
+        sim
+    }
+
+    /// Create the simulation this scenario describes
+    pub fn build(&self) -> Simulation {
+        self.build_with(self.config.clone(), self.seed)
+    }
+}
+
+fn parse_error(path: &serde_path_to_error::Path, message: impl std::fmt::Display) -> String {
+    // The path is "." when the error is not inside any field (e.g. a syntax error)
+    let path = path.to_string();
+    if path == "." {
+        format!("Scenario parse error: {}", message)
+    } else {
+        format!("Scenario parse error at {}: {}", path, message)
+    }
+}
+
+fn non_negative(value: f64) -> bool {
+    value.is_finite() && value >= 0.0
+}
+
+fn valid_lat(lat: f64) -> bool {
+    (-90.0..=90.0).contains(&lat)
+}
+
+fn valid_lon(lon: f64) -> bool {
+    (-180.0..=180.0).contains(&lon)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT_SCENARIO: &str = include_str!("../examples/scenarios/default.toml");
+
+    #[test]
+    fn test_partial_scenario_uses_defaults() {
+        let scenario = Scenario::from_json(r#"{"seed": 7, "population": 300, "config": {"max_ping": 150.0}}"#).unwrap();
+        assert_eq!(scenario.seed, 7);
+        assert_eq!(scenario.ticks, Scenario::default().ticks);
+        assert_eq!(scenario.config.max_ping, 150.0);
+        assert_eq!(scenario.config.weight_skill, MatchmakingConfig::default().weight_skill);
+
+        let mut a = scenario.build();
+        let mut b = scenario.build();
+        a.run(20);
+        b.run(20);
+        assert_eq!(a.players.len(), 300);
+        assert_eq!(a.stats.total_matches, b.stats.total_matches);
+
+        assert!(Scenario::from_json(r#"{"population": "many"}"#).is_err());
+    }
+
+    #[test]
+    fn test_default_scenario_file_matches_built_in_setup() {
+        let scenario = Scenario::from_toml(DEFAULT_SCENARIO).unwrap();
+        let from_file = scenario.build();
+        let built_in = Scenario {
+            data_centers: None,
+            population_mix: None,
+            ..scenario.clone()
+        }
+        .build();
+
+        assert_eq!(from_file.data_centers.len(), built_in.data_centers.len());
+        for (a, b) in from_file.data_centers.iter().zip(&built_in.data_centers) {
+            assert_eq!((&a.name, a.region, a.location.lat, a.location.lon), (&b.name, b.region, b.location.lat, b.location.lon));
+            assert_eq!(a.server_capacity, b.server_capacity);
+        }
+        for id in 0..scenario.population {
+            let (a, b) = (&from_file.players[&id], &built_in.players[&id]);
+            assert_eq!((a.location.lat, a.location.lon, a.region), (b.location.lat, b.location.lon, b.region));
+        }
+    }
+
+    #[test]
+    fn test_custom_playlists_need_map_pools_only_with_maps_enabled() {
+        let playlists = "[[config.playlists]]\nname = \"Gunfight\"\nteam_count = 2\nteam_size = 2\npreference_prob = 1.0\n";
+        let scenario = Scenario::from_toml(playlists).unwrap();
+        assert_eq!(scenario.config.playlists.len(), 1);
+
+        // Enabled maps fall back to the default pools, which name playlists this scenario lacks
+        let err = Scenario::from_toml(&format!("{}[config.map_config]\nenabled = true\n", playlists)).unwrap_err();
+        assert!(err.contains("config.map_config.pools.TeamDeathmatch: playlist is not defined"), "{}", err);
+    }
+
+    #[test]
+    fn test_validation_errors_name_field_paths() {
+        let err = Scenario::from_toml("[[data_centers]]\nname = \"A\"\nlat = 10.0\nlon = \"east\"\nregion = \"Europe\"\n").unwrap_err();
+        assert!(err.contains("data_centers[0].lon"), "{}", err);
+
+        let err = Scenario::from_json(r#"{"config": {"max_ping": "high"}}"#).unwrap_err();
+        assert!(err.contains("config.max_ping"), "{}", err);
+
+        let err = Scenario::from_json(r#"{"popluation": 5}"#).unwrap_err();
+        assert!(err.contains("popluation"), "{}", err);
+
+        let err = Scenario::from_json(
+            r#"{
+                "data_centers": [
+                    {"name": "A", "lat": 95.0, "lon": 0.0, "region": "Europe", "capacity": {"GroundWar": 4}},
+                    {"name": "A", "lat": 0.0, "lon": 0.0, "region": "Europe"}
+                ],
+                "arrival_schedule": {"steps": [{"start_tick": 10, "rate": 5.0}, {"start_tick": 10, "rate": -1.0}]},
+                "population_mix": [{"lat": 0.0, "lon": 0.0, "weight": 0.0}]
+            }"#,
+        )
+        .unwrap_err();
+        for path in [
+            "data_centers[0].lat",
+            "data_centers[1].name",
+            "arrival_schedule.steps[1].start_tick",
+            "arrival_schedule.steps[1].rate",
+            "population_mix:",
+        ] {
+            assert!(err.contains(path), "missing {} in {}", path, err);
+        }
+    }
+
+    #[test]
+    fn test_arrival_schedule_drives_arrivals() {
+        let schedule = ArrivalSchedule {
+            steps: vec![
+                crate::types::ArrivalStep { start_tick: 0, rate: 0.0 },
+                crate::types::ArrivalStep { start_tick: 5, rate: 50.0 },
+            ],
+            period: 10,
+        };
+        assert_eq!(schedule.rate_at(3), Some(0.0));
+        assert_eq!(schedule.rate_at(17), Some(50.0));
+
+        let scenario = Scenario {
+            population: 500,
+            arrival_rate: 50.0,
+            arrival_schedule: Some(schedule),
+            ..Default::default()
+        };
+        let mut sim = scenario.build();
+        sim.run(5);
+        assert_eq!(sim.stats.players_searching + sim.stats.players_in_match + sim.stats.players_in_lobby, 0);
+        sim.run(5);
+        assert!(sim.stats.players_searching + sim.stats.players_in_match > 0);
+    }
+}
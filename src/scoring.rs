@@ -0,0 +1,372 @@
+use crate::types::{PlayerScore, ScoreFormat, Scoreline, ScoringRules};
+use rand::Rng;
+use std::collections::HashMap;
+
+/// Who played a match and how well they played it
+pub struct Lineup<'a> {
+    /// Player IDs per team
+    pub teams: &'a [Vec<usize>],
+    /// Team strength this match: team skill plus the mean form noise of its players
+    pub team_forms: Vec<f64>,
+    /// Player strength this match: true skill plus form noise
+    pub player_forms: HashMap<usize, f64>,
+}
+
+/// Share of points each team wins: softmax of `dominance` × team strength
+pub fn point_shares(rules: &ScoringRules, strengths: &[f64]) -> Vec<f64> {
+    let strongest = strengths.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let weights: Vec<f64> = strengths.iter().map(|&s| (rules.dominance * (s - strongest)).exp()).collect();
+    let total: f64 = weights.iter().sum();
+    weights.iter().map(|w| w / total).collect()
+}
+
+/// Seconds between points; under-full lobbies score more slowly
+fn seconds_per_point(rules: &ScoringRules, fill_ratio: f64) -> f64 {
+    rules.seconds_per_point / fill_ratio.clamp(0.1, 1.0)
+}
+
+/// Expected score per team, scoring with their point shares until the leader reaches the
+/// score limit or `time_limit` seconds pass
+pub fn expected_scores(rules: &ScoringRules, team_skills: &[f64], time_limit: f64, fill_ratio: f64) -> Vec<f64> {
+    let shares = point_shares(rules, team_skills);
+    let leader = shares.iter().copied().fold(0.0, f64::max);
+    let points = (time_limit / seconds_per_point(rules, fill_ratio)).min(rules.score_limit as f64 / leader);
+    shares.iter().map(|share| share * points).collect()
+}
+
+/// Generate the scoreline of a finished match
+/// Points are played out until a team reaches the score limit or the mercy lead, or until
+/// `time_limit` seconds pass, with the winner playing at least as well as the best other team (so upsets are close).
+/// The team totals are then handed out best-first along `finish_order` (team indices, winner
+/// first), so the scoreline agrees with the result the outcome model decided; a level score at
+/// full time goes to the winner in overtime. Kills, deaths and objective points go to players
+/// by form.
+pub fn generate(
+    rules: &ScoringRules,
+    lineup: &Lineup,
+    time_limit: f64,
+    fill_ratio: f64,
+    finish_order: &[usize],
+    rng: &mut impl Rng,
+) -> Scoreline {
+    let team_count = lineup.teams.len();
+    // An upset means the winners played at least as well as the best of the rest
+    let mut strengths = lineup.team_forms.clone();
+    if let Some(&winner) = finish_order.first() {
+        let best_other = strengths
+            .iter()
+            .enumerate()
+            .filter(|&(team, _)| team != winner)
+            .map(|(_, &strength)| strength)
+            .fold(f64::NEG_INFINITY, f64::max);
+        strengths[winner] = strengths[winner].max(best_other);
+    }
+    let shares = point_shares(rules, &strengths);
+    let spp = seconds_per_point(rules, fill_ratio);
+    let max_points = (time_limit / spp) as usize;
+
+    let mut scores = vec![0usize; team_count];
+    let mut played = 0;
+    let (mut reached_limit, mut mercy) = (false, false);
+    while played < max_points && !reached_limit && !mercy {
+        let team = pick(&shares, rng);
+        scores[team] += 1;
+        played += 1;
+        reached_limit = scores[team] >= rules.score_limit;
+        mercy = rules.mercy_lead.is_some_and(|lead| lead_over_rest(&scores, team) >= lead);
+    }
+    let length_seconds = if reached_limit || mercy { played as f64 * spp } else { time_limit };
+
+    scores.sort_unstable_by(|a, b| b.cmp(a));
+    let mut team_scores = vec![0; team_count];
+    for (&team, &score) in finish_order.iter().zip(&scores) {
+        team_scores[team] = score;
+    }
+    if let [winner, runner_up, ..] = *finish_order {
+        if team_scores[winner] == team_scores[runner_up] {
+            team_scores[winner] += 1;
+        }
+    }
+
+    let mut credits = Credits::new(rules, lineup);
+    match rules.format {
+        ScoreFormat::Kills => {
+            for (team, &score) in team_scores.iter().enumerate() {
+                for _ in 0..score {
+                    credits.kill_in(team, rng);
+                }
+            }
+        }
+        ScoreFormat::Rounds => {
+            for (team, &score) in team_scores.iter().enumerate() {
+                for _ in 0..score {
+                    // The round's losers are wiped out; the winners lose some of their own
+                    let losers = lineup.teams.iter().enumerate().filter(|&(other, _)| other != team);
+                    for &victim in losers.flat_map(|(_, members)| members) {
+                        credits.kill(team, victim, rng);
+                    }
+                    for _ in 0..rng.gen_range(0..lineup.teams[team].len().max(1)) {
+                        if let Some(killer_team) = credits.opponent(team, rng) {
+                            let victim = credits.pick_victim(team, rng);
+                            credits.kill(killer_team, victim, rng);
+                        }
+                    }
+                    credits.objective(team, rng);
+                }
+            }
+        }
+        ScoreFormat::Objective => {
+            for (team, &score) in team_scores.iter().enumerate() {
+                for _ in 0..score {
+                    credits.objective(team, rng);
+                }
+            }
+            let kills = (length_seconds / 60.0 * rules.kills_per_minute * fill_ratio.min(1.0)).round() as usize;
+            for _ in 0..kills {
+                credits.kill_in(pick(&shares, rng), rng);
+            }
+        }
+    }
+
+    Scoreline {
+        team_scores,
+        players: credits.players,
+        length_seconds,
+        reached_limit,
+        mercy: mercy && !reached_limit,
+    }
+}
+
+/// How far `team` is ahead of the best other team (0 if it is not ahead)
+fn lead_over_rest(scores: &[usize], team: usize) -> usize {
+    let best_other = scores
+        .iter()
+        .enumerate()
+        .filter(|&(other, _)| other != team)
+        .map(|(_, &score)| score)
+        .max()
+        .unwrap_or(0);
+    scores[team].saturating_sub(best_other)
+}
+
+/// Scoreline of a placement match: each squad scores its eliminations, and everyone outside
+/// the winning squad was eliminated once
+pub fn from_eliminations(teams: &[Vec<usize>], ranks: &[usize], eliminations: &HashMap<usize, usize>, length_seconds: f64) -> Scoreline {
+    let mut players = HashMap::new();
+    let team_scores = teams
+        .iter()
+        .zip(ranks)
+        .map(|(team, &rank)| {
+            let mut score = 0;
+            for &player_id in team {
+                let kills = eliminations.get(&player_id).copied().unwrap_or(0);
+                score += kills;
+                players.insert(player_id, PlayerScore { kills, deaths: (rank > 0) as usize, objective: 0 });
+            }
+            score
+        })
+        .collect();
+    Scoreline {
+        team_scores,
+        players,
+        length_seconds,
+        reached_limit: false,
+        mercy: false,
+    }
+}
+
+/// Performance index (0-1) from a player's line
+/// Their share of the duels they were in, smoothed towards even ((kills + 1) / (kills + deaths
+/// + 2)), blended at `objective_weight` with their objective points against the lobby average.
+pub fn performance(rules: &ScoringRules, scoreline: &Scoreline, player_id: usize) -> f64 {
+    let line = scoreline.players.get(&player_id).copied().unwrap_or_default();
+    let duels = (line.kills as f64 + 1.0) / ((line.kills + line.deaths) as f64 + 2.0);
+    let objective_total: usize = scoreline.players.values().map(|p| p.objective).sum();
+    let avg_objective = objective_total as f64 / scoreline.players.len().max(1) as f64;
+    let objective = if avg_objective > 0.0 {
+        line.objective as f64 / (line.objective as f64 + avg_objective)
+    } else {
+        0.5
+    };
+    (1.0 - rules.objective_weight) * duels + rules.objective_weight * objective
+}
+
+/// Index drawn in proportion to non-negative weights
+fn pick(weights: &[f64], rng: &mut impl Rng) -> usize {
+    let mut roll = rng.gen::<f64>() * weights.iter().sum::<f64>();
+    weights
+        .iter()
+        .position(|&w| {
+            roll -= w;
+            roll < 0.0
+        })
+        .unwrap_or(weights.len() - 1)
+}
+
+/// Hands out kills, deaths and objective points to players by form
+struct Credits<'a> {
+    teams: &'a [Vec<usize>],
+    /// Per team: each member's odds of getting a kill or objective point
+    kill_weights: Vec<Vec<f64>>,
+    /// Per team: each member's odds of being the one who dies
+    death_weights: Vec<Vec<f64>>,
+    players: HashMap<usize, PlayerScore>,
+}
+
+impl<'a> Credits<'a> {
+    fn new(rules: &ScoringRules, lineup: &Lineup<'a>) -> Self {
+        let weights = |sign: f64| -> Vec<Vec<f64>> {
+            lineup
+                .teams
+                .iter()
+                .map(|team| {
+                    team.iter()
+                        .map(|id| (sign * rules.dominance * lineup.player_forms.get(id).copied().unwrap_or(0.0)).exp())
+                        .collect()
+                })
+                .collect()
+        };
+        Self {
+            teams: lineup.teams,
+            kill_weights: weights(1.0),
+            death_weights: weights(-1.0),
+            players: lineup.teams.iter().flatten().map(|&id| (id, PlayerScore::default())).collect(),
+        }
+    }
+
+    /// Another team with players left, weighted by how easily its players die
+    fn opponent(&self, team: usize, rng: &mut impl Rng) -> Option<usize> {
+        let weights: Vec<f64> = self
+            .death_weights
+            .iter()
+            .enumerate()
+            .map(|(other, weights)| if other == team { 0.0 } else { weights.iter().sum() })
+            .collect();
+        weights.iter().any(|&w| w > 0.0).then(|| pick(&weights, rng))
+    }
+
+    fn pick_victim(&self, team: usize, rng: &mut impl Rng) -> usize {
+        self.teams[team][pick(&self.death_weights[team], rng)]
+    }
+
+    /// A member of `team` kills a member of an opposing team
+    fn kill_in(&mut self, team: usize, rng: &mut impl Rng) {
+        if let Some(victim_team) = self.opponent(team, rng) {
+            let victim = self.pick_victim(victim_team, rng);
+            self.kill(team, victim, rng);
+        }
+    }
+
+    /// A member of `killer_team` kills `victim`
+    fn kill(&mut self, killer_team: usize, victim: usize, rng: &mut impl Rng) {
+        if self.teams[killer_team].is_empty() {
+            return;
+        }
+        let killer = self.teams[killer_team][pick(&self.kill_weights[killer_team], rng)];
+        self.players.entry(killer).or_default().kills += 1;
+        self.players.entry(victim).or_default().deaths += 1;
+    }
+
+    /// A member of `team` scores an objective point
+    fn objective(&mut self, team: usize, rng: &mut impl Rng) {
+        if self.teams[team].is_empty() {
+            return;
+        }
+        let scorer = self.teams[team][pick(&self.kill_weights[team], rng)];
+        self.players.entry(scorer).or_default().objective += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn lineup(teams: &[Vec<usize>], team_forms: Vec<f64>) -> Lineup<'_> {
+        let player_forms = teams
+            .iter()
+            .zip(&team_forms)
+            .flat_map(|(team, &form)| team.iter().map(move |&id| (id, form)))
+            .collect();
+        Lineup { teams, team_forms, player_forms }
+    }
+
+    #[test]
+    fn test_kill_scorelines_agree_with_winner_and_lines() {
+        let rules = ScoringRules::default();
+        let teams = vec![(0..6).collect::<Vec<_>>(), (6..12).collect()];
+        let mut rng = StdRng::seed_from_u64(5);
+        for (forms, winner) in [(vec![0.5, -0.5], 0), (vec![0.5, -0.5], 1), (vec![0.0, 0.0], 1)] {
+            let lineup = lineup(&teams, forms);
+            let scoreline = generate(&rules, &lineup, 600.0, 1.0, &[winner, 1 - winner], &mut rng);
+            assert!(scoreline.team_scores[winner] > scoreline.team_scores[1 - winner]);
+            for (team, members) in teams.iter().enumerate() {
+                let kills: usize = members.iter().map(|id| scoreline.players[id].kills).sum();
+                let deaths: usize = teams[1 - team].iter().map(|id| scoreline.players[id].deaths).sum();
+                assert_eq!(kills, scoreline.team_scores[team]);
+                assert_eq!(deaths, kills);
+            }
+        }
+
+        // A stomp reaches the score limit early with a wide margin
+        let stomp = generate(&rules, &lineup(&teams, vec![1.0, -1.0]), 600.0, 1.0, &[0, 1], &mut rng);
+        assert!(stomp.reached_limit && stomp.length_seconds < 400.0);
+        assert_eq!(stomp.team_scores[0], rules.score_limit);
+        assert!(stomp.margin(0) > 0.5);
+        let expected = expected_scores(&rules, &[1.0, -1.0], 600.0, 1.0);
+        assert!((expected[0] - rules.score_limit as f64).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_mercy_rule_ends_stomps_early() {
+        let teams = vec![(0..6).collect::<Vec<_>>(), (6..12).collect()];
+        let stomp = lineup(&teams, vec![0.8, -0.8]);
+        let mut rng = StdRng::seed_from_u64(2);
+        let rules = ScoringRules::default();
+        let full = generate(&rules, &stomp, 600.0, 1.0, &[0, 1], &mut rng);
+        let mercy_rules = ScoringRules { mercy_lead: Some(30), ..rules };
+        let mercy = generate(&mercy_rules, &stomp, 600.0, 1.0, &[0, 1], &mut rng);
+        assert!(full.reached_limit && !full.mercy);
+        assert!(mercy.mercy && !mercy.reached_limit);
+        assert_eq!(mercy.team_scores[0] - mercy.team_scores[1], 30);
+        assert!(mercy.length_seconds < full.length_seconds);
+
+        // A close match never builds the lead and runs to the score limit or the clock
+        let close = generate(&mercy_rules, &lineup(&teams, vec![0.0, 0.0]), 600.0, 1.0, &[1, 0], &mut rng);
+        assert!(!close.mercy);
+        assert!(close.length_seconds > mercy.length_seconds);
+    }
+
+    #[test]
+    fn test_rounds_wipe_out_round_losers() {
+        let rules = ScoringRules { format: ScoreFormat::Rounds, score_limit: 6, seconds_per_point: 75.0, ..ScoringRules::default() };
+        let teams = vec![(0..6).collect::<Vec<_>>(), (6..12).collect()];
+        let mut rng = StdRng::seed_from_u64(9);
+        let scoreline = generate(&rules, &lineup(&teams, vec![0.2, 0.0]), 10_000.0, 1.0, &[0, 1], &mut rng);
+        assert_eq!(scoreline.team_scores[0], 6);
+        assert!(scoreline.reached_limit);
+        for (team, members) in teams.iter().enumerate() {
+            let rounds_lost = scoreline.team_scores[1 - team];
+            assert!(members.iter().all(|id| scoreline.players[id].deaths >= rounds_lost));
+            let plants: usize = members.iter().map(|id| scoreline.players[id].objective).sum();
+            assert_eq!(plants, scoreline.team_scores[team]);
+        }
+    }
+
+    #[test]
+    fn test_performance_rewards_kills_and_objectives() {
+        let rules = ScoringRules { objective_weight: 0.5, ..ScoringRules::default() };
+        let scoreline = Scoreline {
+            players: HashMap::from([
+                (0, PlayerScore { kills: 20, deaths: 5, objective: 30 }),
+                (1, PlayerScore { kills: 5, deaths: 20, objective: 10 }),
+            ]),
+            ..Scoreline::default()
+        };
+        assert!(performance(&rules, &scoreline, 0) > 0.65);
+        assert!(performance(&rules, &scoreline, 1) < 0.35);
+        let even = Scoreline::default();
+        assert!((performance(&rules, &even, 7) - 0.5).abs() < 1e-12);
+    }
+}
@@ -1,10 +1,26 @@
-use crate::matchmaker::{MatchResult, Matchmaker};
+use crate::events::{EventLog, EventSink, SimEventKind};
+use crate::matchmaker::{BackfillOpening, MatchResult};
+use crate::par;
+use crate::rating::{rating_model, RatedMatch};
+use crate::scoring::{self, Lineup};
+use crate::strategy::{backfill_matchmaker, matchmaking_strategy};
 use crate::types::*;
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
+/// Players generated per RNG stream during population generation
+const POPULATION_CHUNK_SIZE: usize = 1024;
+
+/// Seed for the population chunk starting at `chunk_start` (SplitMix64 finalizer)
+fn chunk_seed(seed: u64, chunk_start: usize) -> u64 {
+    let mut z = seed ^ (chunk_start as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
 /// Main simulation state and controller
 #[derive(Serialize, Deserialize)]
 pub struct Simulation {
@@ -33,6 +49,16 @@ pub struct Simulation {
     rng_seed: u64,
     /// Arrival rate (players per tick)
     arrival_rate: f64,
+    /// Time-varying arrival rate; overrides `arrival_rate` from its first step on
+    arrival_schedule: Option<ArrivalSchedule>,
+    /// Per-region time-of-day arrival curves (None = one global arrival cap)
+    diurnal_arrivals: Option<DiurnalArrivals>,
+    /// New-account acquisition process
+    acquisition: AcquisitionConfig,
+    /// Region centroids and weights the population was generated from (reused for new accounts)
+    population_mix: Vec<(Location, f64)>,
+    /// Next map rotation position per playlist
+    map_rotation: HashMap<Playlist, usize>,
     /// Number of matches since last percentile update
     matches_since_percentile_update: usize,
     /// Session tracking: total matches played across completed sessions
@@ -48,6 +74,9 @@ pub struct Simulation {
     /// Diagnostic: track logit values and experience vectors (for debugging)
     logit_samples: Vec<f64>,
     experience_samples: Vec<(f64, f64, f64, f64, f64)>, // (delta_ping, search_time, blowout_rate, win_rate, performance)
+    /// Events emitted since the last flush (not part of snapshots)
+    #[serde(skip)]
+    events: EventLog,
 }
 
 impl Simulation {
@@ -71,6 +100,11 @@ impl Simulation {
             parties: HashMap::new(),
             rng_seed: seed,
             arrival_rate: 10.0,
+            arrival_schedule: None,
+            diurnal_arrivals: None,
+            acquisition: AcquisitionConfig::default(),
+            population_mix: Vec::new(),
+            map_rotation: HashMap::new(),
             matches_since_percentile_update: 0,
             total_matches_in_sessions: 0,
             session_continues: HashMap::new(),
@@ -79,6 +113,7 @@ impl Simulation {
             continue_prob_samples: Vec::new(),
             logit_samples: Vec::new(),
             experience_samples: Vec::new(),
+            events: EventLog::default(),
         }
     }
 
@@ -132,112 +167,56 @@ impl Simulation {
             ("South-America", Location::new(-23.0, -46.0), Region::SouthAmerica),
         ];
 
-        for (i, (name, location, region)) in dcs.into_iter().enumerate() {
-            self.data_centers.push(DataCenter::new(i, name, location, region));
+        for (name, location, region) in dcs {
+            self.add_data_center(DataCenter::new(self.data_centers.len(), name, location, region, &self.config.playlists));
         }
     }
 
-    /// Generate a population of players
-    pub fn generate_population(&mut self, count: usize, region_weights: Option<Vec<(Location, f64)>>) {
-        let mut rng = StdRng::seed_from_u64(self.rng_seed);
+    /// Add a data center (its id must be its index); call before generating the population
+    pub fn add_data_center(&mut self, dc: DataCenter) {
+        debug_assert_eq!(dc.id, self.data_centers.len());
+        self.data_centers.push(dc);
+    }
 
-        let regions = region_weights.unwrap_or_else(|| vec![
+    /// Population centroids and weights used when `generate_population` gets none
+    pub fn default_region_weights() -> Vec<(Location, f64)> {
+        vec![
             (Location::new(39.0, -95.0), 0.35),   // NA
             (Location::new(50.0, 10.0), 0.30),    // EU
             (Location::new(35.0, 105.0), 0.20),   // Asia
             (Location::new(-25.0, 135.0), 0.08), // Australia
             (Location::new(-15.0, -55.0), 0.07), // SA
-        ]);
-
-        for _ in 0..count {
-            // Select region based on weights
-            let r: f64 = rng.gen();
-            let mut cumulative = 0.0;
-            let mut region_loc = regions[0].0;
-            for (loc, weight) in &regions {
-                cumulative += weight;
-                if r < cumulative {
-                    region_loc = *loc;
-                    break;
-                }
-            }
-
-            // Add some randomness to location within region
-            let location = Location::new(
-                region_loc.lat + rng.gen_range(-10.0..10.0),
-                region_loc.lon + rng.gen_range(-15.0..15.0),
-            );
-
-            // Determine region from location
-            let region = Self::region_from_location(location);
-
-            // Generate skill using a normal-ish distribution
-            let skill = self.generate_skill(&mut rng);
-
-            let mut player = Player::new(self.next_player_id, location, skill);
-            player.region = region;
-            self.next_player_id += 1;
-
-            // Randomize platform and input
-            player.platform = match rng.gen_range(0..3) {
-                0 => Platform::PC,
-                1 => Platform::PlayStation,
-                _ => Platform::Xbox,
-            };
-
-            player.input_device = if player.platform == Platform::PC {
-                if rng.gen_bool(0.7) {
-                    InputDevice::MouseKeyboard
-                } else {
-                    InputDevice::Controller
-                }
-            } else {
-                if rng.gen_bool(0.9) {
-                    InputDevice::Controller
-                } else {
-                    InputDevice::MouseKeyboard
-                }
-            };
-
-            // Calculate pings to all DCs
-            for dc in &self.data_centers {
-                let base_distance = location.distance_km(&dc.location);
-                // Ping model: ~1ms per 100km + base latency + jitter
-                let base_ping = base_distance / 100.0 + 15.0;
-                let jitter = rng.gen_range(-5.0..10.0);
-                let ping = (base_ping + jitter).max(10.0);
-                player.dc_pings.insert(dc.id, ping);
-            }
-
-            // Find best DC
-            if let Some((&best_dc, &best_ping)) = player.dc_pings.iter()
-                .min_by(|a, b| a.1.partial_cmp(b.1).unwrap())
-            {
-                player.best_dc = Some(best_dc);
-                player.best_ping = best_ping;
-            }
+        ]
+    }
 
-            // Set preferred playlists
-            player.preferred_playlists.clear();
-            player.preferred_playlists.insert(Playlist::TeamDeathmatch);
-            if rng.gen_bool(0.4) {
-                player.preferred_playlists.insert(Playlist::Domination);
-            }
-            if rng.gen_bool(0.2) {
-                player.preferred_playlists.insert(Playlist::SearchAndDestroy);
-            }
-            if rng.gen_bool(0.15) {
-                player.preferred_playlists.insert(Playlist::GroundWar);
-            }
-            if rng.gen_bool(0.1) {
-                player.preferred_playlists.insert(Playlist::FreeForAll);
-            }
+    /// Players in id order (ids are allocated sequentially and never reused)
+    fn players_in_order(&self) -> impl Iterator<Item = &Player> {
+        (0..self.next_player_id).filter_map(|id| self.players.get(&id))
+    }
 
-            // Start offline
-            player.state = PlayerState::Offline;
+    /// Generate a population of players
+    /// Players are generated in fixed-size chunks, each with its own RNG seeded from the
+    /// simulation seed and the chunk's first player id, so the result is identical whether
+    /// chunks run serially or in parallel.
+    pub fn generate_population(&mut self, count: usize, region_weights: Option<Vec<(Location, f64)>>) {
+        let regions = region_weights.unwrap_or_else(Self::default_region_weights);
 
+        let first_id = self.next_player_id;
+        let chunk_starts: Vec<usize> = (first_id..first_id + count)
+            .step_by(POPULATION_CHUNK_SIZE)
+            .collect();
+        let chunks = par::map(&chunk_starts, |&chunk_start| {
+            let mut rng = StdRng::seed_from_u64(chunk_seed(self.rng_seed, chunk_start));
+            let chunk_end = (chunk_start + POPULATION_CHUNK_SIZE).min(first_id + count);
+            (chunk_start..chunk_end)
+                .map(|id| self.generate_player(id, &regions, &mut rng))
+                .collect::<Vec<_>>()
+        });
+        for player in chunks.into_iter().flatten() {
             self.players.insert(player.id, player);
         }
+        self.next_player_id += count;
+        self.population_mix = regions;
 
         // Calculate skill percentiles
         self.update_skill_percentiles();
@@ -256,7 +235,7 @@ impl Simulation {
             // Re-seed RNG so party generation is stable given the same seed
             let mut rng = StdRng::seed_from_u64(self.rng_seed.wrapping_add(1));
 
-            let mut player_ids: Vec<usize> = self.players.keys().copied().collect();
+            let mut player_ids: Vec<usize> = self.players_in_order().map(|p| p.id).collect();
             player_ids.shuffle(&mut rng);
 
             let total_players = player_ids.len();
@@ -308,6 +287,118 @@ impl Simulation {
         }
     }
 
+    /// Generate one offline player with the given id
+    fn generate_player(&self, id: usize, regions: &[(Location, f64)], rng: &mut impl Rng) -> Player {
+        // Select region based on weights
+        let r: f64 = rng.gen();
+        let mut cumulative = 0.0;
+        let mut region_loc = regions[0].0;
+        for (loc, weight) in regions {
+            cumulative += weight;
+            if r < cumulative {
+                region_loc = *loc;
+                break;
+            }
+        }
+
+        // Add some randomness to location within region
+        let location = Location::new(
+            region_loc.lat + rng.gen_range(-10.0..10.0),
+            region_loc.lon + rng.gen_range(-15.0..15.0),
+        );
+
+        // Determine region from location
+        let region = Self::region_from_location(location);
+
+        // Generate skill using a normal-ish distribution
+        let skill = self.generate_skill(rng);
+
+        let mut player = Player::new(id, location, skill);
+        player.region = region;
+        if self.config.mmr_initial_noise_std > 0.0 {
+            // MMR starts as a noisy estimate of true skill
+            let noise = self.sample_normal(rng) * self.config.mmr_initial_noise_std;
+            player.mmr = (skill + noise).clamp(-1.0, 1.0);
+        }
+        player.rating_state = RatingState::initial(&self.config.rating_config);
+
+        // Randomize platform and input
+        player.platform = match rng.gen_range(0..3) {
+            0 => Platform::PC,
+            1 => Platform::PlayStation,
+            _ => Platform::Xbox,
+        };
+
+        player.input_device = if player.platform == Platform::PC {
+            if rng.gen_bool(0.7) {
+                InputDevice::MouseKeyboard
+            } else {
+                InputDevice::Controller
+            }
+        } else {
+            if rng.gen_bool(0.9) {
+                InputDevice::Controller
+            } else {
+                InputDevice::MouseKeyboard
+            }
+        };
+
+        // Calculate pings to all DCs
+        for dc in &self.data_centers {
+            let base_distance = location.distance_km(&dc.location);
+            // Ping model: ~1ms per 100km + base latency + jitter
+            let base_ping = base_distance / 100.0 + 15.0;
+            let jitter = rng.gen_range(-5.0..10.0);
+            let ping = (base_ping + jitter).max(10.0);
+            player.dc_pings.insert(dc.id, ping);
+        }
+
+        // Find best DC (first in DC order on ties)
+        if let Some((best_dc, best_ping)) = self.data_centers.iter()
+            .map(|dc| (dc.id, player.dc_pings[&dc.id]))
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+        {
+            player.best_dc = Some(best_dc);
+            player.best_ping = best_ping;
+        }
+
+        // Set preferred playlists (everyone wants at least the first defined playlist)
+        player.preferred_playlists.clear();
+        for definition in &self.config.playlists {
+            if rng.gen_bool(definition.preference_prob) {
+                player.preferred_playlists.insert(definition.name);
+            }
+        }
+        if player.preferred_playlists.is_empty() {
+            player.preferred_playlists.extend(self.config.playlist_names().next());
+        }
+
+        // Smurfs: high true skill on a fresh low-rated account
+        let smurfs = &self.config.smurf_config;
+        if smurfs.fraction > 0.0 && rng.gen_bool(smurfs.fraction) {
+            player.smurf = true;
+            player.skill = smurfs.min_skill + (1.0 - smurfs.min_skill) * rng.gen::<f64>();
+            player.mmr = smurfs.account_mmr;
+        }
+
+        // Map preferences: each map is independently disliked, liked or neutral
+        let maps = &self.config.map_config;
+        if maps.enabled {
+            for map in maps.all_maps() {
+                let roll: f64 = rng.gen();
+                if roll < maps.dislike_prob {
+                    player.disliked_maps.insert(map.clone());
+                } else if roll < maps.dislike_prob + maps.like_prob {
+                    player.liked_maps.insert(map.clone());
+                }
+            }
+        }
+
+        // Start offline
+        player.state = PlayerState::Offline;
+        player
+    }
+
     /// Generate skill value using a beta-like distribution
     fn generate_skill(&self, rng: &mut impl Rng) -> f64 {
         // Use sum of uniform randoms to approximate normal distribution
@@ -316,6 +407,11 @@ impl Simulation {
         normalized.clamp(-1.0, 1.0)
     }
 
+    /// Approximate standard normal sample (sum of 12 uniforms, as in generate_skill)
+    fn sample_normal(&self, rng: &mut impl Rng) -> f64 {
+        (0..12).map(|_| rng.gen::<f64>()).sum::<f64>() - 6.0
+    }
+
     /// Generate performance index for a player in a match
     /// Per whitepaper §3.7: Y_i = f_perf(s_i, s_lobby, m) + ε_i
     fn generate_performance(
@@ -343,16 +439,17 @@ impl Simulation {
         (base_perf + noise).clamp(0.0, 1.0)
     }
 
-    /// Compute expected performance (deterministic part, no noise)
-    /// E[Y_i | s_i, lobby] = f_perf(s_i, s_lobby, m)
+    /// Compute expected performance as predicted by the rating system (no noise)
+    /// E[Y_i | mmr_i, lobby] = f_perf(mmr_i, mmr_lobby, m)
+    /// Uses the MMR estimate, not true skill: the rating system cannot observe the latter.
     fn compute_expected_performance(
         &self,
         player: &Player,
-        lobby_avg_skill: f64,
+        lobby_avg_mmr: f64,
     ) -> f64 {
-        // E[Y_i | s_i, lobby] = deterministic part (no noise)
-        let skill_advantage = player.skill - lobby_avg_skill;
-        let base_perf = 0.3 + (player.skill + 1.0) / 2.0 * 0.4 + skill_advantage * 0.2;
+        // E[Y_i | mmr_i, lobby] = deterministic part (no noise)
+        let skill_advantage = player.mmr - lobby_avg_mmr;
+        let base_perf = 0.3 + (player.mmr + 1.0) / 2.0 * 0.4 + skill_advantage * 0.2;
         base_perf.clamp(0.0, 1.0)
     }
 
@@ -365,20 +462,21 @@ impl Simulation {
         // Compute mean skill per bucket
         let mut bucket_skills: HashMap<usize, Vec<f64>> = HashMap::new();
         
-        for player in self.players.values() {
+        for player in self.players_in_order() {
             bucket_skills
                 .entry(player.skill_bucket)
                 .or_insert_with(Vec::new)
                 .push(player.skill);
         }
         
-        let snapshot: Vec<(usize, f64)> = bucket_skills
+        let mut snapshot: Vec<(usize, f64)> = bucket_skills
             .iter()
             .map(|(&bucket_id, skills)| {
                 let mean = skills.iter().sum::<f64>() / skills.len() as f64;
                 (bucket_id, mean)
             })
             .collect();
+        snapshot.sort_by_key(|&(bucket_id, _)| bucket_id);
         
         self.stats.skill_distribution_over_time.push((self.current_time, snapshot));
         
@@ -389,38 +487,51 @@ impl Simulation {
     }
 
     /// Update skill percentiles for all players
+    /// Percentiles are ranked by MMR, since that is all the matchmaker can observe
     pub fn update_skill_percentiles(&mut self) {
-        let mut skills: Vec<(usize, f64)> = self.players
+        let mut mmrs: Vec<(usize, f64)> = self.players
             .iter()
-            .map(|(&id, p)| (id, p.skill))
+            .map(|(&id, p)| (id, p.mmr))
             .collect();
         
-        skills.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        // Ties (e.g. MMR clamped at the bounds) rank by player id
+        par::sort_unstable_by(&mut mmrs, |a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
         
-        let n = skills.len() as f64;
-        for (rank, (id, _)) in skills.into_iter().enumerate() {
+        let n = mmrs.len() as f64;
+        for (rank, (id, _)) in mmrs.into_iter().enumerate() {
             if let Some(player) = self.players.get_mut(&id) {
                 player.skill_percentile = (rank as f64 + 0.5) / n;
                 player.update_skill_bucket(self.config.num_skill_buckets);
             }
         }
+
+        // Compare against the percentile the player would have under perfect information
+        let mut skills: Vec<(f64, usize, f64)> = self.players
+            .values()
+            .map(|p| (p.skill, p.id, p.skill_percentile))
+            .collect();
+        par::sort_unstable_by(&mut skills, |a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        if !skills.is_empty() {
+            self.stats.avg_percentile_error = skills.iter()
+                .enumerate()
+                .map(|(rank, &(_, _, observed))| ((rank as f64 + 0.5) / n - observed).abs())
+                .sum::<f64>() / n;
+        }
     }
 
     /// Bring players online based on arrival rate and return probability
     pub fn process_arrivals(&mut self, rng: &mut impl Rng) {
         // Collect offline players with their return probabilities
-        let mut candidates: Vec<(usize, usize, f64)> = Vec::new(); // (player_id, bucket, return_prob)
-        
-        for (&player_id, player) in &self.players {
-            if player.state == PlayerState::Offline {
-                let return_prob = self.compute_return_probability(player);
-                let bucket = player.skill_bucket;
-                candidates.push((player_id, bucket, return_prob));
-                
-                // Track return attempt by bucket
-                *self.return_attempts_by_bucket.entry(bucket).or_insert(0) += 1;
-                self.stats.total_return_attempts += 1;
-            }
+        let candidates: Vec<(usize, usize, Region, f64)> = self // (player_id, bucket, region, return_prob)
+            .players_in_order()
+            .filter(|player| player.state == PlayerState::Offline)
+            .map(|player| (player.id, player.skill_bucket, player.region, self.compute_return_probability(player)))
+            .collect();
+
+        // Track return attempts by bucket
+        for &(_, bucket, _, _) in &candidates {
+            *self.return_attempts_by_bucket.entry(bucket).or_insert(0) += 1;
+            self.stats.total_return_attempts += 1;
         }
 
         // If no candidates, nothing to process
@@ -430,10 +541,10 @@ impl Simulation {
 
         // Sample arrivals using return probability (threshold-based)
         // Each candidate has a chance to return based on their return probability
-        let mut arrivals: Vec<usize> = Vec::new();
-        for (player_id, bucket, return_prob) in candidates {
+        let mut arrivals: Vec<(usize, Region)> = Vec::new();
+        for (player_id, bucket, region, return_prob) in candidates {
             if rng.gen_bool(return_prob) {
-                arrivals.push(player_id);
+                arrivals.push((player_id, region));
                 
                 // Track return by bucket
                 *self.returns_by_bucket.entry(bucket).or_insert(0) += 1;
@@ -442,36 +553,92 @@ impl Simulation {
         }
 
         // Limit arrivals to Poisson rate (if we have too many, randomly sample)
-        let num_arrivals = self.poisson_sample(self.arrival_rate, rng);
-        if arrivals.len() > num_arrivals {
-            // Shuffle and take first N
-            use rand::seq::SliceRandom;
-            arrivals.as_mut_slice().shuffle(rng);
-            arrivals.truncate(num_arrivals);
-        }
+        let arrivals: Vec<usize> = if self.diurnal_arrivals.is_some() {
+            // Each region is capped separately at its time-of-day rate
+            self.regional_arrival_rates()
+                .into_iter()
+                .flat_map(|(region, rate)| {
+                    let regional = arrivals.iter().filter(|(_, r)| *r == region).map(|(id, _)| *id).collect();
+                    self.cap_arrivals(regional, rate, rng)
+                })
+                .collect()
+        } else {
+            let rate = self.current_arrival_rate();
+            self.cap_arrivals(arrivals.into_iter().map(|(id, _)| id).collect(), rate, rng)
+        };
 
         for player_id in arrivals {
-            if let Some(player) = self.players.get_mut(&player_id) {
-                // Start new session when player comes online
-                if player.state == PlayerState::Offline {
-                    player.session_start_time = Some(self.current_time);
-                    player.matches_in_session = 0;
-                    // Don't clear last_session_experience - we need it for return probability
-                    // Only clear last_session_end_time since they're now active again
-                    // (but keep experience for potential future return calculations)
-                    player.last_session_end_time = None;
-                }
-                player.state = PlayerState::InLobby;
+            self.bring_online(player_id);
+        }
+    }
+
+    /// Move a player to the lobby, starting a new session if they were offline
+    fn bring_online(&mut self, player_id: usize) {
+        if let Some(player) = self.players.get_mut(&player_id) {
+            // Start new session when player comes online
+            if player.state == PlayerState::Offline {
+                let tick = self.current_time;
+                self.events.emit(tick, || match player.last_session_end_time {
+                    Some(end) => SimEventKind::PlayerReturned {
+                        player_id,
+                        offline_ticks: tick.saturating_sub(end),
+                    },
+                    None => SimEventKind::PlayerArrived { player_id },
+                });
+                player.session_start_time = Some(self.current_time);
+                player.matches_in_session = 0;
+                // Don't clear last_session_experience - we need it for return probability
+                // Only clear last_session_end_time since they're now active again
+                // (but keep experience for potential future return calculations)
+                player.last_session_end_time = None;
+            }
+            player.state = PlayerState::InLobby;
+        }
+    }
+
+    /// Create new accounts at the acquisition rate and bring them online
+    /// New players get fresh ids, pings to every DC, a true skill shifted by `skill_offset`
+    /// and the cold-start MMR; percentiles are recomputed once they are added.
+    pub fn process_acquisition(&mut self, rng: &mut impl Rng) {
+        let rate = self.acquisition.rate_at(self.current_time);
+        if rate <= 0.0 {
+            return;
+        }
+        let count = self.poisson_sample(rate, rng);
+        if count == 0 {
+            return;
+        }
+
+        let regions = if self.population_mix.is_empty() {
+            Self::default_region_weights()
+        } else {
+            self.population_mix.clone()
+        };
+        for _ in 0..count {
+            let id = self.next_player_id;
+            self.next_player_id += 1;
+
+            let mut player = self.generate_player(id, &regions, rng);
+            if !player.smurf {
+                player.skill = (player.skill + self.acquisition.skill_offset).clamp(-1.0, 1.0);
+                player.mmr = self.acquisition.initial_mmr;
             }
+            player.created_at = Some(self.current_time);
+            self.players.insert(id, player);
+            self.stats.total_acquired += 1;
+
+            self.events.emit(self.current_time, || SimEventKind::PlayerAcquired { player_id: id });
+            self.bring_online(id);
         }
+
+        self.update_skill_percentiles();
     }
 
     /// Move lobby players to searching
     pub fn process_search_starts(&mut self, rng: &mut impl Rng) {
-        let lobby_players: Vec<usize> = self.players
-            .iter()
-            .filter(|(_, p)| p.state == PlayerState::InLobby)
-            .map(|(&id, _)| id)
+        let lobby_players: Vec<usize> = self.players_in_order()
+            .filter(|p| p.state == PlayerState::InLobby)
+            .map(|p| p.id)
             .collect();
 
         // Each lobby player has a chance to start searching
@@ -527,6 +694,12 @@ impl Simulation {
                     &self.data_centers,
                 );
 
+                self.events.emit(self.current_time, || SimEventKind::SearchStarted {
+                    search_id: search.id,
+                    player_ids: search.player_ids.clone(),
+                    party_id: Some(party_id),
+                });
+                Self::record_playlist_preferences(&mut self.stats.playlist_shares, &search);
                 self.next_search_id += 1;
                 self.searches.push(search);
                 return;
@@ -568,92 +741,124 @@ impl Simulation {
             acceptable_playlists: player.preferred_playlists.clone(),
             search_start_time: self.current_time,
             acceptable_dcs,
+            placement: player.in_placement(&self.config),
         };
 
+        self.events.emit(self.current_time, || SimEventKind::SearchStarted {
+            search_id: search.id,
+            player_ids: vec![player_id],
+            party_id: None,
+        });
+        Self::record_playlist_preferences(&mut self.stats.playlist_shares, &search);
         self.next_search_id += 1;
         self.searches.push(search);
     }
 
-    /// Run matchmaking tick
+    /// Split a new search's players evenly over the playlists it accepts
+    fn record_playlist_preferences(shares: &mut HashMap<Playlist, PlaylistShareStats>, search: &SearchObject) {
+        let weight = search.size() as f64 / search.acceptable_playlists.len().max(1) as f64;
+        for playlist in &search.acceptable_playlists {
+            shares.entry(*playlist).or_default().preference_weight += weight;
+        }
+    }
+
+    /// Run matchmaking tick with the configured strategy
     pub fn run_matchmaking(&mut self) -> Vec<MatchResult> {
         let mut rng = StdRng::seed_from_u64(self.rng_seed.wrapping_add(self.current_time));
-        let matchmaker = Matchmaker::new(self.config.clone());
+        let mut strategy = matchmaking_strategy(&self.config);
+
+        // Searches whose region backoff widened since the previous tick
+        for search in &self.searches {
+            let wait_time = search.wait_time(self.current_time, self.config.tick_interval);
+            let prev_wait_time = wait_time - self.config.tick_interval;
+            let tier = Region::backoff_tier(wait_time);
+            if tier > Region::backoff_tier(prev_wait_time) {
+                self.events.emit(self.current_time, || SimEventKind::SearchExpanded {
+                    search_id: search.id,
+                    tier,
+                    wait_time,
+                });
+            }
+        }
 
-        matchmaker.run_tick(
+        let results = strategy.run_tick(
             &mut self.searches,
             &mut self.players,
             &mut self.data_centers,
             &self.parties,
             self.current_time,
             &mut rng,
-        )
+        );
+
+        if let Some(report) = strategy.batch_report() {
+            self.stats.batch_comparison.record(&report);
+        }
+
+        results
     }
 
     /// Process match results and create matches
     pub fn create_matches(&mut self, results: Vec<MatchResult>, rng: &mut impl Rng) {
-        for result in results {
+        for mut result in results {
+            let map = self.choose_map(result.playlist, &result.player_ids, rng);
+            if let Some(map) = &map {
+                if !self.apply_lobby_quits(&mut result, map, rng) {
+                    continue;
+                }
+            }
+
             let match_id = self.next_match_id;
             self.next_match_id += 1;
 
-            // Calculate team skills
-            let team_skills: Vec<f64> = result.teams
+            // Calculate team skills (empty slots of under-full lobbies count against a team)
+            let team_skills = self.team_strengths(&result.teams, result.playlist);
+
+            // Team MMRs: the balance the matchmaker believed it created
+            let team_mmrs: Vec<f64> = result.teams
                 .iter()
                 .map(|team| {
                     team.iter()
                         .filter_map(|&id| self.players.get(&id))
-                        .map(|p| p.skill)
+                        .map(|p| p.mmr)
                         .sum::<f64>() / team.len() as f64
                 })
                 .collect();
+            if team_mmrs.len() >= 2 {
+                self.stats.team_mmr_difference_samples.push(Self::team_gap(&team_mmrs).abs());
+            }
 
             // Calculate team skill difference and win probability
-            let team_skill_diff = if team_skills.len() >= 2 {
-                team_skills[0] - team_skills[1]
-            } else {
-                0.0
-            };
+            let team_skill_diff = Self::team_gap(&team_skills);
             
             // Track team skill difference for statistics
             self.stats.team_skill_difference_samples.push(team_skill_diff.abs());
 
-            // Calculate win probability using configurable logistic
-            let win_prob_team0 = if team_skills.len() >= 2 {
-                let gamma = self.config.gamma;
-                let logistic = 1.0 / (1.0 + (-gamma * team_skill_diff).exp());
-                logistic.clamp(0.0, 1.0)
-            } else {
-                0.5
-            };
-            
-            // Ensure probability is valid
-            let win_prob_team0 = if win_prob_team0.is_finite() { win_prob_team0 } else { 0.5 };
-            
             // Calculate win probability imbalance (0-1 scale)
-            let win_probability_imbalance = (win_prob_team0 - 0.5).abs() * 2.0;
+            let win_probability_imbalance = Self::win_probability_imbalance(&self.config, &team_skills);
             
-            // Calculate expected score differential
-            // For team-based modes, scale by typical score range (e.g., 0-100 for TDM)
-            let expected_score_differential = match result.playlist {
-                Playlist::TeamDeathmatch => team_skill_diff * 30.0, // Rough scaling
-                Playlist::SearchAndDestroy => team_skill_diff * 6.0,  // Rounds won
-                Playlist::Domination => team_skill_diff * 200.0,     // Points
-                Playlist::GroundWar => team_skill_diff * 100.0,      // Points
-                Playlist::FreeForAll => team_skill_diff * 20.0,      // Kills
+            // Match clock: placement matches vary around the mean, scored matches may end early
+            let definition = self.config.playlist(result.playlist);
+            let spread = definition.duration_spread;
+            let clock = if definition.ranks_placements() && spread > 0.0 {
+                definition.avg_duration_seconds * rng.gen_range((1.0 - spread)..(1.0 + spread))
+            } else {
+                definition.avg_duration_seconds
             };
 
-            // Calculate match duration with some variance
-            let base_duration = result.playlist.avg_match_duration_seconds();
-            let duration_variance = rng.gen_range(0.8..1.2);
-            let duration_ticks = ((base_duration * duration_variance) / self.config.tick_interval) as u64;
+            // Expected scoreline under the playlist's scoring rules
+            let fill_ratio = result.player_ids.len() as f64 / definition.required_players() as f64;
+            let expected_scores = scoring::expected_scores(&definition.scoring, &team_skills, clock, fill_ratio);
+            let expected_score_differential = Self::team_gap(&expected_scores);
 
-            let game_match = Match {
+            let mut game_match = Match {
                 id: match_id,
                 playlist: result.playlist,
                 data_center_id: result.data_center_id,
                 teams: result.teams.clone(),
                 start_time: self.current_time,
-                expected_duration: duration_ticks,
+                expected_duration: 0,
                 team_skills,
+                team_mmrs,
                 quality_score: result.quality_score,
                 skill_disparity: result.skill_disparity,
                 avg_delta_ping: result.avg_delta_ping,
@@ -661,8 +866,36 @@ impl Simulation {
                 win_probability_imbalance,
                 blowout_severity: None, // Will be assigned in determine_outcome()
                 player_performances: HashMap::new(),
+                running_score: if self.config.abandonment_config.enabled && result.teams.len() == 2 {
+                    vec![0, 0]
+                } else {
+                    Vec::new()
+                },
+                abandoned_players: Vec::new(),
+                backfilled_players: Vec::new(),
+                fill_ratio,
+                map: map.clone(),
+                outcome: None,
             };
 
+            // Play out the result now: the scoreline decides how long the match occupies its server
+            let outcome = self.play_out(&game_match, clock, rng);
+            game_match.expected_duration = (outcome.scoreline.length_seconds / self.config.tick_interval).ceil().max(1.0) as u64;
+            game_match.outcome = Some(outcome);
+
+            // Track lobby fill
+            let fill = &mut self.stats.fill;
+            if game_match.fill_ratio < 1.0 {
+                fill.underfull_matches += 1;
+            } else {
+                fill.full_matches += 1;
+            }
+            fill.fill_ratio_sum += game_match.fill_ratio;
+            let (count, ratio_sum) = fill.per_playlist_fill_sums.entry(game_match.playlist).or_insert((0, 0.0));
+            *count += 1;
+            *ratio_sum += game_match.fill_ratio;
+            self.stats.playlist_shares.entry(game_match.playlist).or_default().players_matched += result.player_ids.len();
+
             // Check if match involves parties
             let has_party = result.player_ids.iter().any(|&pid| {
                 self.players.get(&pid)
@@ -681,55 +914,203 @@ impl Simulation {
 
             // Update player states
             for &player_id in &result.player_ids {
-                if let Some(player) = self.players.get_mut(&player_id) {
-                    // Record search time
-                    if let Some(start) = player.search_start_time {
-                        let search_time = (self.current_time - start) as f64 * self.config.tick_interval;
-                        player.recent_search_times.push(search_time);
-                        if player.recent_search_times.len() > 10 {
-                            player.recent_search_times.remove(0);
-                        }
-                        self.stats.search_time_samples.push(search_time);
-                        
-                        // Track party vs solo search times
-                        if player.party_id.is_some() {
-                            self.stats.party_search_times.push(search_time);
-                        } else {
-                            self.stats.solo_search_times.push(search_time);
-                        }
-                    }
-
-                    // Record delta ping
-                    if let Some(&ping) = player.dc_pings.get(&result.data_center_id) {
-                        let delta_ping = ping - player.best_ping;
-                        player.recent_delta_pings.push(delta_ping);
-                        if player.recent_delta_pings.len() > 10 {
-                            player.recent_delta_pings.remove(0);
-                        }
-                        self.stats.delta_ping_samples.push(delta_ping);
-                    }
-
-                    player.state = PlayerState::InMatch;
-                    player.current_match = Some(match_id);
-                    player.search_start_time = None;
+                self.join_match(player_id, match_id, result.data_center_id);
+                if let Some(map) = &map {
+                    self.record_map_play(player_id, map);
                 }
             }
+            if let Some(map) = &map {
+                *self.stats.maps.per_map_matches.entry(map.clone()).or_insert(0) += 1;
+            }
 
-            // Record skill disparity
+            // Record skill disparity and match quality
             self.stats.skill_disparity_samples.push(result.skill_disparity);
+            self.stats.match_quality_samples.push(result.quality_score);
+
+            self.events.emit(self.current_time, || SimEventKind::MatchFormed {
+                match_id,
+                playlist: game_match.playlist,
+                data_center_id: game_match.data_center_id,
+                teams: game_match.teams.clone(),
+                quality_score: game_match.quality_score,
+                skill_disparity: game_match.skill_disparity,
+                map: game_match.map.clone(),
+            });
 
             self.matches.insert(match_id, game_match);
             self.stats.total_matches += 1;
         }
     }
 
+    /// Pick the map for a new lobby from its playlist's pool, by rotation or by player vote
+    /// Voters favor liked maps and avoid disliked or recently played ones; ties go to the
+    /// earlier option. None when maps are disabled or the playlist has no pool.
+    fn choose_map(&mut self, playlist: Playlist, player_ids: &[usize], rng: &mut impl Rng) -> Option<String> {
+        let maps = &self.config.map_config;
+        if !maps.enabled {
+            return None;
+        }
+        let pool = maps.pools.get(&playlist).filter(|pool| !pool.is_empty())?;
+        match maps.selection {
+            MapSelection::Rotation => {
+                let next = self.map_rotation.entry(playlist).or_insert(0);
+                let map = pool[*next % pool.len()].clone();
+                *next = (*next + 1) % pool.len();
+                Some(map)
+            }
+            MapSelection::Vote => {
+                let options: Vec<&String> = rand::seq::index::sample(rng, pool.len(), maps.vote_options.min(pool.len()))
+                    .into_iter()
+                    .map(|i| &pool[i])
+                    .collect();
+                let mut votes = vec![0usize; options.len()];
+                for player in player_ids.iter().filter_map(|id| self.players.get(id)) {
+                    let appeal = |map: &String| {
+                        player.liked_maps.contains(map) as u8 as f64
+                            - player.disliked_maps.contains(map) as u8 as f64
+                            - 0.5 * player.recent_maps.contains(map) as u8 as f64
+                    };
+                    let choice = (0..options.len())
+                        .max_by(|&a, &b| appeal(options[a]).total_cmp(&appeal(options[b])).then(b.cmp(&a)))
+                        .unwrap_or(0);
+                    votes[choice] += 1;
+                }
+                let winner = (0..options.len())
+                    .max_by(|&a, &b| votes[a].cmp(&votes[b]).then(b.cmp(&a)))
+                    .unwrap_or(0);
+                Some(options[winner].clone())
+            }
+        }
+    }
+
+    /// Let players quit the lobby when a disliked or recently played map comes up
+    /// Quitters take their party members in the lobby with them and go back to the menus.
+    /// If a team is left empty (or fewer than two players remain) the lobby dissolves: its
+    /// server is released, everyone returns to the menus and false is returned.
+    fn apply_lobby_quits(&mut self, result: &mut MatchResult, map: &str, rng: &mut impl Rng) -> bool {
+        let maps = &self.config.map_config;
+        let mut quitters: HashSet<usize> = HashSet::new();
+        for &player_id in &result.player_ids {
+            let Some(player) = self.players.get(&player_id) else {
+                continue;
+            };
+            let mut stay_prob = 1.0;
+            if player.disliked_maps.contains(map) {
+                stay_prob *= 1.0 - maps.dislike_quit_prob;
+            }
+            if player.recent_maps.iter().any(|m| m == map) {
+                stay_prob *= 1.0 - maps.repeat_quit_prob;
+            }
+            if quitters.contains(&player_id) || stay_prob >= 1.0 || !rng.gen_bool(1.0 - stay_prob) {
+                continue;
+            }
+            let party = player.party_id.and_then(|id| self.parties.get(&id));
+            quitters.extend(party.map_or(vec![player_id], |p| p.player_ids.clone()));
+        }
+        quitters.retain(|id| result.player_ids.contains(id));
+
+        let map_stats = &mut self.stats.maps;
+        map_stats.players_offered += result.player_ids.len();
+        map_stats.lobby_quits += quitters.len();
+        for &player_id in result.player_ids.iter().filter(|id| quitters.contains(id)) {
+            self.events.emit(self.current_time, || SimEventKind::PlayerQuitLobby {
+                player_id,
+                map: map.to_string(),
+            });
+        }
+        if quitters.is_empty() {
+            return true;
+        }
+
+        result.player_ids.retain(|id| !quitters.contains(id));
+        for team in &mut result.teams {
+            team.retain(|id| !quitters.contains(id));
+        }
+        let definition = self.config.playlist(result.playlist);
+        if definition.is_free_for_all() || definition.ranks_placements() {
+            result.teams.retain(|team| !team.is_empty());
+        }
+        let dissolved = result.player_ids.len() < 2 || result.teams.iter().any(Vec::is_empty);
+        if dissolved {
+            self.stats.maps.dissolved_lobbies += 1;
+            if let Some(dc) = self.data_centers.iter_mut().find(|dc| dc.id == result.data_center_id) {
+                if let Some(busy) = dc.busy_servers.get_mut(&result.playlist) {
+                    *busy = busy.saturating_sub(1);
+                }
+            }
+        }
+
+        let returning = result.player_ids.iter().filter(|_| dissolved);
+        for player_id in quitters.iter().chain(returning) {
+            if let Some(player) = self.players.get_mut(player_id) {
+                player.state = PlayerState::InLobby;
+                player.search_start_time = None;
+            }
+        }
+        !dissolved
+    }
+
+    /// Remember a map a player was placed on and count disliked and repeat plays
+    fn record_map_play(&mut self, player_id: usize, map: &str) {
+        let history_length = self.config.map_config.history_length;
+        let Some(player) = self.players.get_mut(&player_id) else {
+            return;
+        };
+        let map_stats = &mut self.stats.maps;
+        map_stats.player_plays += 1;
+        map_stats.disliked_plays += player.disliked_maps.contains(map) as usize;
+        map_stats.repeat_plays += player.recent_maps.iter().any(|m| m == map) as usize;
+
+        player.recent_maps.push(map.to_string());
+        if player.recent_maps.len() > history_length {
+            player.recent_maps.remove(0);
+        }
+    }
+
+    /// Move a matched player into a match, recording their search time and delta ping
+    fn join_match(&mut self, player_id: usize, match_id: usize, data_center_id: usize) {
+        if let Some(player) = self.players.get_mut(&player_id) {
+            // Record search time
+            if let Some(start) = player.search_start_time {
+                let search_time = (self.current_time - start) as f64 * self.config.tick_interval;
+                player.recent_search_times.push(search_time);
+                if player.recent_search_times.len() > 10 {
+                    player.recent_search_times.remove(0);
+                }
+                self.stats.search_time_samples.push(search_time);
+                
+                // Track party vs solo search times
+                if player.party_id.is_some() {
+                    self.stats.party_search_times.push(search_time);
+                } else {
+                    self.stats.solo_search_times.push(search_time);
+                }
+            }
+
+            // Record delta ping
+            if let Some(&ping) = player.dc_pings.get(&data_center_id) {
+                let delta_ping = ping - player.best_ping;
+                player.recent_delta_pings.push(delta_ping);
+                if player.recent_delta_pings.len() > 10 {
+                    player.recent_delta_pings.remove(0);
+                }
+                self.stats.delta_ping_samples.push(delta_ping);
+            }
+
+            player.state = PlayerState::InMatch;
+            player.current_match = Some(match_id);
+            player.search_start_time = None;
+        }
+    }
+
     /// Process match completions
     pub fn process_match_completions(&mut self, rng: &mut impl Rng) {
-        let completed_matches: Vec<usize> = self.matches
+        let mut completed_matches: Vec<usize> = self.matches
             .iter()
             .filter(|(_, m)| self.current_time >= m.start_time + m.expected_duration)
             .map(|(&id, _)| id)
             .collect();
+        completed_matches.sort_unstable();
 
         for match_id in completed_matches {
             if let Some(mut game_match) = self.matches.remove(&match_id) {
@@ -743,8 +1124,43 @@ impl Simulation {
                 // Track per-playlist match count
                 *self.stats.per_playlist_match_counts.entry(game_match.playlist).or_insert(0) += 1;
 
-                // Determine match outcome
-                let (winning_team, is_blowout, blowout_severity) = self.determine_outcome(&mut game_match, rng);
+                // A match whose roster changed mid-match is re-decided from its running score and
+                // the players still in it; otherwise it ends with the scoreline it was played out with
+                let roster_changed = !game_match.abandoned_players.is_empty() || !game_match.backfilled_players.is_empty();
+                let outcome = match game_match.outcome.take() {
+                    Some(outcome) if !roster_changed => outcome,
+                    _ => {
+                        let played = game_match.expected_duration as f64 * self.config.tick_interval;
+                        self.play_out(&game_match, played, rng)
+                    }
+                };
+                let MatchOutcome { winning_team, ranks, eliminations, scoreline } = outcome;
+                let definition = self.config.playlist(game_match.playlist);
+                let battle_royale = definition.ranks_placements();
+                let rules = definition.scoring.clone();
+                let margin = scoreline.margin(winning_team);
+                let blowout_severity = Self::blowout_severity(&self.config, margin);
+                let is_blowout = blowout_severity.is_some();
+                game_match.blowout_severity = blowout_severity;
+
+                let scorelines = &mut self.stats.scorelines;
+                scorelines.matches += 1;
+                scorelines.length_sum_seconds += scoreline.length_seconds;
+                scorelines.score_limit_endings += scoreline.reached_limit as usize;
+                scorelines.mercy_endings += scoreline.mercy as usize;
+                scorelines.margin_sum += margin;
+                if is_blowout {
+                    scorelines.blowouts += 1;
+                    scorelines.blowout_length_sum_seconds += scoreline.length_seconds;
+                }
+                let (count, length_sum) = scorelines.per_playlist_length_sums.entry(game_match.playlist).or_insert((0, 0.0));
+                *count += 1;
+                *length_sum += scoreline.length_seconds;
+                for line in game_match.teams.iter().flatten().filter_map(|id| scoreline.players.get(id)) {
+                    scorelines.player_matches += 1;
+                    scorelines.kills_sum += line.kills;
+                    scorelines.deaths_sum += line.deaths;
+                }
                 
                 if is_blowout {
                     self.stats.blowout_count += 1;
@@ -757,61 +1173,124 @@ impl Simulation {
                     *self.stats.blowout_severity_counts.entry(severity).or_insert(0) += 1;
                 }
 
+                // Track outcomes by lobby fill
+                let fill = &mut self.stats.fill;
+                if game_match.fill_ratio < 1.0 {
+                    fill.completed_underfull += 1;
+                    fill.blowouts_underfull += is_blowout as usize;
+                } else {
+                    fill.completed_full += 1;
+                    fill.blowouts_full += is_blowout as usize;
+                }
+                if let [team0, team1] = &game_match.teams[..] {
+                    if team0.len() != team1.len() {
+                        let short_team = if team0.len() < team1.len() { 0 } else { 1 };
+                        fill.uneven_matches += 1;
+                        fill.short_team_wins += (winning_team == short_team) as usize;
+                    }
+                }
+
+                self.events.emit(self.current_time, || SimEventKind::MatchCompleted {
+                    match_id,
+                    winning_team,
+                    blowout_severity,
+                    placements: ranks.clone().unwrap_or_default(),
+                    scores: scoreline.team_scores.clone(),
+                });
+
                 // Compute performance indices and update skills
-                // 1. Compute lobby average skill
+                // 1. Compute lobby average MMR
                 let all_player_ids: Vec<usize> = game_match.teams.iter().flatten().copied().collect();
-                let lobby_avg_skill = if !all_player_ids.is_empty() {
+                let lobby_avg_mmr = if !all_player_ids.is_empty() {
                     all_player_ids.iter()
-                        .filter_map(|&pid| self.players.get(&pid).map(|p| p.skill))
+                        .filter_map(|&pid| self.players.get(&pid).map(|p| p.mmr))
                         .sum::<f64>() / all_player_ids.len() as f64
                 } else {
                     0.0
                 };
 
-                // 2. Generate performance for each player and update skills
-                for &player_id in &all_player_ids {
-                    // Get player immutably first to compute expected performance
-                    let expected_perf = if let Some(player) = self.players.get(&player_id) {
-                        if self.config.enable_skill_evolution {
-                            self.compute_expected_performance(player, lobby_avg_skill)
-                        } else {
-                            0.0
-                        }
-                    } else {
+                // 2. Performance for each player from their scoreline (in ranked matches, the share
+                // of other teams they finished ahead of, against what the team MMRs predicted)
+                let mut expected_performances: HashMap<usize, f64> = HashMap::new();
+                for (team_idx, &player_id) in game_match
+                    .teams
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(team_idx, team)| team.iter().map(move |id| (team_idx, id)))
+                {
+                    let Some(player) = self.players.get(&player_id) else {
                         continue;
                     };
-                    
-                    // Generate performance index (need player reference again)
-                    let performance = if let Some(player) = self.players.get(&player_id) {
-                        self.generate_performance(
-                            player,
-                            lobby_avg_skill,
-                            game_match.playlist,
-                            rng,
-                        )
-                    } else {
-                        continue;
+                    let (performance, expected_perf) = match &ranks {
+                        Some(ranks) => (
+                            Self::finish_percentile(ranks, team_idx),
+                            Self::expected_finish_percentile(&self.config, &game_match.team_mmrs, team_idx),
+                        ),
+                        None => (
+                            scoring::performance(&rules, &scoreline, player_id),
+                            self.compute_expected_performance(player, lobby_avg_mmr),
+                        ),
                     };
                     
                     // Store in match
                     game_match.player_performances.insert(player_id, performance);
+                    expected_performances.insert(player_id, expected_perf);
                     
                     // Track performance sample
                     self.stats.performance_samples.push(performance);
                     if self.stats.performance_samples.len() > 1000 {
                         self.stats.performance_samples.remove(0);
                     }
-                    
-                    // Update skill if evolution is enabled
-                    if self.config.enable_skill_evolution {
+                }
+
+                // Placement players (before this match counts) and whether established players were present
+                let placement_mmrs: Vec<(usize, f64)> = all_player_ids
+                    .iter()
+                    .filter_map(|pid| self.players.get(pid))
+                    .filter(|p| p.in_placement(&self.config))
+                    .map(|p| (p.id, p.mmr))
+                    .collect();
+                if placement_mmrs.len() < all_player_ids.len() {
+                    let placement = &mut self.stats.placement;
+                    if placement_mmrs.is_empty() {
+                        placement.matches_without_placement += 1;
+                        placement.blowouts_without_placement += is_blowout as usize;
+                    } else {
+                        placement.matches_with_placement += 1;
+                        placement.blowouts_with_placement += is_blowout as usize;
+                    }
+                }
+
+                // Update ratings with the configured rating system if evolution is enabled
+                // (not for forfeits, where one team left before the end)
+                let forfeit = game_match.teams.iter().any(|team| team.is_empty());
+                if self.config.enable_skill_evolution && !forfeit {
+                    let team_ranks: Vec<usize> = match &ranks {
+                        Some(ranks) => ranks.clone(),
+                        None => (0..game_match.teams.len())
+                            .map(|team_idx| if team_idx == winning_team { 0 } else { 1 })
+                            .collect(),
+                    };
+                    rating_model(&self.config).update(
+                        &RatedMatch {
+                            teams: &game_match.teams,
+                            team_ranks: &team_ranks,
+                            performances: &game_match.player_performances,
+                            expected_performances: &expected_performances,
+                        },
+                        &mut self.players,
+                    );
+
+                    // Provisional ratings move faster so they converge within the placement matches
+                    let multiplier = self.config.placement_rating_multiplier;
+                    for &(player_id, before) in &placement_mmrs {
+                        if let Some(player) = self.players.get_mut(&player_id) {
+                            player.mmr = (before + (player.mmr - before) * multiplier).clamp(-1.0, 1.0);
+                        }
+                    }
+
+                    for (&player_id, &performance) in &game_match.player_performances {
                         if let Some(player_mut) = self.players.get_mut(&player_id) {
-                            // Normalize observed performance (simple: use raw performance)
-                            let observed_perf = performance;
-                            
-                            // Skill update: s_i^+ = s_i^- + α(ŷ_i - E[Y_i])
-                            let skill_update = self.config.skill_learning_rate * (observed_perf - expected_perf);
-                            player_mut.skill = (player_mut.skill + skill_update).clamp(-1.0, 1.0);
-                            
                             // Track performance in rolling window
                             player_mut.recent_performance.push(performance);
                             if player_mut.recent_performance.len() > 10 {
@@ -833,9 +1312,28 @@ impl Simulation {
                     }
                 }
 
+                // Teams with a smurf on them
+                let smurf_teams: Vec<bool> = game_match.teams
+                    .iter()
+                    .map(|team| team.iter().any(|pid| self.players.get(pid).is_some_and(|p| p.smurf)))
+                    .collect();
+                let smurf_match = smurf_teams.contains(&true);
+                self.stats.smurfs.smurf_matches += smurf_match as usize;
+
+                if let Some(ranks) = ranks.as_ref().filter(|_| battle_royale) {
+                    let battle_royale = &mut self.stats.battle_royale;
+                    battle_royale.matches += 1;
+                    battle_royale.squads_sum += ranks.len();
+                    battle_royale.eliminations_sum += eliminations.values().sum::<usize>();
+                    let strongest = (0..ranks.len()).max_by(|&a, &b| game_match.team_skills[a].total_cmp(&game_match.team_skills[b]));
+                    battle_royale.strongest_squad_wins += (strongest == Some(winning_team)) as usize;
+                }
+
                 // Update player stats and decide if they continue
                 for (team_idx, team) in game_match.teams.iter().enumerate() {
                     let won = team_idx == winning_team;
+                    let placement = ranks.as_ref().map(|ranks| Self::finish_percentile(ranks, team_idx));
+                    let facing_smurf = smurf_teams.iter().enumerate().any(|(i, &smurf)| smurf && i != team_idx);
                     
                     for &player_id in team {
                         // Get immutable reference first to compute continue probability
@@ -860,15 +1358,25 @@ impl Simulation {
                             was_blowout: is_blowout,
                             won,
                             performance: match_performance,
+                            placement,
+                            eliminations: eliminations.get(&player_id).copied().unwrap_or(0),
                         };
                         
                         // Now get mutable reference to update player
                         if let Some(player) = self.players.get_mut(&player_id) {
                             player.matches_played += 1;
+                            if let Some(line) = scoreline.players.get(&player_id) {
+                                player.total_kills += line.kills;
+                                player.total_deaths += line.deaths;
+                                player.total_objective += line.objective;
+                            }
+                            Self::track_placement(&mut self.stats, &self.config, player);
                             if won {
                                 player.wins += 1;
+                                player.loss_streak = 0;
                             } else {
                                 player.losses += 1;
+                                player.loss_streak += 1;
                             }
 
                             player.recent_blowouts.push(is_blowout);
@@ -878,6 +1386,23 @@ impl Simulation {
 
                             player.current_match = None;
                             
+                            // Smurf exposure group for this player-match
+                            let smurf_group = if player.smurf {
+                                None
+                            } else if facing_smurf {
+                                Some(&mut self.stats.smurfs.victims)
+                            } else if smurf_match {
+                                Some(&mut self.stats.smurfs.teammates)
+                            } else {
+                                Some(&mut self.stats.smurfs.unaffected)
+                            };
+                            let match_group = if game_match.backfilled_players.contains(&player_id) {
+                                &mut self.stats.abandonment.backfilled
+                            } else {
+                                &mut self.stats.abandonment.full_match
+                            };
+                            let recorded_experience = experience.clone();
+
                             // Add to recent experience (maintain window size)
                             player.recent_experience.push(experience);
                             let window_size = self.config.retention_config.experience_window_size;
@@ -901,8 +1426,9 @@ impl Simulation {
                                     let avg_delta_ping = recent.iter().map(|e| e.avg_delta_ping).sum::<f64>() / recent.len() as f64;
                                     let avg_search_time = recent.iter().map(|e| e.avg_search_time).sum::<f64>() / recent.len() as f64;
                                     let blowout_rate = recent.iter().filter(|e| e.was_blowout).count() as f64 / recent.len() as f64;
-                                    let win_rate = recent.iter().filter(|e| e.won).count() as f64 / recent.len() as f64;
+                                    let win_rate = recent.iter().map(ExperienceVector::win_score).sum::<f64>() / recent.len() as f64;
                                     let avg_performance = recent.iter().map(|e| e.performance).sum::<f64>() / recent.len() as f64;
+                                    let avg_eliminations = recent.iter().map(|e| e.eliminations as f64).sum::<f64>() / recent.len() as f64;
                                     
                                     // Debug: Log first few calculations to verify math
                                     let ping_term = config.theta_ping * avg_delta_ping;
@@ -910,13 +1436,15 @@ impl Simulation {
                                     let blowout_term = config.theta_blowout * blowout_rate;
                                     let win_term = config.theta_win_rate * win_rate;
                                     let perf_term = config.theta_performance * avg_performance;
+                                    let elimination_term = config.theta_eliminations * avg_eliminations;
                                     
                                     let logit = config.base_continue_prob
                                         + ping_term
                                         + search_term
                                         + blowout_term
                                         + win_term
-                                        + perf_term;
+                                        + perf_term
+                                        + elimination_term;
                                     
                                     // Track diagnostic samples (keep last 100)
                                     self.logit_samples.push(logit);
@@ -943,43 +1471,48 @@ impl Simulation {
                                 self.continue_prob_samples.remove(0);
                             }
                             
-                            if rng.gen_bool(continue_prob) {
+                            let continued = rng.gen_bool(continue_prob);
+                            if let Some(group) = smurf_group {
+                                group.record(&recorded_experience, continued);
+                            }
+                            match_group.record(&recorded_experience, continued);
+                            match placement.filter(|_| battle_royale) {
+                                Some(percentile) if percentile >= 0.5 => self.stats.battle_royale.top_half.record(&recorded_experience, continued),
+                                Some(_) => self.stats.battle_royale.bottom_half.record(&recorded_experience, continued),
+                                None => {}
+                            }
+                            if continued {
                                 // Player continues
+                                self.events.emit(self.current_time, || SimEventKind::PlayerContinued {
+                                    player_id,
+                                    match_id,
+                                    continue_prob,
+                                });
                                 *continues += 1;
                                 player.state = PlayerState::InLobby;
                                 player.matches_in_session += 1;
                             } else {
                                 // Player quits
+                                self.events.emit(self.current_time, || SimEventKind::PlayerQuit {
+                                    player_id,
+                                    match_id,
+                                    matches_in_session,
+                                });
                                 *quits += 1;
                                 
                                 // Track quit for leaving rate calculation
                                 self.stats.recent_quits.push((self.current_time, 1));
                                 
-                                // Preserve session experience for return probability calculation
-                                player.last_session_experience = player.recent_experience.clone();
-                                player.last_session_end_time = Some(self.current_time);
-                                
-                                // Clear current session experience (will be rebuilt in next session)
-                                player.recent_experience.clear();
-                                
-                                player.state = PlayerState::Offline;
-                                
-                                // Record session completion
-                                if matches_in_session > 0 {
-                                    let session_length = matches_in_session;
-                                    self.total_matches_in_sessions += session_length;
-                                    self.stats.total_sessions_completed += 1;
-                                    
-                                    // Record in distribution (extend if needed)
-                                    while self.stats.session_length_distribution.len() <= session_length {
-                                        self.stats.session_length_distribution.push(0);
-                                    }
-                                    self.stats.session_length_distribution[session_length] += 1;
-                                    
-                                    // Clear session tracking
-                                    player.session_start_time = None;
-                                    player.matches_in_session = 0;
-                                }
+                                Self::end_session(&mut self.stats, &mut self.total_matches_in_sessions, player, self.current_time);
+                            }
+
+                            // A smurf on a losing streak may abandon the account for a fresh one
+                            let smurfs = &self.config.smurf_config;
+                            if player.smurf && player.loss_streak >= smurfs.loss_streak && rng.gen_bool(smurfs.new_account_prob) {
+                                let rating_state = RatingState::initial(&self.config.rating_config);
+                                player.start_new_account(smurfs.account_mmr, rating_state, self.current_time);
+                                self.stats.smurfs.new_accounts += 1;
+                                self.events.emit(self.current_time, || SimEventKind::SmurfNewAccount { player_id });
                             }
                         }
                     }
@@ -988,58 +1521,495 @@ impl Simulation {
         }
     }
 
-    /// Determine match outcome using skill difference
-    /// Returns (winning_team, is_blowout, blowout_severity)
-    fn determine_outcome(&self, game_match: &mut Match, rng: &mut impl Rng) -> (usize, bool, Option<BlowoutSeverity>) {
-        if game_match.team_skills.len() < 2 {
-            return (0, false, None);
+    /// Placement progress of a new account after it finishes (or abandons) a match
+    fn track_placement(stats: &mut SimulationStats, config: &MatchmakingConfig, player: &mut Player) {
+        if player.created_at.is_none() {
+            return;
+        }
+        let rating_error = (player.mmr - player.skill).abs();
+        let placement = &mut stats.placement;
+        if player.matches_played == config.placement_matches {
+            placement.placements_completed += 1;
+            placement.rating_error_after_placement_sum += rating_error;
         }
+        if player.converged_after.is_none() && rating_error < config.placement_convergence_error {
+            player.converged_after = Some(player.matches_played);
+            placement.converged_players += 1;
+            placement.matches_to_converge_sum += player.matches_played;
+        }
+    }
 
-        let skill_diff = game_match.team_skills[0] - game_match.team_skills[1];
-        let skill_diff_abs = skill_diff.abs();
-        
-        // Use configurable gamma for win probability calculation
-        let gamma = self.config.gamma;
-        let p_team0_wins = (1.0 / (1.0 + (-gamma * skill_diff).exp())).clamp(0.0, 1.0);
+    /// Take a player offline at the end of their session, recording the session length
+    fn end_session(stats: &mut SimulationStats, total_matches_in_sessions: &mut usize, player: &mut Player, tick: u64) {
+        // Preserve session experience for return probability calculation
+        player.last_session_experience = player.recent_experience.clone();
+        player.last_session_end_time = Some(tick);
         
-        // Ensure probability is valid (not NaN or infinite)
-        let p_team0_wins = if p_team0_wins.is_finite() { p_team0_wins } else { 0.5 };
+        // Clear current session experience (will be rebuilt in next session)
+        player.recent_experience.clear();
         
-        let winning_team = if rng.gen_bool(p_team0_wins) { 0 } else { 1 };
+        player.state = PlayerState::Offline;
         
-        // Use win_probability_imbalance already calculated in match (or recalculate if needed)
-        let win_prob_imbalance = game_match.win_probability_imbalance;
+        // Record session completion
+        let session_length = player.matches_in_session;
+        if session_length > 0 {
+            *total_matches_in_sessions += session_length;
+            stats.total_sessions_completed += 1;
+            
+            // Record in distribution (extend if needed)
+            while stats.session_length_distribution.len() <= session_length {
+                stats.session_length_distribution.push(0);
+            }
+            stats.session_length_distribution[session_length] += 1;
+            
+            // Clear session tracking
+            player.session_start_time = None;
+            player.matches_in_session = 0;
+        }
+    }
+
+    /// Advance running scores and let players abandon matches in progress
+    /// The running score is the match's played-out scoreline scaled by its progress, so players
+    /// react to the score that is reported at the end. Players leave with `base_quit_rate`, plus
+    /// deficit- and blowout-driven terms while their team trails; a team that empties forfeits
+    /// and the match ends this tick.
+    pub fn process_abandonment(&mut self, rng: &mut impl Rng) {
+        if !self.config.abandonment_config.enabled {
+            return;
+        }
+        let mut match_ids: Vec<usize> = self.matches
+            .iter()
+            .filter(|(_, m)| m.teams.len() == 2)
+            .map(|(&id, _)| id)
+            .collect();
+        match_ids.sort_unstable();
+
+        for match_id in match_ids {
+            let Some(mut game_match) = self.matches.remove(&match_id) else {
+                continue;
+            };
+            // Matches formed before abandonment was enabled start scoring now
+            if game_match.running_score.len() != 2 {
+                game_match.running_score = vec![0, 0];
+            }
+            if let Some(outcome) = &game_match.outcome {
+                let progress = game_match.progress(self.current_time).min(1.0);
+                for (score, &final_score) in game_match.running_score.iter_mut().zip(&outcome.scoreline.team_scores) {
+                    *score = (final_score as f64 * progress).floor() as usize;
+                }
+            }
+
+            let config = &self.config.abandonment_config;
+            let blowout_prob = Self::blowout_probability(&self.config, &game_match);
+            let score = &game_match.running_score;
+            // Scored over at least 10 points so the first exchanges don't look like a rout
+            let total_points = ((score[0] + score[1]) as f64).max(10.0);
+            let mut leavers = Vec::new();
+            for (team_idx, team) in game_match.teams.iter().enumerate() {
+                let deficit = (score[1 - team_idx] as f64 - score[team_idx] as f64) / total_points;
+                let mut quit_prob = config.base_quit_rate;
+                if deficit > 0.0 {
+                    quit_prob += config.deficit_quit_rate * deficit + config.blowout_quit_rate * blowout_prob;
+                }
+                let quit_prob = quit_prob.clamp(0.0, 1.0);
+                for &player_id in team {
+                    if rng.gen_bool(quit_prob) {
+                        leavers.push((team_idx, player_id, deficit));
+                    }
+                }
+            }
+
+            if !leavers.is_empty() {
+                if game_match.abandoned_players.is_empty() {
+                    self.stats.abandonment.matches_with_abandonment += 1;
+                }
+                for &(team_idx, player_id, deficit) in &leavers {
+                    game_match.teams[team_idx].retain(|&id| id != player_id);
+                    game_match.abandoned_players.push(player_id);
+                    self.abandon_match(player_id, &game_match, deficit, rng);
+                }
+                if game_match.teams.iter().any(|team| team.is_empty()) {
+                    // Forfeit: the match completes this tick
+                    game_match.expected_duration = self.current_time - game_match.start_time;
+                    self.stats.abandonment.forfeits += 1;
+                } else {
+                    self.update_team_strength(&mut game_match);
+                }
+            }
+            self.matches.insert(match_id, game_match);
+        }
+    }
+
+    /// A player leaves a match in progress: it counts as a loss and ends their session
+    fn abandon_match(&mut self, player_id: usize, game_match: &Match, deficit: f64, rng: &mut impl Rng) {
+        let remaining: Vec<f64> = game_match.teams
+            .iter()
+            .flatten()
+            .filter_map(|id| self.players.get(id).map(|p| p.skill))
+            .collect();
+        let lobby_avg_skill = remaining.iter().sum::<f64>() / remaining.len().max(1) as f64;
+        let Some(player) = self.players.get(&player_id) else {
+            return;
+        };
+        let experience = ExperienceVector {
+            avg_delta_ping: player.recent_delta_pings.last().copied().unwrap_or(0.0),
+            avg_search_time: player.recent_search_times.last().copied().unwrap_or(0.0),
+            // Trailing at least 3:1 on points
+            was_blowout: deficit >= 0.5,
+            won: false,
+            performance: self.generate_performance(player, lobby_avg_skill, game_match.playlist, rng),
+            placement: None,
+            eliminations: 0,
+        };
+        self.session_continues.entry(player.skill_bucket).or_insert((0, 0)).1 += 1;
+        self.stats.recent_quits.push((self.current_time, 1));
+        self.stats.abandonment.abandoned.record(&experience, false);
+        self.events.emit(self.current_time, || SimEventKind::PlayerAbandoned {
+            player_id,
+            match_id: game_match.id,
+        });
+
+        if let Some(player) = self.players.get_mut(&player_id) {
+            // An abandoned match counts as a played loss
+            player.matches_played += 1;
+            player.losses += 1;
+            player.loss_streak += 1;
+            Self::track_placement(&mut self.stats, &self.config, player);
+            player.current_match = None;
+            player.recent_experience.push(experience);
+            let window_size = self.config.retention_config.experience_window_size;
+            if player.recent_experience.len() > window_size {
+                player.recent_experience.remove(0);
+            }
+            Self::end_session(&mut self.stats, &mut self.total_matches_in_sessions, player, self.current_time);
+        }
+    }
+
+    /// Fill open slots in two-team matches in progress from the search queue
+    pub fn process_backfill(&mut self) {
+        let config = &self.config.abandonment_config;
+        if !config.enabled || !config.backfill || self.searches.is_empty() {
+            return;
+        }
+        let mut openings: Vec<BackfillOpening> = self.matches
+            .values()
+            .filter(|m| {
+                m.teams.len() == 2
+                    && m.teams.iter().all(|team| !team.is_empty())
+                    && m.progress(self.current_time) < config.backfill_cutoff
+            })
+            .filter_map(|m| {
+                let full = self.config.playlist(m.playlist).team_size;
+                let open_slots: Vec<usize> = m.teams.iter().map(|team| full.saturating_sub(team.len())).collect();
+                if open_slots.iter().all(|&open| open == 0) {
+                    return None;
+                }
+                let percentiles: Vec<f64> = m.teams
+                    .iter()
+                    .flatten()
+                    .filter_map(|id| self.players.get(id).map(|p| p.skill_percentile))
+                    .collect();
+                Some(BackfillOpening {
+                    match_id: m.id,
+                    playlist: m.playlist,
+                    data_center_id: m.data_center_id,
+                    open_slots,
+                    avg_skill_percentile: percentiles.iter().sum::<f64>() / percentiles.len().max(1) as f64,
+                })
+            })
+            .collect();
+        if openings.is_empty() {
+            return;
+        }
+        openings.sort_unstable_by_key(|o| o.match_id);
+
+        let assignments = backfill_matchmaker(&self.config).backfill(&self.searches, &mut openings, self.current_time);
+        for assignment in assignments {
+            let Some(pos) = self.searches.iter().position(|s| s.id == assignment.search_id) else {
+                continue;
+            };
+            let search = self.searches.remove(pos);
+            let Some(mut game_match) = self.matches.remove(&assignment.match_id) else {
+                continue;
+            };
+            let progress = game_match.progress(self.current_time);
+            self.stats.playlist_shares.entry(game_match.playlist).or_default().players_matched += search.size();
+            for &player_id in &search.player_ids {
+                self.join_match(player_id, game_match.id, game_match.data_center_id);
+                if let Some(map) = &game_match.map {
+                    self.record_map_play(player_id, map);
+                }
+                game_match.teams[assignment.team].push(player_id);
+                game_match.backfilled_players.push(player_id);
+                self.stats.abandonment.backfills += 1;
+                self.stats.abandonment.backfill_progress_sum += progress;
+            }
+            self.update_team_strength(&mut game_match);
+            self.events.emit(self.current_time, || SimEventKind::MatchBackfilled {
+                match_id: assignment.match_id,
+                team: assignment.team,
+                player_ids: search.player_ids.clone(),
+            });
+            self.matches.insert(assignment.match_id, game_match);
+        }
+    }
+
+    /// Recompute team skills and win-probability imbalance after the roster changed
+    fn update_team_strength(&self, game_match: &mut Match) {
+        game_match.team_skills = self.team_strengths(&game_match.teams, game_match.playlist);
+        game_match.win_probability_imbalance = Self::win_probability_imbalance(&self.config, &game_match.team_skills);
+    }
+
+    /// Mean true skill per team over the playlist's full team size
+    /// Empty slots (under-full starts, abandonment) count as the lowest possible skill, so a
+    /// short-handed team is weaker; for full teams this is the plain mean.
+    fn team_strengths(&self, teams: &[Vec<usize>], playlist: Playlist) -> Vec<f64> {
+        let full = self.config.playlist(playlist).team_size;
+        teams
+            .iter()
+            .map(|team| {
+                let present: f64 = team.iter().filter_map(|id| self.players.get(id)).map(|p| p.skill).sum();
+                let missing = full.saturating_sub(team.len()) as f64;
+                (present - missing) / full.max(team.len()).max(1) as f64
+            })
+            .collect()
+    }
+
+    /// Team 0 minus team 1 for two teams; strongest minus weakest for more
+    fn team_gap(values: &[f64]) -> f64 {
+        match values {
+            [] | [_] => 0.0,
+            [first, second] => first - second,
+            _ => {
+                let strongest = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                let weakest = values.iter().copied().fold(f64::INFINITY, f64::min);
+                strongest - weakest
+            }
+        }
+    }
+
+    /// Logistic win probability of team 0 from the team skill difference
+    fn team0_win_probability(config: &MatchmakingConfig, team_skills: &[f64]) -> f64 {
+        if team_skills.len() < 2 {
+            return 0.5;
+        }
+        let skill_diff = team_skills[0] - team_skills[1];
+        let p = (1.0 / (1.0 + (-config.gamma * skill_diff).exp())).clamp(0.0, 1.0);
+        if p.is_finite() { p } else { 0.5 }
+    }
+
+    /// Win probability per team for matches of more than two teams: a softmax of γ × team
+    /// skill, which reduces to the two-team logistic
+    fn team_win_probabilities(config: &MatchmakingConfig, team_skills: &[f64]) -> Vec<f64> {
+        let best = team_skills.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let weights: Vec<f64> = team_skills.iter().map(|s| (config.gamma * (s - best)).exp()).collect();
+        let total: f64 = weights.iter().sum();
+        weights.iter().map(|w| w / total).collect()
+    }
+
+    /// How far the favorite's win probability is above an even split (0 = even, 1 = certain)
+    fn win_probability_imbalance(config: &MatchmakingConfig, team_skills: &[f64]) -> f64 {
+        match team_skills.len() {
+            0 | 1 => 0.0,
+            2 => (Self::team0_win_probability(config, team_skills) - 0.5).abs() * 2.0,
+            n => {
+                let even = 1.0 / n as f64;
+                let favorite = Self::team_win_probabilities(config, team_skills).into_iter().fold(0.0, f64::max);
+                (favorite - even) / (1.0 - even)
+            }
+        }
+    }
+
+    /// Blowout likelihood from the team skill gap and win-probability imbalance
+    fn blowout_probability(config: &MatchmakingConfig, game_match: &Match) -> f64 {
+        // Gap between the strongest and weakest team
+        let skill_diff_abs = Self::team_gap(&game_match.team_skills).abs();
         
         // Normalize skill difference to 0-1 scale (assuming max skill diff of ~2.0)
         let normalized_skill_diff = (skill_diff_abs / 2.0).min(1.0);
         
         // Blowout probability using configurable coefficients
-        let blowout_prob = self.config.blowout_skill_coefficient * normalized_skill_diff
-            + self.config.blowout_imbalance_coefficient * win_prob_imbalance;
+        let blowout_prob = config.blowout_skill_coefficient * normalized_skill_diff
+            + config.blowout_imbalance_coefficient * game_match.win_probability_imbalance;
         
         // Ensure blowout probability is valid
         let blowout_prob = blowout_prob.clamp(0.0, 1.0);
-        let blowout_prob = if blowout_prob.is_finite() { blowout_prob } else { 0.0 };
-        
-        let is_blowout = rng.gen_bool(blowout_prob);
-        
-        // Assign blowout severity based on thresholds
-        let blowout_severity = if !is_blowout {
+        if blowout_prob.is_finite() { blowout_prob } else { 0.0 }
+    }
+
+    /// Index drawn with the given probabilities
+    fn sample_index(probabilities: &[f64], rng: &mut impl Rng) -> usize {
+        let mut roll: f64 = rng.gen();
+        probabilities
+            .iter()
+            .position(|&p| {
+                roll -= p;
+                roll < 0.0
+            })
+            .unwrap_or(probabilities.len() - 1)
+    }
+
+    /// Finishing rank per team (0 = first), drawn with Plackett–Luce over team strength
+    /// Places are filled best-first: each is won by one of the teams still unplaced, with their
+    /// softmax win probabilities. With two teams this is the logistic win probability.
+    fn finishing_ranks(config: &MatchmakingConfig, team_skills: &[f64], rng: &mut impl Rng) -> Vec<usize> {
+        let mut remaining: Vec<usize> = (0..team_skills.len()).collect();
+        let mut ranks = vec![0; team_skills.len()];
+        for rank in 0..team_skills.len() {
+            let skills: Vec<f64> = remaining.iter().map(|&team| team_skills[team]).collect();
+            let pick = Self::sample_index(&Self::team_win_probabilities(config, &skills), rng);
+            ranks[remaining.remove(pick)] = rank;
+        }
+        ranks
+    }
+
+    /// Finishing place as the share of other teams finished ahead of (1 = first, 0 = last)
+    fn finish_percentile(ranks: &[usize], team: usize) -> f64 {
+        1.0 - ranks[team] as f64 / (ranks.len() - 1).max(1) as f64
+    }
+
+    /// Expected finish percentile of a team from pairwise Plackett–Luce odds on team MMR
+    fn expected_finish_percentile(config: &MatchmakingConfig, team_mmrs: &[f64], team: usize) -> f64 {
+        let others = team_mmrs.len().saturating_sub(1).max(1) as f64;
+        team_mmrs
+            .iter()
+            .enumerate()
+            .filter(|&(other, _)| other != team)
+            .map(|(_, &mmr)| 1.0 / (1.0 + (-config.gamma * (team_mmrs[team] - mmr)).exp()))
+            .sum::<f64>()
+            / others
+    }
+
+    /// Eliminations per player in a placement match
+    /// Each knocked-out player is credited to a member of a squad that outlasted theirs, drawn
+    /// from the surviving squads' win probabilities.
+    fn assign_eliminations(config: &MatchmakingConfig, game_match: &Match, ranks: &[usize], rng: &mut impl Rng) -> HashMap<usize, usize> {
+        let mut finish_order: Vec<usize> = (0..ranks.len()).collect();
+        finish_order.sort_unstable_by_key(|&team| ranks[team]);
+
+        let mut eliminations: HashMap<usize, usize> = HashMap::new();
+        for place in (1..finish_order.len()).rev() {
+            let survivors = &finish_order[..place];
+            let skills: Vec<f64> = survivors.iter().map(|&team| game_match.team_skills[team]).collect();
+            let probabilities = Self::team_win_probabilities(config, &skills);
+            for _ in &game_match.teams[finish_order[place]] {
+                let members = &game_match.teams[survivors[Self::sample_index(&probabilities, rng)]];
+                if !members.is_empty() {
+                    *eliminations.entry(members[rng.gen_range(0..members.len())]).or_insert(0) += 1;
+                }
+            }
+        }
+        eliminations
+    }
+
+    /// Decide a match's result and play out its scoreline within `time_limit` seconds
+    /// Matches of more than two teams (and placement playlists) get a full finishing order;
+    /// placement playlists also credit eliminations, score them and last exactly `time_limit`.
+    fn play_out(&self, game_match: &Match, time_limit: f64, rng: &mut impl Rng) -> MatchOutcome {
+        let definition = self.config.playlist(game_match.playlist);
+        let battle_royale = definition.ranks_placements();
+        let ranks = (game_match.teams.len() > 2 || battle_royale)
+            .then(|| Self::finishing_ranks(&self.config, &game_match.team_skills, rng));
+        let eliminations = match &ranks {
+            Some(ranks) if battle_royale => Self::assign_eliminations(&self.config, game_match, ranks, rng),
+            _ => HashMap::new(),
+        };
+        let winning_team = self.determine_outcome(game_match, ranks.as_deref(), rng);
+
+        let scoreline = match &ranks {
+            Some(ranks) if battle_royale => scoring::from_eliminations(&game_match.teams, ranks, &eliminations, time_limit),
+            _ => {
+                let finish_order: Vec<usize> = match &ranks {
+                    Some(ranks) => {
+                        let mut order: Vec<usize> = (0..ranks.len()).collect();
+                        order.sort_unstable_by_key(|&team| ranks[team]);
+                        order
+                    }
+                    None => std::iter::once(winning_team)
+                        .chain((0..game_match.teams.len()).filter(|&team| team != winning_team))
+                        .collect(),
+                };
+                let players = game_match.teams.iter().map(Vec::len).sum::<usize>();
+                let fill = players as f64 / definition.required_players() as f64;
+                let lineup = self.lineup(game_match, rng);
+                scoring::generate(&definition.scoring, &lineup, time_limit, fill, &finish_order, rng)
+            }
+        };
+        MatchOutcome {
+            winning_team,
+            ranks,
+            eliminations,
+            scoreline,
+        }
+    }
+
+    /// Determine the winning team using skill difference
+    /// Team skills count empty slots against a team, so short-handed teams from under-full
+    /// starts or abandonment are less likely to win. Ranked matches take the winner from their
+    /// finishing order.
+    fn determine_outcome(&self, game_match: &Match, ranks: Option<&[usize]>, rng: &mut impl Rng) -> usize {
+        if game_match.team_skills.len() < 2 {
+            return 0;
+        }
+
+        // Use configurable gamma for win probability calculation
+        let mut winning_team = match ranks {
+            Some(ranks) => ranks.iter().position(|&rank| rank == 0).unwrap_or(0),
+            None => {
+                let p_team0_wins = Self::team0_win_probability(&self.config, &game_match.team_skills);
+                if rng.gen_bool(p_team0_wins) { 0 } else { 1 }
+            }
+        };
+
+        // With a running score the scoreboard decides: a team that left forfeits, otherwise the leader wins
+        if let [score0, score1] = game_match.running_score[..] {
+            if game_match.teams[1].is_empty() || (!game_match.teams[0].is_empty() && score0 > score1) {
+                winning_team = 0;
+            } else if game_match.teams[0].is_empty() || score1 > score0 {
+                winning_team = 1;
+            }
+        }
+
+        winning_team
+    }
+
+    /// Blowout severity from the scoreline margin (the winner's lead over the best other team as
+    /// a share of their combined score); None below the mild threshold
+    fn blowout_severity(config: &MatchmakingConfig, margin: f64) -> Option<BlowoutSeverity> {
+        if margin < config.blowout_mild_threshold {
             None
-        } else if blowout_prob < self.config.blowout_mild_threshold {
-            None // Too mild to classify
-        } else if blowout_prob < self.config.blowout_moderate_threshold {
+        } else if margin < config.blowout_moderate_threshold {
             Some(BlowoutSeverity::Mild)
-        } else if blowout_prob < self.config.blowout_severe_threshold {
+        } else if margin < config.blowout_severe_threshold {
             Some(BlowoutSeverity::Moderate)
         } else {
             Some(BlowoutSeverity::Severe)
-        };
-        
-        // Store severity in match
-        game_match.blowout_severity = blowout_severity;
-        
-        (winning_team, is_blowout, blowout_severity)
+        }
+    }
+
+    /// Team and player strength in a match: true skill plus per-player form noise
+    /// (`performance_noise_std`); each team adds its players' mean noise to its team skill
+    fn lineup<'a>(&self, game_match: &'a Match, rng: &mut impl Rng) -> Lineup<'a> {
+        let mut player_forms = HashMap::new();
+        let team_forms = game_match
+            .teams
+            .iter()
+            .zip(&game_match.team_skills)
+            .map(|(team, &team_skill)| {
+                let mut noise_sum = 0.0;
+                for &player_id in team {
+                    let noise = self.config.performance_noise_std * self.sample_normal(rng);
+                    noise_sum += noise;
+                    player_forms.insert(player_id, self.players.get(&player_id).map_or(0.0, |p| p.skill) + noise);
+                }
+                team_skill + noise_sum / team.len().max(1) as f64
+            })
+            .collect();
+        Lineup {
+            teams: &game_match.teams,
+            team_forms,
+            player_forms,
+        }
     }
 
     /// Calculate probability of player continuing based on experience
@@ -1089,22 +2059,27 @@ impl Simulation {
             .count() as f64 / recent.len() as f64;
         
         let win_rate = recent.iter()
-            .filter(|e| e.won)
-            .count() as f64 / recent.len() as f64;
+            .map(ExperienceVector::win_score)
+            .sum::<f64>() / recent.len() as f64;
         
         let avg_performance = recent.iter()
             .map(|e| e.performance)
             .sum::<f64>() / recent.len() as f64;
         
+        let avg_eliminations = recent.iter()
+            .map(|e| e.eliminations as f64)
+            .sum::<f64>() / recent.len() as f64;
+        
         // Apply logistic model: P(return) = σ(base + θ^T z)
-        // z = [avg_delta_ping, avg_search_time, blowout_rate, win_rate, avg_performance]
-        // θ = [theta_ping, theta_search_time, theta_blowout, theta_win_rate, theta_performance]
+        // z = [avg_delta_ping, avg_search_time, blowout_rate, win_rate, avg_performance, avg_eliminations]
+        // θ = [theta_ping, theta_search_time, theta_blowout, theta_win_rate, theta_performance, theta_eliminations]
         let logit = config.base_continue_prob
             + config.theta_ping * avg_delta_ping
             + config.theta_search_time * avg_search_time
             + config.theta_blowout * blowout_rate
             + config.theta_win_rate * win_rate
-            + config.theta_performance * avg_performance;
+            + config.theta_performance * avg_performance
+            + config.theta_eliminations * avg_eliminations;
         
         // Logistic function: σ(x) = 1 / (1 + exp(-x))
         let prob = 1.0 / (1.0 + (-logit).exp());
@@ -1150,22 +2125,27 @@ impl Simulation {
             .count() as f64 / recent.len() as f64;
         
         let win_rate = recent.iter()
-            .filter(|e| e.won)
-            .count() as f64 / recent.len() as f64;
+            .map(ExperienceVector::win_score)
+            .sum::<f64>() / recent.len() as f64;
         
         let avg_performance = recent.iter()
             .map(|e| e.performance)
             .sum::<f64>() / recent.len() as f64;
         
+        let avg_eliminations = recent.iter()
+            .map(|e| e.eliminations as f64)
+            .sum::<f64>() / recent.len() as f64;
+        
         // Apply logistic model: P(continue) = σ(base + θ^T z)
-        // z = [avg_delta_ping, avg_search_time, blowout_rate, win_rate, avg_performance]
-        // θ = [theta_ping, theta_search_time, theta_blowout, theta_win_rate, theta_performance]
+        // z = [avg_delta_ping, avg_search_time, blowout_rate, win_rate, avg_performance, avg_eliminations]
+        // θ = [theta_ping, theta_search_time, theta_blowout, theta_win_rate, theta_performance, theta_eliminations]
         let logit = config.base_continue_prob
             + config.theta_ping * avg_delta_ping
             + config.theta_search_time * avg_search_time
             + config.theta_blowout * blowout_rate
             + config.theta_win_rate * win_rate
-            + config.theta_performance * avg_performance;
+            + config.theta_performance * avg_performance
+            + config.theta_eliminations * avg_eliminations;
         
         // Logistic function: σ(x) = 1 / (1 + exp(-x))
         let prob = 1.0 / (1.0 + (-logit).exp());
@@ -1184,25 +2164,34 @@ impl Simulation {
     pub fn tick(&mut self) {
         let mut rng = StdRng::seed_from_u64(self.rng_seed.wrapping_add(self.current_time));
 
-        // 1. Process arrivals (players coming online)
+        // 1. Create new accounts
+        self.process_acquisition(&mut rng);
+
+        // 2. Process arrivals (players coming online)
         self.process_arrivals(&mut rng);
 
-        // 2. Process search starts (lobby players starting to search)
+        // 3. Process search starts (lobby players starting to search)
         self.process_search_starts(&mut rng);
 
-        // 3. Run matchmaking
+        // 4. Run matchmaking
         let match_results = self.run_matchmaking();
 
-        // 4. Create matches from results
+        // 5. Create matches from results
         self.create_matches(match_results, &mut rng);
 
-        // 5. Process match completions
+        // 6. Let players abandon matches in progress
+        self.process_abandonment(&mut rng);
+
+        // 7. Backfill open slots from the queue
+        self.process_backfill();
+
+        // 8. Process match completions
         self.process_match_completions(&mut rng);
 
-        // 6. Update statistics
+        // 9. Update statistics
         self.update_stats();
 
-        // 7. Advance time
+        // 10. Advance time
         self.current_time += 1;
     }
 
@@ -1213,10 +2202,64 @@ impl Simulation {
         }
     }
 
+    /// Keep at most a Poisson(`rate`) sample of the returning players
+    fn cap_arrivals(&self, mut arrivals: Vec<usize>, rate: f64, rng: &mut impl Rng) -> Vec<usize> {
+        let num_arrivals = self.poisson_sample(rate, rng);
+        if arrivals.len() > num_arrivals {
+            // Shuffle and take first N
+            use rand::seq::SliceRandom;
+            arrivals.as_mut_slice().shuffle(rng);
+            arrivals.truncate(num_arrivals);
+        }
+        arrivals
+    }
+
+    /// Global arrival rate at the current tick
+    fn current_arrival_rate(&self) -> f64 {
+        self.arrival_schedule
+            .as_ref()
+            .and_then(|schedule| schedule.rate_at(self.current_time))
+            .unwrap_or(self.arrival_rate)
+    }
+
+    /// Expected arrivals per tick for each region: its population share of the global rate,
+    /// scaled by its diurnal curve at the current time
+    fn regional_arrival_rates(&self) -> Vec<(Region, f64)> {
+        let rate = self.current_arrival_rate();
+        let total = self.players.len().max(1) as f64;
+        let hour = self
+            .diurnal_arrivals
+            .as_ref()
+            .map(|d| d.hour_utc(self.current_time, self.config.tick_interval));
+
+        let mut counts = [0usize; Region::ALL.len()];
+        for player in self.players.values() {
+            if let Some(i) = Region::ALL.iter().position(|r| *r == player.region) {
+                counts[i] += 1;
+            }
+        }
+
+        Region::ALL
+            .into_iter()
+            .zip(counts)
+            .map(|(region, count)| {
+                let multiplier = match (&self.diurnal_arrivals, hour) {
+                    (Some(diurnal), Some(hour)) => diurnal.multiplier(region, hour),
+                    _ => 1.0,
+                };
+                (region, rate * count as f64 / total * multiplier)
+            })
+            .collect()
+    }
+
     /// Update simulation statistics
     fn update_stats(&mut self) {
         self.stats.time_elapsed = self.current_time as f64 * self.config.tick_interval;
         self.stats.ticks = self.current_time;
+        self.stats.hour_of_day_utc = self
+            .diurnal_arrivals
+            .as_ref()
+            .map(|d| d.hour_utc(self.current_time, self.config.tick_interval));
         self.stats.skill_evolution_enabled = self.config.enable_skill_evolution;
         
         // Count players by state
@@ -1236,6 +2279,15 @@ impl Simulation {
         
         self.stats.active_matches = self.matches.len();
         
+        if !self.players.is_empty() {
+            self.stats.avg_rating_deviation = self.players_in_order()
+                .map(|p| p.rating_state.deviation)
+                .sum::<f64>() / self.players.len() as f64;
+            self.stats.avg_rating_error = self.players_in_order()
+                .map(|p| (p.mmr - p.skill).abs())
+                .sum::<f64>() / self.players.len() as f64;
+        }
+        
         // Calculate percentiles
         if !self.stats.search_time_samples.is_empty() {
             let mut sorted = self.stats.search_time_samples.clone();
@@ -1261,6 +2313,11 @@ impl Simulation {
                 / self.stats.skill_disparity_samples.len() as f64;
         }
         
+        if !self.stats.match_quality_samples.is_empty() {
+            self.stats.avg_match_quality = self.stats.match_quality_samples.iter().sum::<f64>()
+                / self.stats.match_quality_samples.len() as f64;
+        }
+        
         // Blowout rate
         if self.stats.total_matches > 0 {
             self.stats.blowout_rate = self.stats.blowout_count as f64 / self.stats.total_matches as f64;
@@ -1281,6 +2338,17 @@ impl Simulation {
         
         // Calculate regional statistics
         self.update_region_stats();
+
+        // New accounts vs. veterans
+        self.update_cohort_stats();
+        self.update_placement_stats();
+        self.update_smurf_stats();
+        self.update_abandonment_stats();
+        self.update_fill_stats();
+        self.update_playlist_share_stats();
+        self.update_map_stats();
+        self.update_battle_royale_stats();
+        self.update_scoreline_stats();
         
         // Calculate retention metrics
         self.update_retention_stats();
@@ -1482,17 +2550,22 @@ impl Simulation {
 
     fn update_bucket_stats(&mut self) {
         self.stats.bucket_stats.clear();
-        
-        for bucket in 1..=self.config.num_skill_buckets {
-            let bucket_players: Vec<&Player> = self.players
-                .values()
-                .filter(|p| p.skill_bucket == bucket)
-                .collect();
-            
+
+        // Group players by bucket (in id order), then compute each bucket independently
+        let num_buckets = self.config.num_skill_buckets;
+        let mut players_by_bucket: Vec<Vec<&Player>> = vec![Vec::new(); num_buckets];
+        for player in self.players_in_order() {
+            if (1..=num_buckets).contains(&player.skill_bucket) {
+                players_by_bucket[player.skill_bucket - 1].push(player);
+            }
+        }
+
+        let bucket_stats = par::map(&players_by_bucket, |bucket_players| {
             if bucket_players.is_empty() {
-                continue;
+                return None;
             }
-            
+            let bucket = bucket_players[0].skill_bucket;
+
             let player_count = bucket_players.len();
             
             let avg_search_time = bucket_players.iter()
@@ -1530,8 +2603,8 @@ impl Simulation {
             } else {
                 1.0
             };
-            
-            self.stats.bucket_stats.insert(bucket, BucketStats {
+
+            Some(BucketStats {
                 bucket_id: bucket,
                 player_count,
                 avg_search_time,
@@ -1539,7 +2612,153 @@ impl Simulation {
                 win_rate,
                 avg_kd,
                 matches_played: total_matches,
-            });
+            })
+        });
+
+        for stats in bucket_stats.into_iter().flatten() {
+            self.stats.bucket_stats.insert(stats.bucket_id, stats);
+        }
+    }
+
+    /// Split player statistics between new accounts and veterans
+    fn update_cohort_stats(&mut self) {
+        let threshold = self.acquisition.new_player_matches;
+        let (new_players, veterans): (Vec<&Player>, Vec<&Player>) =
+            self.players_in_order().partition(|p| p.is_new_player(threshold));
+        let (new_stats, veteran_stats) = (Self::cohort_stats(&new_players), Self::cohort_stats(&veterans));
+        self.stats.new_players = new_stats;
+        self.stats.veteran_players = veteran_stats;
+    }
+
+    fn update_placement_stats(&mut self) {
+        let players_in_placement = self.players.values().filter(|p| p.in_placement(&self.config)).count();
+        let placement = &mut self.stats.placement;
+        placement.players_in_placement = players_in_placement;
+        let ratio = |num: f64, den: usize| if den > 0 { num / den as f64 } else { 0.0 };
+        placement.avg_rating_error_after_placement =
+            ratio(placement.rating_error_after_placement_sum, placement.placements_completed);
+        placement.avg_matches_to_converge =
+            ratio(placement.matches_to_converge_sum as f64, placement.converged_players);
+        placement.blowout_rate_with_placement =
+            ratio(placement.blowouts_with_placement as f64, placement.matches_with_placement);
+        placement.blowout_rate_without_placement =
+            ratio(placement.blowouts_without_placement as f64, placement.matches_without_placement);
+    }
+
+    fn update_smurf_stats(&mut self) {
+        let smurfs = &mut self.stats.smurfs;
+        smurfs.smurf_count = 0;
+        smurfs.smurfs_online = 0;
+        let mut rating_gap_sum = 0.0;
+        for player in self.players.values().filter(|p| p.smurf) {
+            smurfs.smurf_count += 1;
+            smurfs.smurfs_online += (player.state != PlayerState::Offline) as usize;
+            rating_gap_sum += player.skill - player.mmr;
+        }
+        smurfs.avg_smurf_rating_gap = rating_gap_sum / smurfs.smurf_count.max(1) as f64;
+        smurfs.victims.update_rates();
+        smurfs.teammates.update_rates();
+        smurfs.unaffected.update_rates();
+    }
+
+    fn update_fill_stats(&mut self) {
+        let fill = &mut self.stats.fill;
+        let ratio = |num: f64, den: usize| if den > 0 { num / den as f64 } else { 0.0 };
+        fill.avg_fill_ratio = ratio(fill.fill_ratio_sum, fill.full_matches + fill.underfull_matches);
+        fill.blowout_rate_full = ratio(fill.blowouts_full as f64, fill.completed_full);
+        fill.blowout_rate_underfull = ratio(fill.blowouts_underfull as f64, fill.completed_underfull);
+        fill.short_team_win_rate = ratio(fill.short_team_wins as f64, fill.uneven_matches);
+        fill.per_playlist_fill_ratio = fill
+            .per_playlist_fill_sums
+            .iter()
+            .map(|(&playlist, &(count, sum))| (playlist, ratio(sum, count)))
+            .collect();
+    }
+
+    fn update_playlist_share_stats(&mut self) {
+        let shares = &mut self.stats.playlist_shares;
+        let total_matched: usize = shares.values().map(|s| s.players_matched).sum();
+        // Summed in playlist order so the float total doesn't depend on map iteration order
+        let total_preference: f64 = self
+            .config
+            .playlist_names()
+            .filter_map(|playlist| shares.get(&playlist))
+            .map(|s| s.preference_weight)
+            .sum();
+        for share in shares.values_mut() {
+            share.fill_share = share.players_matched as f64 / total_matched.max(1) as f64;
+            share.preference_share = if total_preference > 0.0 { share.preference_weight / total_preference } else { 0.0 };
+        }
+    }
+
+    fn update_map_stats(&mut self) {
+        let map_stats = &mut self.stats.maps;
+        let ratio = |num: usize, den: usize| num as f64 / den.max(1) as f64;
+        map_stats.lobby_quit_rate = ratio(map_stats.lobby_quits, map_stats.players_offered);
+        map_stats.disliked_play_rate = ratio(map_stats.disliked_plays, map_stats.player_plays);
+        map_stats.repeat_play_rate = ratio(map_stats.repeat_plays, map_stats.player_plays);
+    }
+
+    fn update_battle_royale_stats(&mut self) {
+        let battle_royale = &mut self.stats.battle_royale;
+        let matches = battle_royale.matches.max(1) as f64;
+        battle_royale.avg_squads = battle_royale.squads_sum as f64 / matches;
+        battle_royale.strongest_squad_win_rate = battle_royale.strongest_squad_wins as f64 / matches;
+        let player_matches = battle_royale.top_half.samples + battle_royale.bottom_half.samples;
+        battle_royale.avg_eliminations = battle_royale.eliminations_sum as f64 / player_matches.max(1) as f64;
+        battle_royale.top_half.update_rates();
+        battle_royale.bottom_half.update_rates();
+    }
+
+    fn update_scoreline_stats(&mut self) {
+        let scorelines = &mut self.stats.scorelines;
+        let matches = scorelines.matches.max(1) as f64;
+        scorelines.avg_length_seconds = scorelines.length_sum_seconds / matches;
+        scorelines.score_limit_rate = scorelines.score_limit_endings as f64 / matches;
+        scorelines.mercy_rate = scorelines.mercy_endings as f64 / matches;
+        scorelines.avg_blowout_length_seconds = scorelines.blowout_length_sum_seconds / scorelines.blowouts.max(1) as f64;
+        let non_blowouts = scorelines.matches - scorelines.blowouts;
+        scorelines.avg_non_blowout_length_seconds =
+            (scorelines.length_sum_seconds - scorelines.blowout_length_sum_seconds) / non_blowouts.max(1) as f64;
+        scorelines.per_playlist_avg_length_seconds = scorelines
+            .per_playlist_length_sums
+            .iter()
+            .map(|(&playlist, &(count, sum))| (playlist, sum / count.max(1) as f64))
+            .collect();
+        scorelines.avg_margin = scorelines.margin_sum / matches;
+        let player_matches = scorelines.player_matches.max(1) as f64;
+        scorelines.avg_kills = scorelines.kills_sum as f64 / player_matches;
+        scorelines.avg_deaths = scorelines.deaths_sum as f64 / player_matches;
+    }
+
+    fn update_abandonment_stats(&mut self) {
+        let abandonment = &mut self.stats.abandonment;
+        let player_matches = abandonment.abandoned.samples + abandonment.backfilled.samples + abandonment.full_match.samples;
+        abandonment.abandonments = abandonment.abandoned.samples;
+        abandonment.abandon_rate = abandonment.abandoned.samples as f64 / player_matches.max(1) as f64;
+        abandonment.avg_backfill_progress = abandonment.backfill_progress_sum / abandonment.backfills.max(1) as f64;
+        abandonment.abandoned.update_rates();
+        abandonment.backfilled.update_rates();
+        abandonment.full_match.update_rates();
+    }
+
+    fn cohort_stats(players: &[&Player]) -> CohortStats {
+        let mean = |values: &mut dyn Iterator<Item = f64>| {
+            let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+            if count > 0 { sum / count as f64 } else { 0.0 }
+        };
+        let (wins, losses) = players.iter().fold((0, 0), |(w, l), p| (w + p.wins, l + p.losses));
+
+        CohortStats {
+            player_count: players.len(),
+            players_online: players.iter().filter(|p| p.state != PlayerState::Offline).count(),
+            avg_matches_played: mean(&mut players.iter().map(|p| p.matches_played as f64)),
+            avg_search_time: mean(&mut players.iter().flat_map(|p| p.recent_search_times.iter().copied())),
+            avg_delta_ping: mean(&mut players.iter().flat_map(|p| p.recent_delta_pings.iter().copied())),
+            win_rate: if wins + losses > 0 { wins as f64 / (wins + losses) as f64 } else { 0.0 },
+            blowout_rate: mean(&mut players.iter().flat_map(|p| p.recent_blowouts.iter().map(|&b| if b { 1.0 } else { 0.0 }))),
+            avg_rating_error: mean(&mut players.iter().map(|p| (p.mmr - p.skill).abs())),
+            avg_skill: mean(&mut players.iter().map(|p| p.skill)),
         }
     }
 
@@ -1547,13 +2766,15 @@ impl Simulation {
     fn update_region_stats(&mut self) {
         self.stats.region_stats.clear();
         
-        // Group players by region
-        let mut players_by_region: HashMap<Region, Vec<&Player>> = HashMap::new();
-        for player in self.players.values() {
-            players_by_region
-                .entry(player.region)
-                .or_insert_with(Vec::new)
-                .push(player);
+        // Group players by region (in id order)
+        let mut players_by_region: Vec<(Region, Vec<&Player>)> = Region::ALL
+            .into_iter()
+            .map(|region| (region, Vec::new()))
+            .collect();
+        for player in self.players_in_order() {
+            if let Some((_, region_players)) = players_by_region.iter_mut().find(|(r, _)| *r == player.region) {
+                region_players.push(player);
+            }
         }
         
         // Calculate cross-region match rate
@@ -1565,9 +2786,18 @@ impl Simulation {
             0.0
         };
         
+        let arrival_rates = self.regional_arrival_rates();
+
         // Calculate stats per region
-        for (region, region_players) in players_by_region {
+        let region_stats = par::map(&players_by_region, |(region, region_players)| {
+            let region = *region;
+            if region_players.is_empty() {
+                return None;
+            }
             let player_count = region_players.len();
+            let players_online = region_players.iter().filter(|p| p.state != PlayerState::Offline).count();
+            let players_searching = region_players.iter().filter(|p| p.state == PlayerState::Searching).count();
+            let arrival_rate = arrival_rates.iter().find(|(r, _)| *r == region).map_or(0.0, |(_, rate)| *rate);
             
             // Calculate average search time for this region
             let search_times: Vec<f64> = region_players.iter()
@@ -1608,14 +2838,21 @@ impl Simulation {
             // TODO: Could track per-region blowout counts if needed
             let blowout_rate = self.stats.blowout_rate;
             
-            self.stats.region_stats.insert(region, RegionStats {
+            Some((region, RegionStats {
                 player_count,
                 avg_search_time,
                 avg_delta_ping,
                 blowout_rate,
                 active_matches,
                 cross_region_match_rate,
-            });
+                players_online,
+                players_searching,
+                arrival_rate,
+            }))
+        });
+
+        for (region, stats) in region_stats.into_iter().flatten() {
+            self.stats.region_stats.insert(region, stats);
         }
     }
 
@@ -1647,11 +2884,40 @@ impl Simulation {
         }).unwrap_or_default()
     }
 
+    /// Start or stop collecting events for `flush_events`
+    pub fn set_event_recording(&mut self, enabled: bool) {
+        self.events.set_enabled(enabled);
+    }
+
+    /// Forward the events emitted since the last flush to every sink, in emission order
+    pub fn flush_events(&mut self, sinks: &mut [&mut dyn EventSink]) {
+        for event in self.events.take() {
+            for sink in sinks.iter_mut() {
+                sink.record(&event);
+            }
+        }
+    }
+
     /// Set arrival rate
     pub fn set_arrival_rate(&mut self, rate: f64) {
         self.arrival_rate = rate;
     }
 
+    /// Set or clear the time-varying arrival schedule
+    pub fn set_arrival_schedule(&mut self, schedule: Option<ArrivalSchedule>) {
+        self.arrival_schedule = schedule;
+    }
+
+    /// Configure new-account acquisition (a zero rate turns it off)
+    pub fn set_acquisition(&mut self, acquisition: AcquisitionConfig) {
+        self.acquisition = acquisition;
+    }
+
+    /// Set or clear the per-region time-of-day arrival curves
+    pub fn set_diurnal_arrivals(&mut self, diurnal: Option<DiurnalArrivals>) {
+        self.diurnal_arrivals = diurnal;
+    }
+
     /// Get skill distribution data
     pub fn get_skill_distribution(&self) -> Vec<(f64, usize)> {
         let mut buckets: Vec<usize> = vec![0; 20];
@@ -1816,9 +3082,46 @@ impl Simulation {
         Ok(())
     }
 
-    /// Update config parameter
-    pub fn update_config(&mut self, config: MatchmakingConfig) {
+    /// Replace the matchmaking config
+    /// Playlists still in use (preferred by a player or party, searched for, or being played)
+    /// cannot be removed. Data centers get default server capacity for newly defined playlists;
+    /// existing players keep their playlist preferences.
+    pub fn update_config(&mut self, config: MatchmakingConfig) -> Result<(), String> {
+        let defined: HashSet<Playlist> = config.playlist_names().collect();
+        let mut removed_in_use: Vec<Playlist> = self
+            .players
+            .values()
+            .flat_map(|p| p.preferred_playlists.iter().copied())
+            .chain(self.parties.values().flat_map(|p| p.preferred_playlists.iter().copied()))
+            .chain(self.searches.iter().flat_map(|s| s.acceptable_playlists.iter().copied()))
+            .chain(self.matches.values().map(|m| m.playlist))
+            .filter(|playlist| !defined.contains(playlist))
+            .collect();
+        removed_in_use.sort_unstable();
+        removed_in_use.dedup();
+        if !removed_in_use.is_empty() {
+            let names: Vec<String> = removed_in_use.iter().map(|p| p.to_string()).collect();
+            return Err(format!("Cannot remove playlists still in use: {}", names.join(", ")));
+        }
+
+        for dc in &mut self.data_centers {
+            for definition in &config.playlists {
+                dc.server_capacity
+                    .entry(definition.name)
+                    .or_insert_with(|| DataCenter::default_capacity(definition));
+                dc.busy_servers.entry(definition.name).or_insert(0);
+            }
+        }
         self.config = config;
+        Ok(())
+    }
+
+    /// Select the matchmaking strategy by name
+    pub fn set_matchmaking_strategy(&mut self, name: &str) -> Result<(), String> {
+        let kind = MatchmakingStrategyKind::from_name(name)
+            .ok_or_else(|| format!("Unknown matchmaking strategy: {}", name))?;
+        self.config.matchmaking_strategy = kind;
+        Ok(())
     }
 }
 
@@ -1843,6 +3146,42 @@ mod tests {
         sim
     }
 
+    /// Time one matchmaking tick over a large queue, with and without the candidate index
+    /// Run with `cargo test --release bench_candidate_index -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_candidate_index_large_population() {
+        for (population, searching) in [(100_000, 5_000), (100_000, 10_000), (200_000, 20_000)] {
+            for use_candidate_index in [true, false] {
+                let config = MatchmakingConfig {
+                    use_candidate_index,
+                    ..MatchmakingConfig::default()
+                };
+                let mut sim = Simulation::new(config, 42);
+                sim.init_default_data_centers();
+                sim.generate_population(population, None);
+
+                let mut solo_ids: Vec<usize> = sim.players.values()
+                    .filter(|p| p.party_id.is_none())
+                    .map(|p| p.id)
+                    .collect();
+                solo_ids.sort_unstable();
+                for &id in solo_ids.iter().take(searching) {
+                    sim.players.get_mut(&id).unwrap().state = PlayerState::InLobby;
+                    sim.start_search(id);
+                }
+                sim.current_time = 4;
+
+                let start = std::time::Instant::now();
+                let results = sim.run_matchmaking();
+                println!(
+                    "players={} searches={} index={} matches={} elapsed={:?}",
+                    population, searching, use_candidate_index, results.len(), start.elapsed()
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_create_party() {
         let mut sim = create_test_simulation();
@@ -2043,4 +3382,662 @@ mod tests {
         let expected_disparity = 0.7 - 0.3;
         assert!((party.skill_disparity - expected_disparity).abs() < 0.001);
     }
+
+    #[test]
+    fn test_percentiles_follow_mmr_not_true_skill() {
+        let mut sim = create_test_simulation();
+        let ids: Vec<usize> = sim.players.keys().copied().collect();
+        
+        // Invert the estimate: the truly best player is rated worst and vice versa
+        for &pid in &ids {
+            let player = sim.players.get_mut(&pid).unwrap();
+            player.mmr = -player.skill;
+        }
+        sim.update_skill_percentiles();
+        
+        let best = ids.iter().max_by(|a, b| sim.players[a].skill.partial_cmp(&sim.players[b].skill).unwrap()).unwrap();
+        let worst = ids.iter().min_by(|a, b| sim.players[a].skill.partial_cmp(&sim.players[b].skill).unwrap()).unwrap();
+        assert!(sim.players[best].skill_percentile < 0.05);
+        assert!(sim.players[worst].skill_percentile > 0.95);
+        assert!(sim.stats.avg_percentile_error > 0.4);
+    }
+
+    /// (id, state, MMR bits, wins, matches played) per player, in id order
+    type PlayerOutcomes = Vec<(usize, PlayerState, u64, usize, usize)>;
+
+    /// Run a seeded simulation and capture its stats and per-player outcomes
+    fn seeded_run_state(seed: u64) -> (serde_json::Value, PlayerOutcomes) {
+        let config = MatchmakingConfig {
+            party_player_fraction: 0.3,
+            ..Default::default()
+        };
+        let mut sim = Simulation::new(config, seed);
+        sim.init_default_data_centers();
+        sim.generate_population(3000, None);
+        sim.set_arrival_rate(200.0);
+        sim.run(120);
+        assert!(sim.stats.total_matches > 0);
+        let players = sim.players_in_order()
+            .map(|p| (p.id, p.state, p.mmr.to_bits(), p.wins, p.matches_played))
+            .collect();
+        (serde_json::to_value(&sim.stats).unwrap(), players)
+    }
+
+    #[test]
+    fn test_same_seed_reproduces_run() {
+        assert_eq!(seeded_run_state(7), seeded_run_state(7));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_run_independent_of_thread_count() {
+        let run_with_threads = |threads: usize| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| seeded_run_state(11))
+        };
+        assert_eq!(run_with_threads(1), run_with_threads(4));
+    }
+
+    #[test]
+    fn test_event_stream_matches_stats() {
+        use crate::events::{RingBufferSink, SimEventKind};
+
+        let mut sim = create_test_simulation();
+        sim.set_arrival_rate(50.0);
+        sim.set_event_recording(true);
+        let mut buffer = RingBufferSink::new(usize::MAX);
+        for _ in 0..150 {
+            sim.tick();
+            sim.flush_events(&mut [&mut buffer]);
+        }
+        let events = buffer.drain();
+
+        let count = |pred: fn(&SimEventKind) -> bool| events.iter().filter(|e| pred(&e.kind)).count();
+        assert_eq!(count(|k| matches!(k, SimEventKind::MatchFormed { .. })), sim.stats.total_matches);
+        let completed = count(|k| matches!(k, SimEventKind::MatchCompleted { .. }));
+        assert_eq!(completed, sim.stats.total_matches - sim.matches.len());
+        assert_eq!(
+            count(|k| matches!(k, SimEventKind::SearchStarted { .. })),
+            sim.next_search_id
+        );
+        let quits = count(|k| matches!(k, SimEventKind::PlayerQuit { .. }));
+        let returns = count(|k| matches!(k, SimEventKind::PlayerReturned { .. }));
+        assert!(completed > 0 && quits > 0);
+        assert!(returns <= quits);
+        assert!(count(|k| matches!(k, SimEventKind::SearchExpanded { .. })) > 0);
+        assert!(events.windows(2).all(|w| w[0].tick <= w[1].tick));
+    }
+
+    #[test]
+    fn test_diurnal_arrivals_follow_regional_time_of_day() {
+        use crate::events::{RingBufferSink, SimEventKind};
+
+        let mut sim = Simulation::new(MatchmakingConfig::default(), 9);
+        sim.init_default_data_centers();
+        sim.generate_population(3000, None);
+        sim.set_arrival_rate(150.0);
+        // 12:00 UTC: evening in Asia-Pacific, early morning in North America
+        sim.set_diurnal_arrivals(Some(DiurnalArrivals {
+            start_hour_utc: 12.0,
+            day_length_ticks: 96,
+            ..DiurnalArrivals::typical()
+        }));
+        sim.set_event_recording(true);
+        let mut buffer = RingBufferSink::new(usize::MAX);
+        for _ in 0..8 {
+            sim.tick();
+            sim.flush_events(&mut [&mut buffer]);
+        }
+
+        let events = buffer.drain();
+
+        let population = |region| sim.players.values().filter(|p| p.region == region).count() as f64;
+        let arrivals_per_capita = |region| {
+            let arrivals = events
+                .iter()
+                .filter(|e| match e.kind {
+                    SimEventKind::PlayerArrived { player_id } => sim.players[&player_id].region == region,
+                    _ => false,
+                })
+                .count();
+            arrivals as f64 / population(region)
+        };
+        let rate = |region| sim.stats.region_stats[&region].arrival_rate / population(region);
+
+        assert!(rate(Region::AsiaPacific) > 2.5 * rate(Region::NorthAmerica));
+        assert!(arrivals_per_capita(Region::AsiaPacific) > 2.0 * arrivals_per_capita(Region::NorthAmerica));
+        assert_eq!(sim.stats.hour_of_day_utc, Some(13.75));
+    }
+
+    #[test]
+    fn test_acquisition_adds_cold_start_players() {
+        let mut sim = create_test_simulation();
+        sim.generate_population(900, None);
+        sim.set_arrival_rate(40.0);
+        sim.set_acquisition(AcquisitionConfig {
+            rate: 5.0,
+            ..Default::default()
+        });
+        sim.run(60);
+
+        let acquired: Vec<&Player> = sim.players.values().filter(|p| p.created_at.is_some()).collect();
+        assert_eq!(acquired.len(), sim.stats.total_acquired);
+        assert_eq!(sim.players.len(), 1000 + acquired.len());
+        assert!(acquired.len() > 200);
+        for player in &acquired {
+            assert!(player.id >= 1000);
+            assert_eq!(player.dc_pings.len(), sim.data_centers.len());
+            assert!(player.best_dc.is_some());
+        }
+        // Percentiles cover the grown population
+        let mut percentiles: Vec<f64> = sim.players.values().map(|p| p.skill_percentile).collect();
+        percentiles.sort_by(f64::total_cmp);
+        assert!((percentiles[0] - 0.5 / sim.players.len() as f64).abs() < 1e-12);
+
+        let (new, veterans) = (&sim.stats.new_players, &sim.stats.veteran_players);
+        assert_eq!(new.player_count + veterans.player_count, sim.players.len());
+        assert!(new.player_count > 0 && new.players_online > 0);
+        assert!(new.avg_skill < veterans.avg_skill);
+
+        // Placement players search with widened windows and get matched alongside veterans
+        let in_placement = |id: &usize| sim.players[id].in_placement(&sim.config);
+        assert_eq!(sim.stats.placement.players_in_placement, acquired.iter().filter(|p| in_placement(&p.id)).count());
+        assert!(sim.searches.iter().all(|s| s.placement == s.player_ids.iter().any(in_placement)));
+        assert!(sim.matches.values().any(|m| m.teams.iter().flatten().any(in_placement)));
+    }
+
+    #[test]
+    fn test_smurfs_skew_victim_experience() {
+        let config = MatchmakingConfig {
+            smurf_config: SmurfConfig {
+                fraction: 0.1,
+                loss_streak: 1,
+                new_account_prob: 1.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut sim = Simulation::new(config, 42);
+        sim.init_default_data_centers();
+        sim.generate_population(1000, None);
+        sim.set_arrival_rate(50.0);
+        sim.run(250);
+
+        let smurfs: Vec<&Player> = sim.players.values().filter(|p| p.smurf).collect();
+        let stats = &sim.stats.smurfs;
+        assert_eq!(stats.smurf_count, smurfs.len());
+        assert!(smurfs.len() > 50);
+        assert!(smurfs.iter().all(|p| p.skill >= 0.6));
+        assert!(stats.smurf_matches > 0 && stats.new_accounts > 0);
+
+        // Without acquisition, only smurfs' new accounts play placement, starting from a clean record
+        let new_accounts: Vec<&&Player> = smurfs.iter().filter(|p| p.created_at.is_some()).collect();
+        assert!(!new_accounts.is_empty());
+        assert!(new_accounts.iter().all(|p| p.matches_played as u64 <= sim.current_time - p.created_at.unwrap()));
+        assert!(sim.stats.placement.players_in_placement + sim.stats.placement.placements_completed > 0);
+
+        // Facing a smurf costs wins compared with smurf-free lobbies
+        assert!(stats.victims.samples > 0 && stats.unaffected.samples > 0);
+        assert!(stats.victims.win_rate < stats.unaffected.win_rate);
+        assert!(stats.victims.blowout_rate > stats.unaffected.blowout_rate);
+    }
+
+    #[test]
+    fn test_abandoned_slots_are_backfilled() {
+        let config = MatchmakingConfig {
+            abandonment_config: AbandonmentConfig {
+                enabled: true,
+                base_quit_rate: 0.005,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut sim = Simulation::new(config, 42);
+        sim.init_default_data_centers();
+        sim.generate_population(1500, None);
+        sim.set_arrival_rate(50.0);
+        sim.run(150);
+
+        let stats = &sim.stats.abandonment;
+        assert!(stats.abandonments > 0 && stats.backfills > 0);
+        assert!(stats.abandon_rate > 0.0 && stats.abandon_rate < 1.0);
+        assert!(stats.avg_backfill_progress > 0.0 && stats.avg_backfill_progress < 0.75);
+        assert!(stats.backfilled.samples > 0);
+        // Abandoned matches count as played losses
+        assert!(sim.players.values().all(|p| p.wins + p.losses == p.matches_played));
+        for game_match in sim.matches.values() {
+            if game_match.teams.len() == 2 {
+                assert_eq!(game_match.running_score.len(), 2);
+                assert!(game_match.teams.iter().all(|team| team.len() <= sim.config.playlist(game_match.playlist).team_size));
+            }
+            let roster: Vec<usize> = game_match.teams.iter().flatten().copied().collect();
+            assert!(roster.iter().all(|id| sim.players[id].current_match == Some(game_match.id)));
+            // Backfilled players are still in the match unless they abandoned it too
+            assert!(game_match.backfilled_players
+                .iter()
+                .all(|id| roster.contains(id) || game_match.abandoned_players.contains(id)));
+        }
+    }
+
+    #[test]
+    fn test_running_score_follows_played_out_scoreline() {
+        use crate::events::{RingBufferSink, SimEventKind};
+
+        // Abandonment on, but nobody ever leaves: every match keeps its played-out scoreline
+        let config = MatchmakingConfig {
+            abandonment_config: AbandonmentConfig {
+                enabled: true,
+                base_quit_rate: 0.0,
+                deficit_quit_rate: 0.0,
+                blowout_quit_rate: 0.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut sim = Simulation::new(config, 42);
+        sim.init_default_data_centers();
+        sim.generate_population(1000, None);
+        sim.set_arrival_rate(30.0);
+        sim.set_event_recording(true);
+        let mut buffer = RingBufferSink::new(usize::MAX);
+        let mut played_out: HashMap<usize, Vec<usize>> = HashMap::new();
+        for _ in 0..120 {
+            sim.tick();
+            sim.flush_events(&mut [&mut buffer]);
+            for game_match in sim.matches.values().filter(|m| m.teams.len() == 2) {
+                let final_scores = &game_match.outcome.as_ref().unwrap().scoreline.team_scores;
+                played_out.entry(game_match.id).or_insert_with(|| final_scores.clone());
+                assert!(game_match.running_score.iter().zip(final_scores).all(|(score, final_score)| score <= final_score));
+            }
+        }
+
+        let mut completed = 0;
+        for event in buffer.drain() {
+            if let SimEventKind::MatchCompleted { match_id, scores, .. } = event.kind {
+                if let Some(final_scores) = played_out.get(&match_id) {
+                    assert_eq!(&scores, final_scores);
+                    completed += 1;
+                }
+            }
+        }
+        assert!(completed > 0);
+    }
+
+    #[test]
+    fn test_underfull_starts_in_small_population() {
+        let config = MatchmakingConfig {
+            fill_policies: [(Playlist::TEAM_DEATHMATCH, FillPolicy { min_fill_ratio: 0.75, wait_threshold: 30.0 })]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+        let mut sim = Simulation::new(config, 42);
+        sim.init_default_data_centers();
+        sim.generate_population(300, None);
+        sim.set_arrival_rate(20.0);
+        sim.run(200);
+
+        let fill = &sim.stats.fill;
+        assert!(fill.underfull_matches > 0 && fill.completed_underfull > 0);
+        assert_eq!(fill.full_matches + fill.underfull_matches, sim.stats.total_matches);
+        assert!(fill.avg_fill_ratio > 0.75 && fill.avg_fill_ratio < 1.0);
+        assert!(fill.per_playlist_fill_ratio[&Playlist::TEAM_DEATHMATCH] < 1.0);
+        for game_match in sim.matches.values().filter(|m| m.fill_ratio < 1.0) {
+            assert_eq!(game_match.playlist, Playlist::TEAM_DEATHMATCH);
+            assert!(game_match.teams.iter().flatten().count() >= 9);
+        }
+    }
+
+    #[test]
+    fn test_playlist_shares_track_preferences_and_fills() {
+        let mut sim = Simulation::new(MatchmakingConfig::default(), 42);
+        sim.init_default_data_centers();
+        sim.generate_population(500, None);
+        sim.set_arrival_rate(20.0);
+        sim.run(100);
+
+        let shares = &sim.stats.playlist_shares;
+        let fill_total: f64 = shares.values().map(|s| s.fill_share).sum();
+        let preference_total: f64 = shares.values().map(|s| s.preference_share).sum();
+        assert!((fill_total - 1.0).abs() < 1e-9);
+        assert!((preference_total - 1.0).abs() < 1e-9);
+        assert!(shares[&Playlist::TEAM_DEATHMATCH].players_matched > 0);
+        // Everyone accepts TDM, so it carries the largest preference share
+        let tdm = shares[&Playlist::TEAM_DEATHMATCH].preference_share;
+        assert!(shares.values().all(|s| s.preference_share <= tdm));
+    }
+
+    #[test]
+    fn test_map_votes_avoid_disliked_maps_and_quits_leave_lobbies() {
+        let run = |selection: MapSelection| {
+            let config = MatchmakingConfig {
+                map_config: MapConfig {
+                    enabled: true,
+                    selection,
+                    dislike_prob: 0.3,
+                    dislike_quit_prob: 0.3,
+                    ..MapConfig::default()
+                },
+                ..Default::default()
+            };
+            let mut sim = Simulation::new(config, 42);
+            sim.init_default_data_centers();
+            sim.generate_population(500, None);
+            sim.set_arrival_rate(20.0);
+            sim.run(150);
+            sim
+        };
+
+        let rotation = run(MapSelection::Rotation);
+        let maps = &rotation.stats.maps;
+        assert!(maps.lobby_quits > 0 && maps.lobby_quit_rate < 0.2);
+        assert_eq!(maps.per_map_matches.values().sum::<usize>(), rotation.stats.total_matches);
+        assert!(maps.repeat_play_rate > 0.0);
+        let history_length = rotation.config.map_config.history_length;
+        assert!(rotation.players.values().all(|p| p.recent_maps.len() <= history_length));
+        for game_match in rotation.matches.values() {
+            let pool = &rotation.config.map_config.pools[&game_match.playlist];
+            assert!(pool.contains(game_match.map.as_ref().unwrap()));
+        }
+
+        // Voting steers lobbies away from maps their players dislike
+        let vote = run(MapSelection::Vote);
+        assert!(vote.stats.maps.disliked_play_rate < maps.disliked_play_rate);
+        assert!(vote.stats.maps.lobby_quit_rate < maps.lobby_quit_rate);
+    }
+
+    #[test]
+    fn test_custom_playlists_from_definitions() {
+        use crate::events::{RingBufferSink, SimEventKind};
+
+        let gunfight = Playlist::new("Gunfight");
+        let trios = Playlist::new("Trios");
+        let config = MatchmakingConfig {
+            playlists: vec![
+                PlaylistDefinition {
+                    avg_duration_seconds: 120.0,
+                    server_cost: 0.25,
+                    preference_prob: 0.5,
+                    ..PlaylistDefinition::new(gunfight, 2, 2)
+                },
+                PlaylistDefinition {
+                    avg_duration_seconds: 120.0,
+                    preference_prob: 1.0,
+                    ..PlaylistDefinition::new(trios, 3, 3)
+                },
+            ],
+            ..Default::default()
+        };
+        let mut sim = Simulation::new(config, 42);
+        sim.init_default_data_centers();
+        assert_eq!(sim.data_centers[0].server_capacity[&gunfight], 800);
+        assert!(!sim.data_centers[0].server_capacity.contains_key(&Playlist::TEAM_DEATHMATCH));
+        sim.generate_population(500, None);
+        sim.set_arrival_rate(20.0);
+        sim.set_event_recording(true);
+        let mut buffer = RingBufferSink::new(usize::MAX);
+        for _ in 0..150 {
+            sim.tick();
+            sim.flush_events(&mut [&mut buffer]);
+        }
+
+        let mut team_counts: HashMap<Playlist, Vec<usize>> = HashMap::new();
+        let mut trios_winners = HashSet::new();
+        let mut playlists: HashMap<usize, Playlist> = HashMap::new();
+        for event in buffer.drain() {
+            match event.kind {
+                SimEventKind::MatchFormed { match_id, playlist, teams, .. } => {
+                    assert!(teams.iter().all(|team| team.len() <= sim.config.playlist(playlist).team_size));
+                    team_counts.entry(playlist).or_default().push(teams.len());
+                    playlists.insert(match_id, playlist);
+                }
+                SimEventKind::MatchCompleted { match_id, winning_team, .. } if playlists[&match_id] == trios => {
+                    trios_winners.insert(winning_team);
+                }
+                _ => {}
+            }
+        }
+        assert!(team_counts[&gunfight].iter().all(|&count| count == 2));
+        assert!(team_counts[&trios].iter().all(|&count| count == 3));
+        // Every team of a three-team match can win
+        assert_eq!(trios_winners.len(), 3);
+    }
+
+    #[test]
+    fn test_update_config_guards_playlists_in_use_and_adds_capacity() {
+        let gunfight = PlaylistDefinition {
+            avg_duration_seconds: 120.0,
+            preference_prob: 1.0,
+            ..PlaylistDefinition::new(Playlist::new("Gunfight"), 2, 2)
+        };
+        let only_gunfight = MatchmakingConfig {
+            playlists: vec![gunfight.clone()],
+            ..Default::default()
+        };
+
+        // Built-in playlists are preferred by the population and cannot be dropped
+        let mut sim = create_test_simulation();
+        let err = sim.update_config(only_gunfight.clone()).unwrap_err();
+        assert!(err.contains("TeamDeathmatch"), "{}", err);
+        assert_eq!(sim.config.playlists.len(), PlaylistDefinition::defaults().len());
+
+        // A playlist defined after the data centers were built still gets servers
+        let mut sim = Simulation::new(MatchmakingConfig::default(), 42);
+        sim.init_default_data_centers();
+        sim.update_config(only_gunfight).unwrap();
+        assert!(sim.data_centers.iter().all(|dc| dc.available_servers(&gunfight.name) > 0));
+        sim.generate_population(300, None);
+        sim.set_arrival_rate(20.0);
+        sim.run(60);
+        assert!(sim.stats.total_matches > 0);
+    }
+
+    #[test]
+    fn test_free_for_all_finishing_order() {
+        use crate::events::{RingBufferSink, SimEventKind};
+
+        let config = MatchmakingConfig {
+            playlists: vec![PlaylistDefinition {
+                avg_duration_seconds: 120.0,
+                preference_prob: 1.0,
+                ..PlaylistDefinition::new(Playlist::FREE_FOR_ALL, 12, 1)
+            }],
+            rating_system: RatingSystem::Elo,
+            gamma: 10.0,
+            ..Default::default()
+        };
+        let mut sim = Simulation::new(config, 42);
+        sim.init_default_data_centers();
+        sim.generate_population(500, None);
+        sim.set_arrival_rate(20.0);
+        sim.set_event_recording(true);
+        let mut buffer = RingBufferSink::new(usize::MAX);
+        for _ in 0..150 {
+            sim.tick();
+            sim.flush_events(&mut [&mut buffer]);
+        }
+
+        let mut lobbies: HashMap<usize, Vec<Vec<usize>>> = HashMap::new();
+        let (mut concordant, mut pairs, mut completed) = (0, 0, 0);
+        for event in buffer.drain() {
+            match event.kind {
+                SimEventKind::MatchFormed { match_id, teams, .. } => {
+                    lobbies.insert(match_id, teams);
+                }
+                SimEventKind::MatchCompleted { match_id, winning_team, placements, .. } => {
+                    let players = &lobbies[&match_id];
+                    let mut ranks = placements.clone();
+                    ranks.sort_unstable();
+                    assert_eq!(ranks, (0..players.len()).collect::<Vec<_>>());
+                    assert_eq!(placements[winning_team], 0);
+                    completed += 1;
+                    for a in 0..players.len() {
+                        for b in a + 1..players.len() {
+                            let (skill_a, skill_b) = (sim.players[&players[a][0]].skill, sim.players[&players[b][0]].skill);
+                            pairs += 1;
+                            concordant += ((skill_a > skill_b) == (placements[a] < placements[b])) as usize;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        assert!(completed > 10);
+        // The whole finishing order follows player strength, not just the winner
+        assert!(concordant as f64 > 0.6 * pairs as f64);
+        // Performance is the share of the lobby a player finished ahead of
+        let performances: Vec<f64> = sim.players.values().flat_map(|p| p.recent_performance.iter().copied()).collect();
+        assert!(!performances.is_empty());
+        assert!(performances.iter().all(|p| (p * 11.0 - (p * 11.0).round()).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_scorelines_feed_stats_and_blowouts() {
+        use crate::events::{RingBufferSink, SimEventKind};
+
+        let mut sim = Simulation::new(MatchmakingConfig::default(), 11);
+        sim.init_default_data_centers();
+        sim.generate_population(3000, None);
+        sim.set_arrival_rate(200.0);
+        sim.set_event_recording(true);
+        let mut buffer = RingBufferSink::new(usize::MAX);
+        for _ in 0..150 {
+            sim.tick();
+            sim.flush_events(&mut [&mut buffer]);
+        }
+
+        let mut completed = 0;
+        for event in buffer.drain() {
+            if let SimEventKind::MatchCompleted { winning_team, blowout_severity, scores, .. } = event.kind {
+                completed += 1;
+                let runner_up = scores.iter().enumerate().filter(|&(team, _)| team != winning_team).map(|(_, &s)| s).max().unwrap();
+                assert!(scores[winning_team] > runner_up);
+                let margin = (scores[winning_team] - runner_up) as f64 / (scores[winning_team] + runner_up) as f64;
+                assert_eq!(blowout_severity.is_some(), margin >= sim.config.blowout_mild_threshold);
+            }
+        }
+        assert!(completed > 50);
+
+        let scorelines = &sim.stats.scorelines;
+        assert_eq!(scorelines.matches, completed);
+        assert!(scorelines.avg_kills > 1.0 && scorelines.score_limit_rate > 0.0);
+        assert!(scorelines.avg_length_seconds > 0.0);
+        // Kills now track skill: the strongest players out-frag the weakest
+        let kd = |players: Vec<&Player>| {
+            players.iter().map(|p| p.total_kills).sum::<usize>() as f64 / players.iter().map(|p| p.total_deaths).sum::<usize>() as f64
+        };
+        let played: Vec<&Player> = sim.players.values().filter(|p| p.matches_played > 0).collect();
+        assert!(kd(played.iter().copied().filter(|p| p.skill > 0.3).collect()) > 1.2);
+        assert!(kd(played.iter().copied().filter(|p| p.skill < -0.3).collect()) < 0.8);
+    }
+
+    #[test]
+    fn test_match_length_follows_scoreline_and_mercy_rule() {
+        let run = |mercy_lead: Option<usize>| {
+            let mut config = MatchmakingConfig::default();
+            for definition in &mut config.playlists {
+                definition.scoring.mercy_lead = mercy_lead;
+            }
+            let mut sim = Simulation::new(config, 5);
+            sim.init_default_data_centers();
+            sim.generate_population(3000, None);
+            sim.set_arrival_rate(200.0);
+            sim.run(200);
+            sim
+        };
+
+        let sim = run(None);
+        for game_match in sim.matches.values() {
+            let length = game_match.outcome.as_ref().unwrap().scoreline.length_seconds;
+            assert_eq!(game_match.expected_duration, (length / sim.config.tick_interval).ceil() as u64);
+            assert!(length <= sim.config.playlist(game_match.playlist).avg_duration_seconds);
+        }
+        let scorelines = &sim.stats.scorelines;
+        assert!(scorelines.blowouts > 0 && scorelines.score_limit_rate > 0.0);
+        // Stomps hit the score limit sooner than close matches
+        assert!(scorelines.avg_blowout_length_seconds < scorelines.avg_non_blowout_length_seconds);
+        assert_eq!(scorelines.mercy_endings, 0);
+
+        let mercy = run(Some(15));
+        assert!(mercy.stats.scorelines.mercy_rate > 0.0);
+        assert!(mercy.stats.scorelines.avg_length_seconds < scorelines.avg_length_seconds);
+    }
+
+    #[test]
+    fn test_battle_royale_squads_and_placements() {
+        use crate::events::{RingBufferSink, SimEventKind};
+
+        let battle_royale = Playlist::new("Battle Royale");
+        let config = MatchmakingConfig {
+            playlists: vec![PlaylistDefinition {
+                avg_duration_seconds: 120.0,
+                preference_prob: 1.0,
+                outcome: PlaylistOutcome::Placements,
+                ..PlaylistDefinition::new(battle_royale, 10, 3)
+            }],
+            fill_policies: HashMap::from([(battle_royale, FillPolicy::default())]),
+            // Steeper win curve, so squad strength shows within skill-matched lobbies
+            gamma: 10.0,
+            ..Default::default()
+        };
+        let mut sim = Simulation::new(config, 42);
+        sim.init_default_data_centers();
+        sim.generate_population(600, None);
+        sim.set_arrival_rate(20.0);
+        sim.set_event_recording(true);
+        let mut buffer = RingBufferSink::new(usize::MAX);
+        for _ in 0..120 {
+            sim.tick();
+            sim.flush_events(&mut [&mut buffer]);
+        }
+
+        let mut lobbies: HashMap<usize, Vec<Vec<usize>>> = HashMap::new();
+        let mut eliminated = 0;
+        let (mut concordant, mut pairs) = (0, 0);
+        for event in buffer.drain() {
+            match event.kind {
+                SimEventKind::MatchFormed { match_id, teams, .. } => {
+                    // Squads keep parties whole and never exceed the squad size
+                    assert!(teams.iter().all(|squad| !squad.is_empty() && squad.len() <= 3));
+                    for squad in &teams {
+                        let parties: HashSet<Option<usize>> = squad.iter().map(|id| sim.players[id].party_id).collect();
+                        assert!(parties.iter().flatten().all(|&party_id| {
+                            sim.parties.get(&party_id).is_none_or(|party| party.player_ids.iter().all(|id| squad.contains(id)))
+                        }));
+                    }
+                    lobbies.insert(match_id, teams);
+                }
+                SimEventKind::MatchCompleted { match_id, winning_team, placements, .. } => {
+                    let squads = &lobbies[&match_id];
+                    let mut ranks = placements.clone();
+                    ranks.sort_unstable();
+                    assert_eq!(ranks, (0..squads.len()).collect::<Vec<_>>());
+                    assert_eq!(placements[winning_team], 0);
+                    eliminated += squads.iter().flatten().count() - squads[winning_team].len();
+                    let strength: Vec<f64> = squads
+                        .iter()
+                        .map(|squad| squad.iter().map(|id| sim.players[id].skill).sum::<f64>() / squad.len() as f64)
+                        .collect();
+                    for a in 0..squads.len() {
+                        for b in a + 1..squads.len() {
+                            pairs += 1;
+                            concordant += ((strength[a] > strength[b]) == (placements[a] < placements[b])) as usize;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let stats = &sim.stats.battle_royale;
+        assert!(stats.matches > 10);
+        // Everyone outside the winning squad was eliminated by someone
+        assert_eq!(stats.eliminations_sum, eliminated);
+        // Stronger squads tend to outlast weaker ones, and good placements keep players playing
+        assert!(concordant as f64 > 0.6 * pairs as f64);
+        assert!(stats.top_half.continue_rate > stats.bottom_half.continue_rate);
+    }
 }
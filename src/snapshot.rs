@@ -0,0 +1,144 @@
+use crate::simulation::Simulation;
+use serde::{Deserialize, Serialize};
+
+/// Current snapshot format version (bump when `Simulation`'s serialized layout changes)
+pub const SNAPSHOT_VERSION: u32 = 16;
+/// Leading bytes of a binary snapshot
+const BINARY_MAGIC: &[u8; 4] = b"OKQS";
+
+/// Snapshot encoding
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnapshotFormat {
+    /// Human-readable JSON document
+    Json,
+    /// Compact binary: magic, little-endian version, then bincode payload
+    Binary,
+}
+
+impl SnapshotFormat {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Ok(SnapshotFormat::Json),
+            "binary" | "bin" => Ok(SnapshotFormat::Binary),
+            _ => Err(format!("Unknown snapshot format: {} (expected \"json\" or \"binary\")", name)),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SnapshotOut<'a> {
+    format_version: u32,
+    simulation: &'a Simulation,
+}
+
+#[derive(Deserialize)]
+struct SnapshotHeader {
+    format_version: u32,
+}
+
+#[derive(Deserialize)]
+struct SnapshotIn {
+    simulation: Simulation,
+}
+
+/// Encode the full simulation state, including ID counters and session tracking
+pub fn save(sim: &Simulation, format: SnapshotFormat) -> Result<Vec<u8>, String> {
+    match format {
+        SnapshotFormat::Json => serde_json::to_vec(&SnapshotOut {
+            format_version: SNAPSHOT_VERSION,
+            simulation: sim,
+        })
+        .map_err(|e| format!("Snapshot encode error: {}", e)),
+        SnapshotFormat::Binary => {
+            let mut bytes = BINARY_MAGIC.to_vec();
+            bytes.extend_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
+            bincode::serialize_into(&mut bytes, sim)
+                .map_err(|e| format!("Snapshot encode error: {}", e))?;
+            Ok(bytes)
+        }
+    }
+}
+
+/// Decode a snapshot written by `save`; the encoding is detected from its leading bytes
+pub fn load(bytes: &[u8]) -> Result<Simulation, String> {
+    if let Some(payload) = bytes.strip_prefix(BINARY_MAGIC) {
+        let (version, payload) = payload
+            .split_first_chunk::<4>()
+            .ok_or("Truncated binary snapshot")?;
+        check_version(u32::from_le_bytes(*version))?;
+        bincode::deserialize(payload).map_err(|e| format!("Snapshot decode error: {}", e))
+    } else {
+        let header: SnapshotHeader = serde_json::from_slice(bytes)
+            .map_err(|e| format!("Snapshot decode error: {}", e))?;
+        check_version(header.format_version)?;
+        let snapshot: SnapshotIn = serde_json::from_slice(bytes)
+            .map_err(|e| format!("Snapshot decode error: {}", e))?;
+        Ok(snapshot.simulation)
+    }
+}
+
+fn check_version(version: u32) -> Result<(), String> {
+    if version == SNAPSHOT_VERSION {
+        Ok(())
+    } else {
+        Err(format!(
+            "Unsupported snapshot version {} (this build reads version {})",
+            version, SNAPSHOT_VERSION
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MatchmakingConfig;
+
+    fn running_simulation() -> Simulation {
+        let config = MatchmakingConfig {
+            party_player_fraction: 0.3,
+            ..Default::default()
+        };
+        let mut sim = Simulation::new(config, 21);
+        sim.init_default_data_centers();
+        sim.generate_population(2000, None);
+        sim.set_arrival_rate(150.0);
+        sim.run(60);
+        sim
+    }
+
+    /// Stats plus the full state after running `ticks` more ticks
+    fn continue_run(mut sim: Simulation, ticks: u64) -> serde_json::Value {
+        sim.run(ticks);
+        let mut players: Vec<_> = sim.players.values().collect();
+        players.sort_by_key(|p| p.id);
+        serde_json::json!({
+            "stats": sim.stats,
+            "players": players.iter().map(|p| (p.id, p.state, p.mmr.to_bits(), p.matches_played)).collect::<Vec<_>>(),
+            "searches": sim.searches.iter().map(|s| (s.id, s.player_ids.clone())).collect::<Vec<_>>(),
+        })
+    }
+
+    #[test]
+    fn test_restored_run_continues_exactly() {
+        for format in [SnapshotFormat::Json, SnapshotFormat::Binary] {
+            let sim = running_simulation();
+            let restored = load(&save(&sim, format).unwrap()).unwrap();
+            assert_eq!(continue_run(restored, 60), continue_run(sim, 60), "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn test_rejects_unknown_version() {
+        let sim = Simulation::new(MatchmakingConfig::default(), 1);
+        let mut bytes = save(&sim, SnapshotFormat::Binary).unwrap();
+        bytes[4..8].copy_from_slice(&(SNAPSHOT_VERSION + 1).to_le_bytes());
+        let err = load(&bytes).err().unwrap();
+        assert!(err.contains("Unsupported snapshot version"), "{}", err);
+
+        let json = String::from_utf8(save(&sim, SnapshotFormat::Json).unwrap()).unwrap();
+        let current = format!("\"format_version\":{}", SNAPSHOT_VERSION);
+        assert!(json.contains(&current));
+        let json = json.replacen(&current, "\"format_version\":99", 1);
+        assert!(load(json.as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+use crate::batch::BatchMatchmaker;
+use crate::matchmaker::{CandidateOrder, LobbyPolicy, MatchResult, Matchmaker};
+use crate::types::*;
+use rand::rngs::StdRng;
+use std::collections::HashMap;
+
+/// A matchmaking algorithm run once per tick
+/// Implementations consume matched searches from the queue, reserve servers, and
+/// return the lobbies they formed.
+pub trait MatchmakingStrategy {
+    fn run_tick(
+        &mut self,
+        searches: &mut Vec<SearchObject>,
+        players: &mut HashMap<usize, Player>,
+        data_centers: &mut [DataCenter],
+        parties: &HashMap<usize, Party>,
+        current_time: u64,
+        rng: &mut StdRng,
+    ) -> Vec<MatchResult>;
+
+    /// Comparison against the greedy assignment for the last tick, if the strategy computes one
+    fn batch_report(&self) -> Option<BatchTickReport> {
+        None
+    }
+}
+
+impl MatchmakingStrategy for Matchmaker {
+    fn run_tick(
+        &mut self,
+        searches: &mut Vec<SearchObject>,
+        players: &mut HashMap<usize, Player>,
+        data_centers: &mut [DataCenter],
+        parties: &HashMap<usize, Party>,
+        current_time: u64,
+        rng: &mut StdRng,
+    ) -> Vec<MatchResult> {
+        Matchmaker::run_tick(self, searches, players, data_centers, parties, current_time, rng)
+    }
+}
+
+/// Lobby policy implementing each seed-based baseline strategy
+fn baseline_policy(kind: MatchmakingStrategyKind) -> LobbyPolicy {
+    match kind {
+        MatchmakingStrategyKind::SeedGreedy => LobbyPolicy::SEED_GREEDY,
+        MatchmakingStrategyKind::Fifo => LobbyPolicy {
+            candidate_order: CandidateOrder::WaitTime,
+            enforce_skill: false,
+            enforce_ping: true,
+        },
+        MatchmakingStrategyKind::PingOnly => LobbyPolicy {
+            candidate_order: CandidateOrder::Geographic,
+            enforce_skill: false,
+            enforce_ping: true,
+        },
+        MatchmakingStrategyKind::SkillOnly => LobbyPolicy {
+            candidate_order: CandidateOrder::Skill,
+            enforce_skill: true,
+            enforce_ping: false,
+        },
+        // Batch assignment refines greedy lobbies built under the standard policy
+        MatchmakingStrategyKind::BatchOptimal => LobbyPolicy::SEED_GREEDY,
+    }
+}
+
+/// Build the matchmaking strategy selected in the config
+pub fn matchmaking_strategy(config: &MatchmakingConfig) -> Box<dyn MatchmakingStrategy> {
+    match config.matchmaking_strategy {
+        MatchmakingStrategyKind::SeedGreedy => Box::new(Matchmaker::new(config.clone())),
+        MatchmakingStrategyKind::BatchOptimal => Box::new(BatchMatchmaker::new(config.clone())),
+        kind => Box::new(Matchmaker::with_policy(config.clone(), baseline_policy(kind))),
+    }
+}
+
+/// Matchmaker that backfills in-progress matches under the configured strategy's lobby policy
+pub fn backfill_matchmaker(config: &MatchmakingConfig) -> Matchmaker {
+    Matchmaker::with_policy(config.clone(), baseline_policy(config.matchmaking_strategy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use std::collections::HashSet;
+
+    /// Twelve solo searches at the same location but spread across the whole skill range
+    fn spread_skill_queue() -> (Vec<SearchObject>, HashMap<usize, Player>, Vec<DataCenter>) {
+        let mut players = HashMap::new();
+        let mut searches = Vec::new();
+        for id in 0..12 {
+            let mut player = Player::new(id, Location::new(0.0, 0.0), 0.0);
+            player.skill_percentile = id as f64 / 11.0;
+            player.dc_pings.insert(0, 20.0);
+            player.best_dc = Some(0);
+            player.best_ping = 20.0;
+            players.insert(id, player);
+
+            searches.push(SearchObject {
+                id,
+                player_ids: vec![id],
+                avg_skill_percentile: id as f64 / 11.0,
+                skill_disparity: 0.0,
+                avg_location: Location::new(0.0, 0.0),
+                platforms: HashMap::new(),
+                input_devices: HashMap::new(),
+                acceptable_playlists: [Playlist::TEAM_DEATHMATCH].into_iter().collect(),
+                search_start_time: 0,
+                acceptable_dcs: HashSet::new(),
+                placement: false,
+            });
+        }
+        let data_centers = vec![DataCenter::new(0, "Test", Location::new(0.0, 0.0), Region::Other, &PlaylistDefinition::defaults())];
+        (searches, players, data_centers)
+    }
+
+    fn run(kind: MatchmakingStrategyKind) -> usize {
+        let config = MatchmakingConfig {
+            matchmaking_strategy: kind,
+            ..MatchmakingConfig::default()
+        };
+        let (mut searches, mut players, mut data_centers) = spread_skill_queue();
+        let mut rng = StdRng::seed_from_u64(1);
+        matchmaking_strategy(&config)
+            .run_tick(&mut searches, &mut players, &mut data_centers, &HashMap::new(), 0, &mut rng)
+            .len()
+    }
+
+    #[test]
+    fn test_skill_blind_strategies_fill_lobbies_sbmm_rejects() {
+        assert_eq!(run(MatchmakingStrategyKind::SeedGreedy), 0);
+        assert_eq!(run(MatchmakingStrategyKind::SkillOnly), 0);
+        assert_eq!(run(MatchmakingStrategyKind::Fifo), 1);
+        assert_eq!(run(MatchmakingStrategyKind::PingOnly), 1);
+        assert_eq!(run(MatchmakingStrategyKind::BatchOptimal), 0);
+    }
+
+    #[test]
+    fn test_strategy_names_round_trip() {
+        for kind in MatchmakingStrategyKind::ALL {
+            assert_eq!(MatchmakingStrategyKind::from_name(kind.name()), Some(kind));
+        }
+        assert_eq!(MatchmakingStrategyKind::from_name("fifo"), Some(MatchmakingStrategyKind::Fifo));
+        assert_eq!(MatchmakingStrategyKind::from_name("unknown"), None);
+    }
+}
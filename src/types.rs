@@ -1,5 +1,7 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{Mutex, OnceLock};
 
 /// Geographic coordinates (latitude, longitude)
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -38,6 +40,26 @@ pub enum Region {
 }
 
 impl Region {
+    /// All regions, in a fixed order
+    pub const ALL: [Region; 5] = [
+        Region::NorthAmerica,
+        Region::Europe,
+        Region::AsiaPacific,
+        Region::SouthAmerica,
+        Region::Other,
+    ];
+
+    /// Region backoff tier for a wait time: 0 = own region, 1 = + adjacent, 2 = all regions
+    pub fn backoff_tier(wait_time: f64) -> usize {
+        if wait_time < 10.0 {
+            0
+        } else if wait_time < 30.0 {
+            1
+        } else {
+            2
+        }
+    }
+
     /// Get adjacent regions based on geographic connectivity
     /// Defines the region adjacency graph:
     /// - NA ↔ EU (transatlantic)
@@ -85,43 +107,302 @@ pub enum PlayerState {
     InMatch,
 }
 
-/// Available playlists/game modes
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum Playlist {
-    TeamDeathmatch,      // 6v6
-    SearchAndDestroy,    // 6v6
-    Domination,          // 6v6
-    GroundWar,           // 32v32
-    FreeForAll,          // 12 players
-}
+/// A playlist (game mode), identified by the name of its definition in `MatchmakingConfig::playlists`
+/// Names are interned, so a playlist is a cheap `Copy` handle; it serializes as its name.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Playlist(&'static str);
 
 impl Playlist {
-    pub fn required_players(&self) -> usize {
-        match self {
-            Playlist::TeamDeathmatch => 12,
-            Playlist::SearchAndDestroy => 12,
-            Playlist::Domination => 12,
-            Playlist::GroundWar => 64,
-            Playlist::FreeForAll => 12,
+    pub const TEAM_DEATHMATCH: Playlist = Playlist("TeamDeathmatch");
+    pub const SEARCH_AND_DESTROY: Playlist = Playlist("SearchAndDestroy");
+    pub const DOMINATION: Playlist = Playlist("Domination");
+    pub const GROUND_WAR: Playlist = Playlist("GroundWar");
+    pub const FREE_FOR_ALL: Playlist = Playlist("FreeForAll");
+
+    /// The playlist with this name (each distinct name is allocated once and kept for the process)
+    pub fn new(name: &str) -> Self {
+        static NAMES: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+        let mut names = NAMES
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(&interned) = names.get(name) {
+            return Playlist(interned);
         }
+        let interned: &'static str = Box::leak(name.to_string().into_boxed_str());
+        names.insert(interned);
+        Playlist(interned)
     }
 
-    pub fn team_count(&self) -> usize {
-        match self {
-            Playlist::FreeForAll => 12,
-            Playlist::GroundWar => 2,
-            _ => 2,
+    pub fn name(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl fmt::Debug for Playlist {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl fmt::Display for Playlist {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl Serialize for Playlist {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Playlist {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(Playlist::new(&name))
+    }
+}
+
+/// How a playlist's matches are decided
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlaylistOutcome {
+    /// One team wins, the rest lose
+    #[default]
+    WinLoss,
+    /// Every team gets a finishing place (Battle Royale squads)
+    Placements,
+}
+
+/// What a playlist's score counts
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScoreFormat {
+    /// Each kill is a point (Team Deathmatch, Free-for-All)
+    #[default]
+    Kills,
+    /// Each round is a point; the losers of a round are all eliminated (Search & Destroy)
+    Rounds,
+    /// Points come from objectives; kills happen alongside at their own rate (Domination, Ground War)
+    Objective,
+}
+
+/// How a playlist's scoreline is generated
+/// Points go to teams by a softmax of `dominance` × team strength (true skill plus the match's
+/// form noise) until a team reaches `score_limit` or time runs out.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScoringRules {
+    pub format: ScoreFormat,
+    /// First team to this score wins and ends the match
+    pub score_limit: usize,
+    /// Mean seconds between points in a full lobby
+    pub seconds_per_point: f64,
+    /// Lobby-wide kills per minute in a full lobby (Objective format; other formats derive kills from points)
+    pub kills_per_minute: f64,
+    /// Logistic slope of each point's and each kill's odds on the skill gap
+    pub dominance: f64,
+    /// Weight of objective play, against kill/death share, in the performance index
+    pub objective_weight: f64,
+    /// Mercy rule: the match ends once a team leads by this many points (None = off)
+    pub mercy_lead: Option<usize>,
+}
+
+impl Default for ScoringRules {
+    fn default() -> Self {
+        Self {
+            format: ScoreFormat::Kills,
+            score_limit: 75,
+            seconds_per_point: 4.0,
+            kills_per_minute: 0.0,
+            dominance: 2.0,
+            objective_weight: 0.0,
+            mercy_lead: None,
         }
     }
+}
 
-    pub fn avg_match_duration_seconds(&self) -> f64 {
-        match self {
-            Playlist::TeamDeathmatch => 600.0,      // 10 min
-            Playlist::SearchAndDestroy => 900.0,    // 15 min
-            Playlist::Domination => 600.0,          // 10 min
-            Playlist::GroundWar => 1200.0,          // 20 min
-            Playlist::FreeForAll => 600.0,          // 10 min
+impl ScoringRules {
+    /// Problems with these rules, as `path: message` entries
+    pub fn validate(&self, path: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.score_limit == 0 {
+            errors.push(format!("{}.score_limit: must be at least 1", path));
+        }
+        if self.seconds_per_point <= 0.0 {
+            errors.push(format!("{}.seconds_per_point: must be positive", path));
+        }
+        if self.kills_per_minute < 0.0 {
+            errors.push(format!("{}.kills_per_minute: must not be negative", path));
+        }
+        if self.dominance < 0.0 {
+            errors.push(format!("{}.dominance: must not be negative", path));
+        }
+        if !(0.0..=1.0).contains(&self.objective_weight) {
+            errors.push(format!("{}.objective_weight: must be between 0 and 1", path));
         }
+        if self.mercy_lead == Some(0) {
+            errors.push(format!("{}.mercy_lead: must be at least 1", path));
+        }
+        errors
+    }
+}
+
+/// A playlist's rules: teams, match length, party cap and server cost
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlaylistDefinition {
+    pub name: Playlist,
+    pub team_count: usize,
+    /// Players per team (1 = free-for-all)
+    pub team_size: usize,
+    /// Match clock (seconds): scored matches end here unless the score limit or a mercy lead
+    /// ends them first; placement matches last this long on average
+    #[serde(default = "default_match_duration")]
+    pub avg_duration_seconds: f64,
+    /// Placement match length varies uniformly within ± this fraction of the mean
+    #[serde(default = "default_duration_spread")]
+    pub duration_spread: f64,
+    /// Largest party that may queue for the playlist (None = team size)
+    #[serde(default)]
+    pub max_party_size: Option<usize>,
+    /// Server units one match occupies; a data center gets `DataCenter::SERVER_UNITS / server_cost` servers by default
+    #[serde(default = "default_server_cost")]
+    pub server_cost: f64,
+    /// Score limit, pace and what a point counts
+    #[serde(default)]
+    pub scoring: ScoringRules,
+    /// Chance that a generated player wants to play the playlist
+    #[serde(default)]
+    pub preference_prob: f64,
+    /// Win/loss, or ranked placements with squads filled from parties and solos
+    #[serde(default)]
+    pub outcome: PlaylistOutcome,
+}
+
+fn default_match_duration() -> f64 {
+    600.0
+}
+
+fn default_duration_spread() -> f64 {
+    0.2
+}
+
+fn default_server_cost() -> f64 {
+    1.0
+}
+
+impl PlaylistDefinition {
+    pub fn new(name: Playlist, team_count: usize, team_size: usize) -> Self {
+        Self {
+            name,
+            team_count,
+            team_size,
+            avg_duration_seconds: default_match_duration(),
+            duration_spread: default_duration_spread(),
+            max_party_size: None,
+            server_cost: default_server_cost(),
+            scoring: ScoringRules::default(),
+            preference_prob: 0.0,
+            outcome: PlaylistOutcome::WinLoss,
+        }
+    }
+
+    /// The built-in playlists, in matchmaking order
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            PlaylistDefinition { preference_prob: 1.0, ..Self::new(Playlist::TEAM_DEATHMATCH, 2, 6) },
+            PlaylistDefinition {
+                avg_duration_seconds: 900.0,
+                scoring: ScoringRules {
+                    format: ScoreFormat::Rounds,
+                    score_limit: 6,
+                    seconds_per_point: 75.0,
+                    objective_weight: 0.2,
+                    ..ScoringRules::default()
+                },
+                preference_prob: 0.2,
+                ..Self::new(Playlist::SEARCH_AND_DESTROY, 2, 6)
+            },
+            PlaylistDefinition {
+                scoring: ScoringRules {
+                    format: ScoreFormat::Objective,
+                    score_limit: 200,
+                    seconds_per_point: 1.6,
+                    kills_per_minute: 20.0,
+                    objective_weight: 0.4,
+                    ..ScoringRules::default()
+                },
+                preference_prob: 0.4,
+                ..Self::new(Playlist::DOMINATION, 2, 6)
+            },
+            PlaylistDefinition {
+                avg_duration_seconds: 1200.0,
+                server_cost: 4.0,
+                scoring: ScoringRules {
+                    format: ScoreFormat::Objective,
+                    score_limit: 200,
+                    seconds_per_point: 3.0,
+                    kills_per_minute: 90.0,
+                    objective_weight: 0.4,
+                    ..ScoringRules::default()
+                },
+                preference_prob: 0.15,
+                ..Self::new(Playlist::GROUND_WAR, 2, 32)
+            },
+            PlaylistDefinition {
+                scoring: ScoringRules { score_limit: 30, seconds_per_point: 1.6, ..ScoringRules::default() },
+                preference_prob: 0.1,
+                ..Self::new(Playlist::FREE_FOR_ALL, 12, 1)
+            },
+        ]
+    }
+
+    pub fn required_players(&self) -> usize {
+        self.team_count * self.team_size
+    }
+
+    /// Every player for themselves (one-player teams)
+    pub fn is_free_for_all(&self) -> bool {
+        self.team_size == 1
+    }
+
+    pub fn party_cap(&self) -> usize {
+        self.max_party_size.unwrap_or(self.team_size)
+    }
+
+    /// Teams finish in ranked places rather than win/lose
+    pub fn ranks_placements(&self) -> bool {
+        self.outcome == PlaylistOutcome::Placements
+    }
+
+    /// Problems with this definition, as `path: message` entries
+    pub fn validate(&self, path: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.name.name().is_empty() {
+            errors.push(format!("{}.name: must not be empty", path));
+        }
+        if self.team_count == 0 || self.team_size == 0 {
+            errors.push(format!("{}: team_count and team_size must be at least 1", path));
+        } else if self.required_players() < 2 {
+            errors.push(format!("{}: a match needs at least 2 players", path));
+        }
+        if self.avg_duration_seconds <= 0.0 {
+            errors.push(format!("{}.avg_duration_seconds: must be positive", path));
+        }
+        if !(0.0..1.0).contains(&self.duration_spread) {
+            errors.push(format!("{}.duration_spread: must be in [0, 1)", path));
+        }
+        if self.max_party_size == Some(0) {
+            errors.push(format!("{}.max_party_size: must be at least 1", path));
+        }
+        if self.server_cost <= 0.0 {
+            errors.push(format!("{}.server_cost: must be positive", path));
+        }
+        if !(0.0..=1.0).contains(&self.preference_prob) {
+            errors.push(format!("{}.preference_prob: must be between 0 and 1", path));
+        }
+        errors.extend(self.scoring.validate(&format!("{}.scoring", path)));
+        errors
     }
 }
 
@@ -139,24 +420,17 @@ pub struct DataCenter {
 }
 
 impl DataCenter {
-    pub fn new(id: usize, name: &str, location: Location, region: Region) -> Self {
+    /// Server units per playlist when a scenario does not specify a capacity
+    pub const SERVER_UNITS: f64 = 200.0;
+
+    pub fn new(id: usize, name: &str, location: Location, region: Region, playlists: &[PlaylistDefinition]) -> Self {
         let mut server_capacity = HashMap::new();
         let mut busy_servers = HashMap::new();
         
         // Default capacities
-        for playlist in [
-            Playlist::TeamDeathmatch,
-            Playlist::SearchAndDestroy,
-            Playlist::Domination,
-            Playlist::GroundWar,
-            Playlist::FreeForAll,
-        ] {
-            let capacity = match playlist {
-                Playlist::GroundWar => 50,
-                _ => 200,
-            };
-            server_capacity.insert(playlist, capacity);
-            busy_servers.insert(playlist, 0);
+        for definition in playlists {
+            server_capacity.insert(definition.name, Self::default_capacity(definition));
+            busy_servers.insert(definition.name, 0);
         }
 
         Self {
@@ -169,6 +443,11 @@ impl DataCenter {
         }
     }
 
+    /// Server count per playlist when a scenario does not specify one
+    pub fn default_capacity(definition: &PlaylistDefinition) -> usize {
+        (Self::SERVER_UNITS / definition.server_cost) as usize
+    }
+
     pub fn available_servers(&self, playlist: &Playlist) -> usize {
         let capacity = self.server_capacity.get(playlist).copied().unwrap_or(0);
         let busy = self.busy_servers.get(playlist).copied().unwrap_or(0);
@@ -176,6 +455,179 @@ impl DataCenter {
     }
 }
 
+/// One step of an arrival schedule
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ArrivalStep {
+    /// First tick this rate applies to
+    pub start_tick: u64,
+    /// Expected arrivals per tick
+    pub rate: f64,
+}
+
+/// Piecewise-constant arrival rate over time, optionally repeating
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ArrivalSchedule {
+    /// Steps in increasing `start_tick` order
+    pub steps: Vec<ArrivalStep>,
+    /// Repeat the schedule every `period` ticks (0 = no repeat)
+    pub period: u64,
+}
+
+impl ArrivalSchedule {
+    /// Arrival rate at `tick`, or None before the first step
+    pub fn rate_at(&self, tick: u64) -> Option<f64> {
+        let t = if self.period > 0 { tick % self.period } else { tick };
+        self.steps.iter().rev().find(|step| step.start_tick <= t).map(|step| step.rate)
+    }
+
+    /// Problems with this schedule, as `path: message` entries
+    pub fn validate(&self, path: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.steps.is_empty() {
+            errors.push(format!("{}.steps: must not be empty", path));
+        }
+        for (i, step) in self.steps.iter().enumerate() {
+            if !step.rate.is_finite() || step.rate < 0.0 {
+                errors.push(format!("{}.steps[{}].rate: must be a non-negative number", path, i));
+            }
+            if i > 0 && step.start_tick <= self.steps[i - 1].start_tick {
+                errors.push(format!(
+                    "{}.steps[{}].start_tick: must be greater than the previous step's start_tick",
+                    path, i
+                ));
+            }
+        }
+        if let Some(last) = self.steps.last() {
+            if self.period != 0 && self.period <= last.start_tick {
+                errors.push(format!("{}.period: must be 0 or greater than the last step's start_tick", path));
+            }
+        }
+        errors
+    }
+}
+
+/// 24-hour arrival curve for one region
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DiurnalProfile {
+    /// Offset of the region's local time from UTC (hours)
+    pub utc_offset_hours: f64,
+    /// Relative arrival intensity at local hours 0..23, interpolated linearly in between
+    /// Divided by its mean, so only the shape matters.
+    pub hourly: Vec<f64>,
+}
+
+impl DiurnalProfile {
+    /// Evening-peaking player activity by local hour
+    pub const TYPICAL_SHAPE: [f64; 24] = [
+        0.9, 0.6, 0.4, 0.3, 0.25, 0.25, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8,
+        0.9, 1.0, 1.1, 1.2, 1.35, 1.5, 1.7, 1.9, 2.0, 1.9, 1.6, 1.2,
+    ];
+
+    pub fn typical(utc_offset_hours: f64) -> Self {
+        Self {
+            utc_offset_hours,
+            hourly: Self::TYPICAL_SHAPE.to_vec(),
+        }
+    }
+
+    /// Arrival rate multiplier at a UTC hour (1.0 = the region's daily average)
+    pub fn multiplier(&self, hour_utc: f64) -> f64 {
+        let n = self.hourly.len();
+        let mean = self.hourly.iter().sum::<f64>() / n.max(1) as f64;
+        if mean <= 0.0 {
+            return 1.0;
+        }
+        let local = (hour_utc + self.utc_offset_hours).rem_euclid(24.0) * n as f64 / 24.0;
+        let i = (local.floor() as usize).min(n - 1);
+        let frac = local - i as f64;
+        let value = self.hourly[i] * (1.0 - frac) + self.hourly[(i + 1) % n] * frac;
+        value / mean
+    }
+
+    /// Problems with this profile, as `path: message` entries
+    pub fn validate(&self, path: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.hourly.len() != 24 {
+            errors.push(format!("{}.hourly: must have 24 entries (got {})", path, self.hourly.len()));
+        }
+        if let Some(i) = self.hourly.iter().position(|v| !v.is_finite() || *v < 0.0) {
+            errors.push(format!("{}.hourly[{}]: must be a non-negative number", path, i));
+        } else if self.hourly.iter().all(|v| *v == 0.0) {
+            errors.push(format!("{}.hourly: must not be all zero", path));
+        }
+        if !(-12.0..=14.0).contains(&self.utc_offset_hours) {
+            errors.push(format!("{}.utc_offset_hours: must be between -12 and 14", path));
+        }
+        errors
+    }
+}
+
+/// Per-region time-of-day arrival curves
+/// The global arrival rate is split between regions by population share, then each
+/// region's share is scaled by its profile at the current local time.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DiurnalArrivals {
+    /// UTC hour of day at tick 0
+    pub start_hour_utc: f64,
+    /// Ticks per simulated day (0 = real time, 86400 s / tick_interval)
+    pub day_length_ticks: u64,
+    /// Regions without a profile arrive at a flat rate
+    pub profiles: HashMap<Region, DiurnalProfile>,
+}
+
+impl DiurnalArrivals {
+    /// Typical evening-peaking curves for the main regions at representative UTC offsets
+    pub fn typical() -> Self {
+        let profiles = [
+            (Region::NorthAmerica, -5.0),
+            (Region::Europe, 1.0),
+            (Region::AsiaPacific, 8.0),
+            (Region::SouthAmerica, -3.0),
+        ]
+        .into_iter()
+        .map(|(region, offset)| (region, DiurnalProfile::typical(offset)))
+        .collect();
+        Self {
+            start_hour_utc: 0.0,
+            day_length_ticks: 0,
+            profiles,
+        }
+    }
+
+    /// UTC hour of day (0..24) at `tick`
+    pub fn hour_utc(&self, tick: u64, tick_interval: f64) -> f64 {
+        let hours_per_tick = if self.day_length_ticks > 0 {
+            24.0 / self.day_length_ticks as f64
+        } else {
+            tick_interval / 3600.0
+        };
+        (self.start_hour_utc + tick as f64 * hours_per_tick).rem_euclid(24.0)
+    }
+
+    /// Arrival rate multiplier for `region` at a UTC hour
+    pub fn multiplier(&self, region: Region, hour_utc: f64) -> f64 {
+        self.profiles.get(&region).map_or(1.0, |p| p.multiplier(hour_utc))
+    }
+
+    /// Problems with these curves, as `path: message` entries
+    pub fn validate(&self, path: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        if !(0.0..24.0).contains(&self.start_hour_utc) {
+            errors.push(format!("{}.start_hour_utc: must be in [0, 24)", path));
+        }
+        for region in Region::ALL {
+            if let Some(profile) = self.profiles.get(&region) {
+                errors.extend(profile.validate(&format!("{}.profiles.{:?}", path, region)));
+            }
+        }
+        errors
+    }
+}
+
 /// Player statistics and state
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Player {
@@ -186,9 +638,11 @@ pub struct Player {
     pub input_device: InputDevice,
     pub voice_chat_enabled: bool,
     
-    /// Raw skill value in [-1, 1]
+    /// Latent true skill in [-1, 1] (drives outcomes and performance only)
     pub skill: f64,
-    /// Skill percentile in [0, 1]
+    /// Matchmaker's observed rating estimate (MMR) in [-1, 1]
+    pub mmr: f64,
+    /// Skill percentile in [0, 1], ranked by MMR
     pub skill_percentile: f64,
     /// Skill bucket (1 to B)
     pub skill_bucket: usize,
@@ -217,6 +671,9 @@ pub struct Player {
     pub matches_played: usize,
     pub total_kills: usize,
     pub total_deaths: usize,
+    /// Objective points scored (plants and defuses in round-based modes)
+    #[serde(default)]
+    pub total_objective: usize,
     pub wins: usize,
     pub losses: usize,
     
@@ -246,12 +703,39 @@ pub struct Player {
     
     /// Return probability tracking: when player last went offline (tick)
     pub last_session_end_time: Option<u64>,
+    
+    /// Per-player state kept by the active rating system (e.g., rating deviation)
+    pub rating_state: RatingState,
+
+    /// Tick the account was created (None for the initial population)
+    #[serde(default)]
+    pub created_at: Option<u64>,
+    /// Matches an acquired player needed for their rating to converge (None until it has)
+    #[serde(default)]
+    pub converged_after: Option<usize>,
+
+    /// Smurf archetype: plays well above the rating of their current account
+    #[serde(default)]
+    pub smurf: bool,
+    /// Consecutive losses (reset by a win or a new account)
+    #[serde(default)]
+    pub loss_streak: usize,
+
+    /// Maps this player likes (voted for, never quit over)
+    #[serde(default)]
+    pub liked_maps: HashSet<String>,
+    /// Maps this player dislikes (may quit the lobby when one comes up)
+    #[serde(default)]
+    pub disliked_maps: HashSet<String>,
+    /// Most recently played maps, oldest first (at most `MapConfig::history_length`)
+    #[serde(default)]
+    pub recent_maps: Vec<String>,
 }
 
 impl Player {
     pub fn new(id: usize, location: Location, skill: f64) -> Self {
         let mut preferred = HashSet::new();
-        preferred.insert(Playlist::TeamDeathmatch);
+        preferred.insert(Playlist::TEAM_DEATHMATCH);
         
         Self {
             id,
@@ -261,6 +745,7 @@ impl Player {
             input_device: InputDevice::Controller,
             voice_chat_enabled: true,
             skill,
+            mmr: skill,
             skill_percentile: 0.5,
             skill_bucket: 5,
             state: PlayerState::Offline,
@@ -274,6 +759,7 @@ impl Player {
             matches_played: 0,
             total_kills: 0,
             total_deaths: 0,
+            total_objective: 0,
             wins: 0,
             losses: 0,
             recent_delta_pings: Vec::new(),
@@ -286,9 +772,47 @@ impl Player {
             matches_in_session: 0,
             last_session_experience: Vec::new(),
             last_session_end_time: None,
+            rating_state: RatingState::default(),
+            created_at: None,
+            converged_after: None,
+            smurf: false,
+            loss_streak: 0,
+            liked_maps: HashSet::new(),
+            disliked_maps: HashSet::new(),
+            recent_maps: Vec::new(),
         }
     }
 
+    /// Swap to a fresh account created at `tick`: rating, record and recent match history start
+    /// over, so the account goes through placement like any new player
+    pub fn start_new_account(&mut self, mmr: f64, rating_state: RatingState, tick: u64) {
+        self.mmr = mmr;
+        self.rating_state = rating_state;
+        self.created_at = Some(tick);
+        self.converged_after = None;
+        self.matches_played = 0;
+        self.wins = 0;
+        self.losses = 0;
+        self.loss_streak = 0;
+        self.total_kills = 0;
+        self.total_deaths = 0;
+        self.total_objective = 0;
+        self.recent_delta_pings.clear();
+        self.recent_search_times.clear();
+        self.recent_blowouts.clear();
+        self.recent_performance.clear();
+    }
+
+    /// Acquired mid-simulation and still playing placement matches with a provisional rating
+    pub fn in_placement(&self, config: &MatchmakingConfig) -> bool {
+        self.created_at.is_some() && self.matches_played < config.placement_matches
+    }
+
+    /// Acquired mid-simulation and still short of `new_player_matches` matches
+    pub fn is_new_player(&self, new_player_matches: usize) -> bool {
+        self.created_at.is_some() && self.matches_played < new_player_matches
+    }
+
     /// Calculate acceptable data centers based on wait time with region-aware backoff
     /// Implements three-tier backoff:
     /// - Short wait (0-10s): Only best region DCs
@@ -306,23 +830,17 @@ impl Player {
         let max_ping = config.get_region_max_ping(player_region);
         
         // Determine which regions are acceptable based on wait time
-        let acceptable_regions: Vec<Region> = if wait_time < 10.0 {
+        let acceptable_regions: Vec<Region> = match Region::backoff_tier(wait_time) {
             // Short wait: only best region
-            vec![player_region]
-        } else if wait_time < 30.0 {
+            0 => vec![player_region],
             // Medium wait: best region + adjacent regions
-            let mut regions = vec![player_region];
-            regions.extend(player_region.adjacent_regions());
-            regions
-        } else {
+            1 => {
+                let mut regions = vec![player_region];
+                regions.extend(player_region.adjacent_regions());
+                regions
+            }
             // Long wait: all regions
-            vec![
-                Region::NorthAmerica,
-                Region::Europe,
-                Region::AsiaPacific,
-                Region::SouthAmerica,
-                Region::Other,
-            ]
+            _ => Region::ALL.to_vec(),
         };
         
         // Create a set of acceptable region IDs for fast lookup
@@ -361,8 +879,10 @@ pub struct Party {
     pub id: usize,
     pub player_ids: Vec<usize>,
     pub leader_id: usize,
-    /// Average skill of party (raw skill)
+    /// Average skill of party (raw true skill)
     pub avg_skill: f64,
+    /// Average MMR of party (what the matchmaker sees)
+    pub avg_mmr: f64,
     /// Skill disparity within party (raw skill)
     pub skill_disparity: f64,
     /// Average skill percentile
@@ -402,6 +922,7 @@ impl Party {
             let max_skill = skills.iter().fold(f64::MIN, |a, &b| a.max(b));
             max_skill - min_skill
         };
+        let avg_mmr = players.iter().map(|p| p.mmr).sum::<f64>() / players.len() as f64;
 
         // Compute percentile aggregates
         let percentiles: Vec<f64> = players.iter().map(|p| p.skill_percentile).collect();
@@ -446,6 +967,7 @@ impl Party {
             player_ids,
             leader_id,
             avg_skill,
+            avg_mmr,
             skill_disparity,
             avg_skill_percentile,
             skill_percentile_disparity,
@@ -475,6 +997,7 @@ impl Party {
             let max_skill = skills.iter().fold(f64::MIN, |a, &b| a.max(b));
             max_skill - min_skill
         };
+        self.avg_mmr = party_players.iter().map(|p| p.mmr).sum::<f64>() / party_players.len() as f64;
 
         // Update percentile aggregates
         let percentiles: Vec<f64> = party_players.iter().map(|p| p.skill_percentile).collect();
@@ -553,9 +1076,16 @@ impl Party {
             avg_location: self.avg_location,
             platforms: self.platforms.clone(),
             input_devices: self.input_devices.clone(),
-            acceptable_playlists: self.preferred_playlists.clone(),
+            // Only playlists whose party cap fits the whole party
+            acceptable_playlists: self
+                .preferred_playlists
+                .iter()
+                .copied()
+                .filter(|&playlist| config.playlist(playlist).party_cap() >= self.player_ids.len())
+                .collect(),
             search_start_time,
             acceptable_dcs: acceptable_dcs.unwrap_or_default(),
+            placement: party_players.iter().any(|p| p.in_placement(config)),
         }
     }
 }
@@ -581,6 +1111,9 @@ pub struct SearchObject {
     pub search_start_time: u64,
     /// Currently acceptable data centers
     pub acceptable_dcs: HashSet<usize>,
+    /// Contains a player in placement (gets wider skill constraints)
+    #[serde(default)]
+    pub placement: bool,
 }
 
 impl SearchObject {
@@ -615,6 +1148,19 @@ pub struct ExperienceVector {
     pub won: bool,
     /// Performance index from match (0-1 scale)
     pub performance: f64,
+    /// Finishing place as a percentile (1 = first, 0 = last), for placement playlists
+    #[serde(default)]
+    pub placement: Option<f64>,
+    /// Players this player eliminated (placement playlists)
+    #[serde(default)]
+    pub eliminations: usize,
+}
+
+impl ExperienceVector {
+    /// Win credit for the retention model: the placement percentile, else 1 for a win
+    pub fn win_score(&self) -> f64 {
+        self.placement.unwrap_or(if self.won { 1.0 } else { 0.0 })
+    }
 }
 
 /// Per-region configuration overrides
@@ -633,6 +1179,540 @@ pub struct RegionConfig {
     pub skill_similarity_rate: Option<f64>,
 }
 
+/// Statistics for one group of players (e.g. new accounts vs. veterans)
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CohortStats {
+    pub player_count: usize,
+    pub players_online: usize,
+    pub avg_matches_played: f64,
+    pub avg_search_time: f64,
+    pub avg_delta_ping: f64,
+    pub win_rate: f64,
+    /// Fraction of recent matches that were blowouts
+    pub blowout_rate: f64,
+    /// Mean |MMR - true skill|
+    pub avg_rating_error: f64,
+    pub avg_skill: f64,
+}
+
+/// Placement (cold-start) metrics for new accounts
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PlacementStats {
+    /// Players currently playing placement matches
+    pub players_in_placement: usize,
+    pub placements_completed: usize,
+    /// Mean |MMR - true skill| when placement ended
+    pub avg_rating_error_after_placement: f64,
+    pub rating_error_after_placement_sum: f64,
+    /// New players whose rating error fell below `placement_convergence_error`
+    pub converged_players: usize,
+    /// Mean matches a new player needed for their rating to converge
+    pub avg_matches_to_converge: f64,
+    pub matches_to_converge_sum: usize,
+    /// Completed matches with established players, with and without placement players in the lobby
+    pub matches_with_placement: usize,
+    pub blowouts_with_placement: usize,
+    pub matches_without_placement: usize,
+    pub blowouts_without_placement: usize,
+    /// Blowout rate of established players' matches that included placement players
+    pub blowout_rate_with_placement: f64,
+    /// Blowout rate of established players' matches without placement players
+    pub blowout_rate_without_placement: f64,
+}
+
+/// Match experience of one group of players, accumulated over completed matches
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ExperienceStats {
+    /// Player-matches recorded
+    pub samples: usize,
+    pub wins: usize,
+    pub blowouts: usize,
+    pub continues: usize,
+    pub performance_sum: f64,
+    pub delta_ping_sum: f64,
+    pub search_time_sum: f64,
+    pub win_rate: f64,
+    pub blowout_rate: f64,
+    pub avg_performance: f64,
+    pub avg_delta_ping: f64,
+    pub avg_search_time: f64,
+    /// Fraction of matches after which the player kept playing
+    pub continue_rate: f64,
+}
+
+impl ExperienceStats {
+    pub fn record(&mut self, experience: &ExperienceVector, continued: bool) {
+        self.samples += 1;
+        self.wins += experience.won as usize;
+        self.blowouts += experience.was_blowout as usize;
+        self.continues += continued as usize;
+        self.performance_sum += experience.performance;
+        self.delta_ping_sum += experience.avg_delta_ping;
+        self.search_time_sum += experience.avg_search_time;
+    }
+
+    /// Recompute the rates from the running sums
+    pub fn update_rates(&mut self) {
+        let n = self.samples.max(1) as f64;
+        self.win_rate = self.wins as f64 / n;
+        self.blowout_rate = self.blowouts as f64 / n;
+        self.continue_rate = self.continues as f64 / n;
+        self.avg_performance = self.performance_sum / n;
+        self.avg_delta_ping = self.delta_ping_sum / n;
+        self.avg_search_time = self.search_time_sum / n;
+    }
+}
+
+/// Mid-match abandonment and backfill metrics
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AbandonmentStats {
+    /// Players who left a match before it ended
+    pub abandonments: usize,
+    /// Fraction of player-matches that ended in abandonment
+    pub abandon_rate: f64,
+    /// Matches that lost at least one player
+    pub matches_with_abandonment: usize,
+    /// Matches ended early because a whole team left
+    pub forfeits: usize,
+    /// Players placed into matches in progress
+    pub backfills: usize,
+    /// Mean fraction of the match already played when a backfilled player joined
+    pub avg_backfill_progress: f64,
+    pub backfill_progress_sum: f64,
+    /// Experience of players when they abandoned
+    pub abandoned: ExperienceStats,
+    /// Experience of backfilled players at the end of their match
+    pub backfilled: ExperienceStats,
+    /// Experience of players who played a whole match
+    pub full_match: ExperienceStats,
+}
+
+/// Smurf metrics, including the experience of the players they meet
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SmurfStats {
+    pub smurf_count: usize,
+    pub smurfs_online: usize,
+    /// Fresh accounts smurfs made after losing streaks
+    pub new_accounts: usize,
+    /// Completed matches with at least one smurf
+    pub smurf_matches: usize,
+    /// Mean true skill - MMR of smurfs (how far below their skill they are rated)
+    pub avg_smurf_rating_gap: f64,
+    /// Non-smurfs on a team facing a smurf
+    pub victims: ExperienceStats,
+    /// Non-smurfs on a smurf's team
+    pub teammates: ExperienceStats,
+    /// Players in matches without smurfs
+    pub unaffected: ExperienceStats,
+}
+
+/// Smurf archetype: highly skilled players on fresh, low-rated accounts
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SmurfConfig {
+    /// Fraction of generated and acquired players who are smurfs
+    pub fraction: f64,
+    /// Smurfs' true skill is drawn uniformly from [min_skill, 1]
+    pub min_skill: f64,
+    /// MMR a smurf account starts at
+    pub account_mmr: f64,
+    /// Consecutive losses after which a smurf may make a new account
+    pub loss_streak: usize,
+    /// Chance of making a new account after each loss once the streak is reached
+    pub new_account_prob: f64,
+}
+
+impl Default for SmurfConfig {
+    fn default() -> Self {
+        Self {
+            fraction: 0.0,
+            min_skill: 0.6,
+            account_mmr: -0.5,
+            loss_streak: 3,
+            new_account_prob: 0.3,
+        }
+    }
+}
+
+impl SmurfConfig {
+    /// Problems with this config, as `path: message` entries
+    pub fn validate(&self, path: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        if !(0.0..=1.0).contains(&self.fraction) {
+            errors.push(format!("{}.fraction: must be between 0 and 1", path));
+        }
+        if !(-1.0..=1.0).contains(&self.min_skill) {
+            errors.push(format!("{}.min_skill: must be between -1 and 1", path));
+        }
+        if !(-1.0..=1.0).contains(&self.account_mmr) {
+            errors.push(format!("{}.account_mmr: must be between -1 and 1", path));
+        }
+        if !(0.0..=1.0).contains(&self.new_account_prob) {
+            errors.push(format!("{}.new_account_prob: must be between 0 and 1", path));
+        }
+        errors
+    }
+}
+
+/// Under-full match starts for one playlist (whitepaper §6.8)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FillPolicy {
+    /// Smallest lobby that may start, as a fraction of the playlist's required players
+    pub min_fill_ratio: f64,
+    /// Seconds the seed search must have waited before an under-full start is allowed
+    pub wait_threshold: f64,
+}
+
+impl Default for FillPolicy {
+    fn default() -> Self {
+        Self {
+            min_fill_ratio: 0.8,
+            wait_threshold: 60.0,
+        }
+    }
+}
+
+impl FillPolicy {
+    /// Problems with this policy, as `path: message` entries
+    pub fn validate(&self, path: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        if !(self.min_fill_ratio > 0.0 && self.min_fill_ratio <= 1.0) {
+            errors.push(format!("{}.min_fill_ratio: must be in (0, 1]", path));
+        }
+        if !self.wait_threshold.is_finite() || self.wait_threshold < 0.0 {
+            errors.push(format!("{}.wait_threshold: must be a non-negative number", path));
+        }
+        errors
+    }
+}
+
+/// Per-playlist fill share against player preference share
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PlaylistShareStats {
+    /// Players placed into matches of this playlist
+    pub players_matched: usize,
+    /// Searching players who accepted this playlist, each split evenly over their playlists
+    pub preference_weight: f64,
+    /// Share of all matched players
+    pub fill_share: f64,
+    /// Share of all search preferences
+    pub preference_share: f64,
+}
+
+/// Lobby fill metrics (under-full starts)
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct FillStats {
+    pub full_matches: usize,
+    pub underfull_matches: usize,
+    /// Mean players at start / required players
+    pub avg_fill_ratio: f64,
+    pub fill_ratio_sum: f64,
+    /// Mean fill ratio per playlist
+    pub per_playlist_fill_ratio: HashMap<Playlist, f64>,
+    /// Per-playlist (matches, fill ratio sum) for calculating means
+    pub per_playlist_fill_sums: HashMap<Playlist, (usize, f64)>,
+    /// Completed matches and blowouts, split by how the match started
+    pub completed_full: usize,
+    pub blowouts_full: usize,
+    pub completed_underfull: usize,
+    pub blowouts_underfull: usize,
+    pub blowout_rate_full: f64,
+    pub blowout_rate_underfull: f64,
+    /// Completed two-team matches that ended with uneven teams, and how often the smaller team won
+    pub uneven_matches: usize,
+    pub short_team_wins: usize,
+    pub short_team_win_rate: f64,
+}
+
+/// How a match's map is chosen from its playlist's pool
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MapSelection {
+    /// Cycle through the pool in order, one step per match of the playlist
+    #[default]
+    Rotation,
+    /// Offer `vote_options` random maps; each player votes for their favorite and the most votes win
+    Vote,
+}
+
+/// Map pools, map selection, player map preferences and lobby quits over disliked maps
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MapConfig {
+    /// Assign maps to matches and model map preferences
+    pub enabled: bool,
+    /// Rotation or voting
+    pub selection: MapSelection,
+    /// Maps per playlist (playlists without a pool get no map)
+    pub pools: HashMap<Playlist, Vec<String>>,
+    /// Maps offered per vote
+    pub vote_options: usize,
+    /// Chance that a player likes a given map
+    pub like_prob: f64,
+    /// Chance that a player dislikes a given map
+    pub dislike_prob: f64,
+    /// Recently played maps remembered per player
+    pub history_length: usize,
+    /// Chance of quitting the lobby when a disliked map comes up
+    pub dislike_quit_prob: f64,
+    /// Chance of quitting the lobby when a recently played map comes up again
+    pub repeat_quit_prob: f64,
+}
+
+impl Default for MapConfig {
+    fn default() -> Self {
+        let pool = |maps: &[&str]| maps.iter().map(|m| m.to_string()).collect::<Vec<_>>();
+        Self {
+            enabled: false,
+            selection: MapSelection::Rotation,
+            pools: [
+                (Playlist::TEAM_DEATHMATCH, pool(&["Shipment", "Nuketown", "Rust", "Terminal", "Highrise", "Favela"])),
+                (Playlist::SEARCH_AND_DESTROY, pool(&["Terminal", "Highrise", "Crash", "Crossfire", "Vacant", "Overgrown"])),
+                (Playlist::DOMINATION, pool(&["Crash", "Crossfire", "Favela", "Terminal", "Scrapyard", "Skidrow"])),
+                (Playlist::GROUND_WAR, pool(&["Karst River", "Boneyard", "Tavorsk", "Port"])),
+                (Playlist::FREE_FOR_ALL, pool(&["Shipment", "Nuketown", "Rust", "Vacant", "Killhouse"])),
+            ]
+            .into_iter()
+            .collect(),
+            vote_options: 3,
+            like_prob: 0.2,
+            dislike_prob: 0.2,
+            history_length: 5,
+            dislike_quit_prob: 0.15,
+            repeat_quit_prob: 0.05,
+        }
+    }
+}
+
+impl MapConfig {
+    /// Problems with this config, as `path: message` entries
+    pub fn validate(&self, path: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (name, prob) in [
+            ("like_prob", self.like_prob),
+            ("dislike_prob", self.dislike_prob),
+            ("dislike_quit_prob", self.dislike_quit_prob),
+            ("repeat_quit_prob", self.repeat_quit_prob),
+        ] {
+            if !(0.0..=1.0).contains(&prob) {
+                errors.push(format!("{}.{}: must be between 0 and 1", path, name));
+            }
+        }
+        if self.like_prob + self.dislike_prob > 1.0 {
+            errors.push(format!("{}: like_prob + dislike_prob must be at most 1", path));
+        }
+        if self.vote_options == 0 {
+            errors.push(format!("{}.vote_options: must be at least 1", path));
+        }
+        for playlist in self.pool_playlists() {
+            if self.pools[&playlist].is_empty() {
+                errors.push(format!("{}.pools.{}: must list at least one map", path, playlist));
+            }
+        }
+        errors
+    }
+
+    /// Playlists with a map pool, by name
+    pub fn pool_playlists(&self) -> Vec<Playlist> {
+        let mut playlists: Vec<Playlist> = self.pools.keys().copied().collect();
+        playlists.sort_unstable();
+        playlists
+    }
+
+    /// Every map across all pools, in playlist name order without duplicates
+    pub fn all_maps(&self) -> Vec<&String> {
+        let mut maps: Vec<&String> = Vec::new();
+        for playlist in self.pool_playlists() {
+            for map in &self.pools[&playlist] {
+                if !maps.contains(&map) {
+                    maps.push(map);
+                }
+            }
+        }
+        maps
+    }
+}
+
+/// Map selection, repetition and lobby quits over maps
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MapStats {
+    /// Matches started per map
+    pub per_map_matches: HashMap<String, usize>,
+    /// Players in lobbies when a map was chosen
+    pub players_offered: usize,
+    /// Players who quit the lobby over the map
+    pub lobby_quits: usize,
+    pub lobby_quit_rate: f64,
+    /// Lobbies that fell apart because a whole team quit
+    pub dissolved_lobbies: usize,
+    /// Players who stayed and played the map
+    pub player_plays: usize,
+    /// Plays of a map the player dislikes
+    pub disliked_plays: usize,
+    pub disliked_play_rate: f64,
+    /// Plays of a map the player played recently
+    pub repeat_plays: usize,
+    pub repeat_play_rate: f64,
+}
+
+/// Placement playlist (Battle Royale) outcomes
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BattleRoyaleStats {
+    /// Completed placement matches
+    pub matches: usize,
+    /// Mean squads per match
+    pub avg_squads: f64,
+    pub squads_sum: usize,
+    /// How often the strongest squad (by true skill) finished first
+    pub strongest_squad_win_rate: f64,
+    pub strongest_squad_wins: usize,
+    /// Mean eliminations per player-match
+    pub avg_eliminations: f64,
+    pub eliminations_sum: usize,
+    /// Players finishing in the top half of their lobby, and the bottom half
+    pub top_half: ExperienceStats,
+    pub bottom_half: ExperienceStats,
+}
+
+/// Generated scorelines of completed matches
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ScorelineStats {
+    /// Completed matches with a scoreline
+    pub matches: usize,
+    /// Mean match length by the scoreline (seconds)
+    pub avg_length_seconds: f64,
+    pub length_sum_seconds: f64,
+    /// Share of matches where a team reached the score limit before time ran out
+    pub score_limit_rate: f64,
+    pub score_limit_endings: usize,
+    /// Share of matches ended by the mercy rule
+    pub mercy_rate: f64,
+    pub mercy_endings: usize,
+    /// Mean match length of blowouts and of other matches (seconds)
+    pub avg_blowout_length_seconds: f64,
+    pub avg_non_blowout_length_seconds: f64,
+    pub blowouts: usize,
+    pub blowout_length_sum_seconds: f64,
+    /// Mean match length per playlist (seconds)
+    pub per_playlist_avg_length_seconds: HashMap<Playlist, f64>,
+    /// Per playlist: (matches, length sum)
+    pub per_playlist_length_sums: HashMap<Playlist, (usize, f64)>,
+    /// Mean winner's margin (lead over the best other team / combined score)
+    pub avg_margin: f64,
+    pub margin_sum: f64,
+    /// Mean kills and deaths per player-match
+    pub avg_kills: f64,
+    pub avg_deaths: f64,
+    pub kills_sum: usize,
+    pub deaths_sum: usize,
+    pub player_matches: usize,
+}
+
+/// Mid-match abandonment driven by the running score, and join-in-progress backfill
+/// Applies to two-team playlists.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AbandonmentConfig {
+    /// Track a running score and let players leave matches early
+    pub enabled: bool,
+    /// Per-tick chance that a player leaves regardless of the score
+    pub base_quit_rate: f64,
+    /// Extra per-tick chance per unit of score deficit ((opponent - own) / points scored)
+    pub deficit_quit_rate: f64,
+    /// Extra per-tick chance per unit of blowout likelihood, for the trailing team
+    pub blowout_quit_rate: f64,
+    /// Fill open slots with searching players
+    pub backfill: bool,
+    /// Matches past this fraction of their expected duration are not backfilled
+    pub backfill_cutoff: f64,
+}
+
+impl Default for AbandonmentConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            base_quit_rate: 0.0002,
+            deficit_quit_rate: 0.005,
+            blowout_quit_rate: 0.002,
+            backfill: true,
+            backfill_cutoff: 0.75,
+        }
+    }
+}
+
+impl AbandonmentConfig {
+    /// Problems with this config, as `path: message` entries
+    pub fn validate(&self, path: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (name, rate) in [
+            ("base_quit_rate", self.base_quit_rate),
+            ("deficit_quit_rate", self.deficit_quit_rate),
+            ("blowout_quit_rate", self.blowout_quit_rate),
+        ] {
+            if !(0.0..=1.0).contains(&rate) {
+                errors.push(format!("{}.{}: must be between 0 and 1", path, name));
+            }
+        }
+        if !(0.0..=1.0).contains(&self.backfill_cutoff) {
+            errors.push(format!("{}.backfill_cutoff: must be between 0 and 1", path));
+        }
+        errors
+    }
+}
+
+/// New-account acquisition: players created mid-simulation with cold-start attributes
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AcquisitionConfig {
+    /// Expected new accounts per tick
+    pub rate: f64,
+    /// Time-varying acquisition rate (e.g. a launch spike); overrides `rate` from its first step on
+    pub schedule: Option<ArrivalSchedule>,
+    /// Shift of new players' true skill relative to the initial population
+    pub skill_offset: f64,
+    /// MMR new accounts start at, since the matchmaker knows nothing about them yet
+    pub initial_mmr: f64,
+    /// Matches after which an acquired player counts as a veteran
+    pub new_player_matches: usize,
+}
+
+impl Default for AcquisitionConfig {
+    fn default() -> Self {
+        Self {
+            rate: 0.0,
+            schedule: None,
+            skill_offset: -0.2,
+            initial_mmr: 0.0,
+            new_player_matches: 20,
+        }
+    }
+}
+
+impl AcquisitionConfig {
+    /// Expected new accounts at `tick`
+    pub fn rate_at(&self, tick: u64) -> f64 {
+        self.schedule.as_ref().and_then(|s| s.rate_at(tick)).unwrap_or(self.rate)
+    }
+
+    /// Problems with this config, as `path: message` entries
+    pub fn validate(&self, path: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        if !self.rate.is_finite() || self.rate < 0.0 {
+            errors.push(format!("{}.rate: must be a non-negative number", path));
+        }
+        if let Some(schedule) = &self.schedule {
+            errors.extend(schedule.validate(&format!("{}.schedule", path)));
+        }
+        if !(-2.0..=2.0).contains(&self.skill_offset) {
+            errors.push(format!("{}.skill_offset: must be between -2 and 2", path));
+        }
+        if !(-1.0..=1.0).contains(&self.initial_mmr) {
+            errors.push(format!("{}.initial_mmr: must be between -1 and 1", path));
+        }
+        errors
+    }
+}
+
 /// Regional statistics for analysis
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct RegionStats {
@@ -643,6 +1723,10 @@ pub struct RegionStats {
     pub active_matches: usize,
     /// Fraction of matches involving multiple regions
     pub cross_region_match_rate: f64,
+    pub players_online: usize,
+    pub players_searching: usize,
+    /// Expected arrivals per tick at the current time of day
+    pub arrival_rate: f64,
 }
 
 /// Retention model configuration
@@ -659,12 +1743,90 @@ pub struct RetentionConfig {
     pub theta_win_rate: f64,
     /// Coefficient for performance (typically positive: good performance increases retention)
     pub theta_performance: f64,
+    /// Coefficient for eliminations per match (placement playlists)
+    #[serde(default = "default_theta_eliminations")]
+    pub theta_eliminations: f64,
     /// Base logit (before experience terms) - maps to base probability via logistic
     pub base_continue_prob: f64,
     /// How many recent matches to include in experience vector
     pub experience_window_size: usize,
 }
 
+fn default_theta_eliminations() -> f64 {
+    0.2
+}
+
+/// Skill rating system used to update ratings after each match
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RatingSystem {
+    /// Performance vs. expectation: s_i^+ = s_i^- + α(ŷ_i - E[Y_i])
+    #[default]
+    PerformanceDelta,
+    /// Team Elo on win/loss
+    Elo,
+    /// Glicko-2 with rating deviation and volatility
+    Glicko2,
+    /// TrueSkill-style Gaussian team rating
+    TrueSkill,
+}
+
+/// Rating system parameters (all on the raw skill scale [-1, 1])
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RatingConfig {
+    /// Elo K-factor (max rating change per match)
+    pub elo_k_factor: f64,
+    /// Initial rating deviation (Glicko-2 φ / TrueSkill σ) for unrated players
+    pub initial_deviation: f64,
+    /// Initial Glicko-2 volatility
+    pub glicko_initial_volatility: f64,
+    /// Glicko-2 system constant τ (constrains volatility change)
+    pub glicko_tau: f64,
+    /// TrueSkill performance noise β
+    pub trueskill_beta: f64,
+    /// TrueSkill dynamics factor τ (added uncertainty per match)
+    pub trueskill_tau: f64,
+}
+
+impl Default for RatingConfig {
+    fn default() -> Self {
+        Self {
+            elo_k_factor: 0.04,
+            initial_deviation: 0.5,
+            glicko_initial_volatility: 0.06,
+            glicko_tau: 0.5,
+            trueskill_beta: 0.25,
+            trueskill_tau: 0.005,
+        }
+    }
+}
+
+/// Per-player state kept by the rating system
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RatingState {
+    /// Rating uncertainty (Glicko-2 φ / TrueSkill σ, skill scale)
+    pub deviation: f64,
+    /// Glicko-2 volatility σ
+    pub volatility: f64,
+    /// Number of matches this player has been rated in
+    pub rated_matches: usize,
+}
+
+impl RatingState {
+    pub fn initial(config: &RatingConfig) -> Self {
+        Self {
+            deviation: config.initial_deviation,
+            volatility: config.glicko_initial_volatility,
+            rated_matches: 0,
+        }
+    }
+}
+
+impl Default for RatingState {
+    fn default() -> Self {
+        Self::initial(&RatingConfig::default())
+    }
+}
+
 /// An active match
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Match {
@@ -677,15 +1839,18 @@ pub struct Match {
     pub start_time: u64,
     /// Expected duration in simulation ticks
     pub expected_duration: u64,
-    /// Team skills (for outcome prediction)
+    /// Team skills (true skill, for outcome prediction)
     pub team_skills: Vec<f64>,
+    /// Team MMRs (what the matchmaker balanced on)
+    pub team_mmrs: Vec<f64>,
     /// Match quality score
     pub quality_score: f64,
     /// Skill disparity across all players
     pub skill_disparity: f64,
     /// Average delta ping
     pub avg_delta_ping: f64,
-    /// Expected score differential based on skill
+    /// Expected score of team 0 minus team 1 from the playlist's scoring rules (strongest minus
+    /// weakest team for more than two teams)
     pub expected_score_differential: f64,
     /// Win probability imbalance (0-1 scale, how one-sided the match is)
     pub win_probability_imbalance: f64,
@@ -693,11 +1858,223 @@ pub struct Match {
     pub blowout_severity: Option<BlowoutSeverity>,
     /// Performance index per player (player_id -> performance)
     pub player_performances: HashMap<usize, f64>,
+    /// Running score per team (tracked only when mid-match abandonment is enabled)
+    #[serde(default)]
+    pub running_score: Vec<usize>,
+    /// Players who left before the match ended
+    #[serde(default)]
+    pub abandoned_players: Vec<usize>,
+    /// Players who joined the match in progress
+    #[serde(default)]
+    pub backfilled_players: Vec<usize>,
+    /// Players at start / required players (below 1 for under-full starts)
+    #[serde(default = "default_fill_ratio")]
+    pub fill_ratio: f64,
+    /// Map the match is played on (None when maps are disabled)
+    #[serde(default)]
+    pub map: Option<String>,
+    /// Result played out when the match formed (matches whose roster changed mid-match are
+    /// re-decided when they end)
+    #[serde(default)]
+    pub outcome: Option<MatchOutcome>,
+}
+
+fn default_fill_ratio() -> f64 {
+    1.0
+}
+
+/// A match's result, decided when the lobby forms (its scoreline sets the match length)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MatchOutcome {
+    pub winning_team: usize,
+    /// Finishing rank per team (0 = first), for matches of more than two teams and placement playlists
+    pub ranks: Option<Vec<usize>>,
+    /// Eliminations per player (placement playlists)
+    pub eliminations: HashMap<usize, usize>,
+    pub scoreline: Scoreline,
+}
+
+/// One player's line in a finished match
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerScore {
+    pub kills: usize,
+    pub deaths: usize,
+    /// Objective points (plants and defuses in round-based modes)
+    pub objective: usize,
+}
+
+/// Generated result of a match: team scores, player lines and match length
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Scoreline {
+    /// Score per team (kills, rounds, objective points, or eliminations in placement playlists)
+    pub team_scores: Vec<usize>,
+    /// Kills, deaths and objective points per player
+    pub players: HashMap<usize, PlayerScore>,
+    /// Match length (seconds)
+    pub length_seconds: f64,
+    /// A team reached the score limit before time ran out
+    pub reached_limit: bool,
+    /// The mercy rule ended the match
+    #[serde(default)]
+    pub mercy: bool,
+}
+
+impl Scoreline {
+    /// Winner's lead over the best other team as a share of their combined score (0 = level or
+    /// behind, 1 = the others never scored)
+    pub fn margin(&self, winning_team: usize) -> f64 {
+        let Some(&winner) = self.team_scores.get(winning_team) else {
+            return 0.0;
+        };
+        let runner_up = self
+            .team_scores
+            .iter()
+            .enumerate()
+            .filter(|&(team, _)| team != winning_team)
+            .map(|(_, &score)| score)
+            .max()
+            .unwrap_or(0);
+        if winner <= runner_up {
+            return 0.0;
+        }
+        (winner - runner_up) as f64 / (winner + runner_up) as f64
+    }
+}
+
+impl Match {
+    /// Fraction of the expected duration that has elapsed
+    pub fn progress(&self, current_time: u64) -> f64 {
+        current_time.saturating_sub(self.start_time) as f64 / self.expected_duration.max(1) as f64
+    }
+}
+
+/// Matchmaking algorithm selected for each tick
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MatchmakingStrategyKind {
+    /// Seed + greedy lobby construction on the weighted distance metric (whitepaper §3.4)
+    #[default]
+    SeedGreedy,
+    /// First-in-first-out: fill lobbies in queue order, ignoring skill
+    Fifo,
+    /// Closest players geographically, ignoring skill
+    PingOnly,
+    /// Closest skill percentiles, ignoring ping backoff and DC acceptability
+    SkillOnly,
+    /// Greedy lobbies refined by local search to maximize total batch quality
+    BatchOptimal,
+}
+
+impl MatchmakingStrategyKind {
+    pub const ALL: [MatchmakingStrategyKind; 5] = [
+        MatchmakingStrategyKind::SeedGreedy,
+        MatchmakingStrategyKind::Fifo,
+        MatchmakingStrategyKind::PingOnly,
+        MatchmakingStrategyKind::SkillOnly,
+        MatchmakingStrategyKind::BatchOptimal,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MatchmakingStrategyKind::SeedGreedy => "SeedGreedy",
+            MatchmakingStrategyKind::Fifo => "Fifo",
+            MatchmakingStrategyKind::PingOnly => "PingOnly",
+            MatchmakingStrategyKind::SkillOnly => "SkillOnly",
+            MatchmakingStrategyKind::BatchOptimal => "BatchOptimal",
+        }
+    }
+
+    /// Look up a strategy by name (case-insensitive)
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// How the matchmaker picks a playlist for searches that accept several
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlaylistSelection {
+    /// Fill playlists one at a time in definition order
+    FixedOrder,
+    /// Per lobby, the playlist with the best candidate overlap and queue health (whitepaper §6.3)
+    #[default]
+    BestOverlap,
+}
+
+/// Local search parameters for the batch-optimal strategy
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatchConfig {
+    /// Maximum improvement passes over the batch per tick
+    pub improvement_passes: usize,
+    /// Queued searches tried as replacements for each lobby member
+    pub swap_candidates: usize,
+    /// Lobbies (nearest in mean skill) tried for member exchanges
+    pub neighbor_lobbies: usize,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            improvement_passes: 3,
+            swap_candidates: 8,
+            neighbor_lobbies: 3,
+        }
+    }
+}
+
+/// What team balancing minimizes, and how hard the local search tries
+/// The objective is the weighted sum of the gaps between the best and worst team on each measure.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TeamBalanceConfig {
+    /// Weight of the gap in team mean MMR
+    pub mean_gap_weight: f64,
+    /// Weight of the gap in within-team MMR spread (standard deviation)
+    pub variance_gap_weight: f64,
+    /// Weight of the gap between the teams' highest-rated players
+    pub top_player_gap_weight: f64,
+    /// Maximum improvement passes of party moves and swaps for lobbies too large for exact search
+    pub max_passes: usize,
+}
+
+impl Default for TeamBalanceConfig {
+    fn default() -> Self {
+        Self {
+            mean_gap_weight: 1.0,
+            variance_gap_weight: 0.0,
+            top_player_gap_weight: 0.0,
+            max_passes: 20,
+        }
+    }
+}
+
+impl TeamBalanceConfig {
+    /// Problems with this config, as `path: message` entries
+    pub fn validate(&self, path: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        let weights = [
+            ("mean_gap_weight", self.mean_gap_weight),
+            ("variance_gap_weight", self.variance_gap_weight),
+            ("top_player_gap_weight", self.top_player_gap_weight),
+        ];
+        for (field, weight) in weights {
+            if !weight.is_finite() || weight < 0.0 {
+                errors.push(format!("{}.{}: must be a non-negative number", path, field));
+            }
+        }
+        errors
+    }
+}
+
+fn default_use_candidate_index() -> bool {
+    true
 }
 
 /// Matchmaking configuration parameters
+/// Fields missing from a serialized config take their default values.
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct MatchmakingConfig {
+    /// Playlist definitions, in matchmaking order
+    pub playlists: Vec<PlaylistDefinition>,
     /// Maximum acceptable ping (ms)
     pub max_ping: f64,
     /// Delta ping backoff curve parameters
@@ -725,6 +2102,8 @@ pub struct MatchmakingConfig {
     pub quality_weight_ping: f64,
     pub quality_weight_skill_balance: f64,
     pub quality_weight_wait_time: f64,
+    /// Weight of map diversity (maps in the pool the lobby hasn't played recently)
+    pub quality_weight_map_diversity: f64,
     
     /// Fraction of players that participate in parties (0.0 - 1.0)
     /// This controls the baseline solo vs party mix in the simulation.
@@ -739,30 +2118,76 @@ pub struct MatchmakingConfig {
     
     /// Top K candidates to consider per seed
     pub top_k_candidates: usize,
+    /// Generate candidates from a skill/DC bucket index instead of scanning the whole queue
+    #[serde(default = "default_use_candidate_index")]
+    pub use_candidate_index: bool,
     
-    /// Enable expensive exact balancing for small modes (6v6)
+    /// Matchmaking algorithm
+    #[serde(default)]
+    pub matchmaking_strategy: MatchmakingStrategyKind,
+    /// Batch-optimal local search parameters
+    #[serde(default)]
+    pub batch_config: BatchConfig,
+    /// Playlist choice for multi-playlist searches
+    pub playlist_selection: PlaylistSelection,
+    /// Weight of queue health (preference share minus in-match share) against candidate overlap
+    pub playlist_health_weight: f64,
+    
+    /// Enable expensive exact balancing for lobbies with few enough party assignments (6v6)
     pub use_exact_team_balancing: bool,
+    /// Team balancing objective and local search effort
+    #[serde(default)]
+    pub team_balance: TeamBalanceConfig,
     /// Logistic coefficient for win probability calculation
     pub gamma: f64,
-    /// Weight for skill difference in blowout detection
+    /// Weight for skill difference in the pre-match blowout likelihood (drives abandonment)
     pub blowout_skill_coefficient: f64,
-    /// Weight for win-probability imbalance in blowout detection
+    /// Weight for win-probability imbalance in the pre-match blowout likelihood
     pub blowout_imbalance_coefficient: f64,
-    /// Threshold for mild blowouts
+    /// Scoreline margin ((winner - runner-up) / (winner + runner-up)) at which a match is a mild blowout
     pub blowout_mild_threshold: f64,
-    /// Threshold for moderate blowouts
+    /// Scoreline margin for moderate blowouts
     pub blowout_moderate_threshold: f64,
-    /// Threshold for severe blowouts
+    /// Scoreline margin for severe blowouts
     pub blowout_severe_threshold: f64,
     
     /// Skill learning rate (α in update rule: s_i^+ = s_i^- + α(ŷ_i - E[Y_i]))
     pub skill_learning_rate: f64,
     /// Performance noise standard deviation (σ for ε_i ~ N(0, σ²))
     pub performance_noise_std: f64,
-    /// Enable skill evolution (false = static skill, true = evolving skill)
+    /// Enable skill evolution (false = static ratings, true = ratings updated after each match)
     pub enable_skill_evolution: bool,
     /// Update skill percentiles every N matches (batch size)
     pub skill_update_batch_size: usize,
+    /// Rating system applied after each match
+    #[serde(default)]
+    pub rating_system: RatingSystem,
+    /// Rating system parameters
+    #[serde(default)]
+    pub rating_config: RatingConfig,
+    /// Std dev of the initial MMR error around true skill (0 = perfectly known skill)
+    #[serde(default)]
+    pub mmr_initial_noise_std: f64,
+
+    /// Matches a new account plays with a provisional rating
+    pub placement_matches: usize,
+    /// Extra skill similarity window (percentile) for searches with placement players
+    pub placement_skill_widening: f64,
+    /// Extra allowed lobby skill disparity for searches with placement players
+    pub placement_disparity_widening: f64,
+    /// Rating changes during placement are scaled by this factor so the rating converges quickly
+    pub placement_rating_multiplier: f64,
+    /// |MMR - true skill| below which a new player's rating counts as converged
+    pub placement_convergence_error: f64,
+
+    /// Smurf archetype and alt-account behavior
+    pub smurf_config: SmurfConfig,
+    /// Mid-match abandonment and backfill
+    pub abandonment_config: AbandonmentConfig,
+    /// Per-playlist under-full start policies (playlists without one only start full lobbies)
+    pub fill_policies: HashMap<Playlist, FillPolicy>,
+    /// Map pools, selection and map preferences
+    pub map_config: MapConfig,
     
     /// Per-region configuration overrides (optional)
     pub region_configs: HashMap<Region, RegionConfig>,
@@ -774,6 +2199,7 @@ pub struct MatchmakingConfig {
 impl Default for MatchmakingConfig {
     fn default() -> Self {
         Self {
+            playlists: PlaylistDefinition::defaults(),
             max_ping: 200.0,
             delta_ping_initial: 10.0,
             delta_ping_rate: 2.0,
@@ -791,23 +2217,42 @@ impl Default for MatchmakingConfig {
             quality_weight_ping: 0.4,
             quality_weight_skill_balance: 0.4,
             quality_weight_wait_time: 0.2,
+            quality_weight_map_diversity: 0.0,
             // By default, target roughly 50% of players being in parties,
             // with party sizes drawn between 2-4 members.
             party_player_fraction: 0.5,
             tick_interval: 5.0,
             num_skill_buckets: 10,
             top_k_candidates: 50,
+            use_candidate_index: true,
+            matchmaking_strategy: MatchmakingStrategyKind::SeedGreedy,
+            batch_config: BatchConfig::default(),
+            playlist_selection: PlaylistSelection::BestOverlap,
+            playlist_health_weight: 1.0,
             use_exact_team_balancing: true,
+            team_balance: TeamBalanceConfig::default(),
             gamma: 2.0,
             blowout_skill_coefficient: 0.4,
             blowout_imbalance_coefficient: 0.3,
-            blowout_mild_threshold: 0.15,
-            blowout_moderate_threshold: 0.35,
+            blowout_mild_threshold: 0.3,
+            blowout_moderate_threshold: 0.45,
             blowout_severe_threshold: 0.6,
             skill_learning_rate: 0.01,
             performance_noise_std: 0.15,
             enable_skill_evolution: true,
             skill_update_batch_size: 10,
+            rating_system: RatingSystem::PerformanceDelta,
+            rating_config: RatingConfig::default(),
+            mmr_initial_noise_std: 0.0,
+            placement_matches: 5,
+            placement_skill_widening: 0.2,
+            placement_disparity_widening: 0.2,
+            placement_rating_multiplier: 10.0,
+            placement_convergence_error: 0.1,
+            smurf_config: SmurfConfig::default(),
+            abandonment_config: AbandonmentConfig::default(),
+            fill_policies: HashMap::new(),
+            map_config: MapConfig::default(),
             region_configs: HashMap::new(),
             retention_config: RetentionConfig {
                 theta_ping: -0.02,
@@ -815,6 +2260,7 @@ impl Default for MatchmakingConfig {
                 theta_blowout: -0.5,
                 theta_win_rate: 0.8,
                 theta_performance: 0.6,
+                theta_eliminations: default_theta_eliminations(),
                 base_continue_prob: 0.0,
                 experience_window_size: 5,
             },
@@ -823,6 +2269,118 @@ impl Default for MatchmakingConfig {
 }
 
 impl MatchmakingConfig {
+    /// Names accepted by `set_parameter` (the numeric parameters experiments sweep over)
+    pub const SWEEP_PARAMETERS: [&'static str; 9] = [
+        "skill_similarity_initial",
+        "skill_similarity_rate",
+        "skill_similarity_max",
+        "max_skill_disparity_initial",
+        "max_skill_disparity_rate",
+        "delta_ping_initial",
+        "delta_ping_rate",
+        "weight_skill",
+        "weight_geo",
+    ];
+
+    /// Set a sweepable numeric parameter by name
+    pub fn set_parameter(&mut self, name: &str, value: f64) -> Result<(), String> {
+        match name {
+            "skill_similarity_initial" => self.skill_similarity_initial = value,
+            "skill_similarity_rate" => self.skill_similarity_rate = value,
+            "skill_similarity_max" => self.skill_similarity_max = value,
+            "max_skill_disparity_initial" => self.max_skill_disparity_initial = value,
+            "max_skill_disparity_rate" => self.max_skill_disparity_rate = value,
+            "delta_ping_initial" => self.delta_ping_initial = value,
+            "delta_ping_rate" => self.delta_ping_rate = value,
+            "weight_skill" => self.weight_skill = value,
+            "weight_geo" => self.weight_geo = value,
+            _ => return Err(format!("Unknown parameter: {}", name)),
+        }
+        Ok(())
+    }
+
+    /// Definition of a playlist; every playlist in play must be defined in `playlists`
+    pub fn playlist(&self, playlist: Playlist) -> &PlaylistDefinition {
+        self.playlists
+            .iter()
+            .find(|definition| definition.name == playlist)
+            .unwrap_or_else(|| panic!("Undefined playlist: {}", playlist))
+    }
+
+    /// Defined playlists, in matchmaking order
+    pub fn playlist_names(&self) -> impl Iterator<Item = Playlist> + '_ {
+        self.playlists.iter().map(|definition| definition.name)
+    }
+
+    /// Problems with this config, as `path: message` entries
+    pub fn validate(&self, path: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.max_ping <= 0.0 {
+            errors.push(format!("{}.max_ping: must be positive", path));
+        }
+        if self.tick_interval <= 0.0 {
+            errors.push(format!("{}.tick_interval: must be positive", path));
+        }
+        if !(0.0..=1.0).contains(&self.party_player_fraction) {
+            errors.push(format!("{}.party_player_fraction: must be between 0 and 1", path));
+        }
+        if self.num_skill_buckets == 0 {
+            errors.push(format!("{}.num_skill_buckets: must be positive", path));
+        }
+        errors.extend(self.smurf_config.validate(&format!("{}.smurf_config", path)));
+        errors.extend(self.abandonment_config.validate(&format!("{}.abandonment_config", path)));
+        errors.extend(self.map_config.validate(&format!("{}.map_config", path)));
+        errors.extend(self.team_balance.validate(&format!("{}.team_balance", path)));
+        errors.extend(self.validate_playlists(path));
+        for playlist in self.playlist_names() {
+            if let Some(policy) = self.fill_policies.get(&playlist) {
+                errors.extend(policy.validate(&format!("{}.fill_policies.{}", path, playlist)));
+            }
+        }
+        errors
+    }
+
+    /// Problems with the playlist definitions and the playlists other settings refer to
+    pub fn validate_playlists(&self, path: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.playlists.is_empty() {
+            errors.push(format!("{}.playlists: must define at least one playlist", path));
+        }
+        for (i, definition) in self.playlists.iter().enumerate() {
+            errors.extend(definition.validate(&format!("{}.playlists[{}]", path, i)));
+            if self.playlists[..i].iter().any(|other| other.name == definition.name) {
+                errors.push(format!("{}.playlists[{}].name: duplicate playlist {}", path, i, definition.name));
+            }
+        }
+        let defined = |playlist: &Playlist| self.playlists.iter().any(|definition| definition.name == *playlist);
+        // Map pools only matter once maps are enabled (the default pools name the built-in playlists)
+        let pools = self.map_config.enabled.then_some(&self.map_config.pools);
+        let mut referenced: Vec<(&str, Playlist)> = self
+            .fill_policies
+            .keys()
+            .map(|p| ("fill_policies", *p))
+            .chain(pools.into_iter().flat_map(|pools| pools.keys().map(|p| ("map_config.pools", *p))))
+            .collect();
+        referenced.sort_unstable();
+        for (field, playlist) in referenced {
+            if !defined(&playlist) {
+                errors.push(format!("{}.{}.{}: playlist is not defined", path, field, playlist));
+            }
+        }
+        errors
+    }
+
+    /// Smallest lobby that may start for a seed search that has waited `wait_time` seconds
+    pub fn min_lobby_size(&self, playlist: Playlist, wait_time: f64) -> usize {
+        let required = self.playlist(playlist).required_players();
+        match self.fill_policies.get(&playlist) {
+            Some(policy) if wait_time >= policy.wait_threshold => {
+                ((required as f64 * policy.min_fill_ratio).ceil() as usize).clamp(2, required)
+            }
+            _ => required,
+        }
+    }
+
     /// Calculate allowed delta ping based on wait time
     pub fn delta_ping_backoff(&self, wait_time: f64) -> f64 {
         (self.delta_ping_initial + self.delta_ping_rate * wait_time)
@@ -903,6 +2461,8 @@ pub struct SimulationStats {
     pub time_elapsed: f64,
     /// Number of matchmaking ticks
     pub ticks: u64,
+    /// UTC hour of day (set when diurnal arrival curves are active)
+    pub hour_of_day_utc: Option<f64>,
     
     /// Total matches created
     pub total_matches: usize,
@@ -934,6 +2494,7 @@ pub struct SimulationStats {
     
     /// Match quality
     pub avg_match_quality: f64,
+    pub match_quality_samples: Vec<f64>,
     
     /// Blowout rate (games with >2x score differential)
     pub blowout_rate: f64,
@@ -946,8 +2507,10 @@ pub struct SimulationStats {
     pub per_playlist_blowout_counts: HashMap<Playlist, usize>,
     /// Per-playlist match counts (for calculating rates)
     pub per_playlist_match_counts: HashMap<Playlist, usize>,
-    /// Team skill difference samples (distribution)
+    /// Team skill difference samples (distribution, true skill)
     pub team_skill_difference_samples: Vec<f64>,
+    /// Team MMR difference samples (the imbalance the matchmaker believed it created)
+    pub team_mmr_difference_samples: Vec<f64>,
     
     /// Per skill bucket statistics
     pub bucket_stats: HashMap<usize, BucketStats>,
@@ -969,6 +2532,35 @@ pub struct SimulationStats {
     pub total_skill_updates: usize,
     /// Distribution of performance indices
     pub performance_samples: Vec<f64>,
+    /// Average rating deviation across the population (uncertainty-tracking systems)
+    pub avg_rating_deviation: f64,
+    /// Mean absolute rating error |MMR - true skill| across the population
+    pub avg_rating_error: f64,
+    /// Rank correlation proxy: mean |percentile by MMR - percentile by true skill|
+    pub avg_percentile_error: f64,
+
+    /// Players created by the acquisition process
+    pub total_acquired: usize,
+    /// Placement matches and rating convergence of new accounts
+    pub placement: PlacementStats,
+    /// Acquired players who have not yet played `new_player_matches` matches
+    pub new_players: CohortStats,
+    /// Everyone else: the initial population and acquired players past their first matches
+    pub veteran_players: CohortStats,
+    /// Smurfs and the experience of their opponents
+    pub smurfs: SmurfStats,
+    /// Mid-match abandonment and backfill
+    pub abandonment: AbandonmentStats,
+    /// Lobby fill rates and under-full match outcomes
+    pub fill: FillStats,
+    /// Share of matched players per playlist against the share of search preferences
+    pub playlist_shares: HashMap<Playlist, PlaylistShareStats>,
+    /// Map selection, repetition and lobby quits
+    pub maps: MapStats,
+    /// Squad placements and eliminations in placement playlists
+    pub battle_royale: BattleRoyaleStats,
+    /// Match lengths, margins and kills from the generated scorelines
+    pub scorelines: ScorelineStats,
     
     /// Retention model metrics
     /// Continuation rate by skill bucket (bucket_id -> continuation_rate)
@@ -1019,6 +2611,9 @@ pub struct SimulationStats {
     pub region_stats: HashMap<Region, RegionStats>,
     /// Track if each match was cross-region (for calculating cross-region match rate)
     pub cross_region_match_samples: Vec<bool>,
+
+    /// Batch-optimal vs. greedy comparison (BatchOptimal strategy only)
+    pub batch_comparison: BatchComparisonStats,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -1032,21 +2627,78 @@ pub struct BucketStats {
     pub matches_played: usize,
 }
 
+/// One tick's batch assignment compared with the greedy assignment it started from
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BatchTickReport {
+    /// Sum of lobby quality scores
+    pub greedy_quality: f64,
+    pub batch_quality: f64,
+    /// Players placed into lobbies
+    pub greedy_players_matched: usize,
+    pub batch_players_matched: usize,
+    /// Total wait (player-seconds) of players left in the queue
+    pub greedy_queue_wait: f64,
+    pub batch_queue_wait: f64,
+}
+
+/// Cumulative batch-optimal gains over greedy matchmaking
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BatchComparisonStats {
+    pub ticks_compared: usize,
+    pub greedy_total_quality: f64,
+    pub batch_total_quality: f64,
+    pub greedy_players_matched: usize,
+    pub batch_players_matched: usize,
+    pub greedy_queue_wait_seconds: f64,
+    pub batch_queue_wait_seconds: f64,
+    /// Relative total quality gain ((batch - greedy) / greedy)
+    pub quality_gain: f64,
+    /// Relative reduction in player-seconds left waiting in queue
+    pub wait_time_gain: f64,
+}
+
+impl BatchComparisonStats {
+    pub fn record(&mut self, report: &BatchTickReport) {
+        self.ticks_compared += 1;
+        self.greedy_total_quality += report.greedy_quality;
+        self.batch_total_quality += report.batch_quality;
+        self.greedy_players_matched += report.greedy_players_matched;
+        self.batch_players_matched += report.batch_players_matched;
+        self.greedy_queue_wait_seconds += report.greedy_queue_wait;
+        self.batch_queue_wait_seconds += report.batch_queue_wait;
+
+        if self.greedy_total_quality > 0.0 {
+            self.quality_gain = (self.batch_total_quality - self.greedy_total_quality) / self.greedy_total_quality;
+        }
+        if self.greedy_queue_wait_seconds > 0.0 {
+            self.wait_time_gain = (self.greedy_queue_wait_seconds - self.batch_queue_wait_seconds)
+                / self.greedy_queue_wait_seconds;
+        }
+    }
+}
+
 /// Research experiment configuration
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ExperimentConfig {
+    #[serde(default)]
     pub name: String,
+    #[serde(default)]
     pub description: String,
     /// Parameter to vary
     pub parameter: String,
     /// Values to test
     pub values: Vec<f64>,
     /// Number of simulation runs per value
+    #[serde(default = "default_runs_per_value")]
     pub runs_per_value: usize,
     /// Simulation duration per run (ticks)
     pub ticks_per_run: u64,
 }
 
+fn default_runs_per_value() -> usize {
+    1
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1064,6 +2716,7 @@ mod tests {
             acceptable_playlists: HashSet::new(),
             search_start_time: 0,
             acceptable_dcs: HashSet::new(),
+            placement: false,
         };
         
         let tick_interval = 5.0;
@@ -1108,4 +2761,38 @@ mod tests {
         let expected = (config.delta_ping_initial + config.delta_ping_rate * wait_seconds).min(config.delta_ping_max);
         assert_eq!(backoff, expected);
     }
+
+    #[test]
+    fn test_config_validation_covers_playlists_and_sub_configs() {
+        assert!(MatchmakingConfig::default().validate("config").is_empty());
+
+        let mut config: MatchmakingConfig =
+            serde_json::from_str(r#"{"team_balance": {"mean_gap_weight": -1.0}, "fill_policies": {"TeamDeathmatch": {"min_fill_ratio": 0.0}}}"#)
+                .unwrap();
+        config.playlists[0].team_count = 0;
+        let errors = config.validate("config");
+        for expected in [
+            "config.playlists[0]: team_count and team_size must be at least 1",
+            "config.team_balance.mean_gap_weight: must be a non-negative number",
+            "config.fill_policies.TeamDeathmatch.min_fill_ratio: must be in (0, 1]",
+        ] {
+            assert!(errors.iter().any(|e| e == expected), "{:?}", errors);
+        }
+    }
+
+    #[test]
+    fn test_diurnal_profile_follows_local_time() {
+        let profile = DiurnalProfile::typical(8.0);
+        // 12:00 UTC is 20:00 local, the typical evening peak
+        assert!(profile.multiplier(12.0) > 1.5);
+        assert!(profile.multiplier(20.0) < 0.5);
+        let daily_mean = (0..240).map(|i| profile.multiplier(i as f64 / 10.0)).sum::<f64>() / 240.0;
+        assert!((daily_mean - 1.0).abs() < 1e-9);
+
+        let diurnal = DiurnalArrivals { day_length_ticks: 48, start_hour_utc: 22.0, ..DiurnalArrivals::typical() };
+        assert_eq!(diurnal.hour_utc(6, 5.0), 1.0);
+        assert_eq!(diurnal.multiplier(Region::Other, 3.0), 1.0);
+        let bad = DiurnalProfile { utc_offset_hours: 20.0, hourly: vec![1.0; 12] };
+        assert_eq!(bad.validate("p").len(), 2);
+    }
 }
@@ -76,8 +76,8 @@ const defaultConfig = {
   gamma: 2.0,
   blowoutSkillCoefficient: 0.4,
   blowoutImbalanceCoefficient: 0.3,
-  blowoutMildThreshold: 0.15,
-  blowoutModerateThreshold: 0.35,
+  blowoutMildThreshold: 0.3,
+  blowoutModerateThreshold: 0.45,
   blowoutSevereThreshold: 0.6,
   skillLearningRate: 0.01,
   performanceNoiseStd: 0.15,
@@ -231,8 +231,8 @@ export default function MatchmakingSimulator() {
       gamma: jsConfig.gamma ?? 2.0,
       blowout_skill_coefficient: jsConfig.blowoutSkillCoefficient ?? 0.4,
       blowout_imbalance_coefficient: jsConfig.blowoutImbalanceCoefficient ?? 0.3,
-      blowout_mild_threshold: jsConfig.blowoutMildThreshold ?? 0.15,
-      blowout_moderate_threshold: jsConfig.blowoutModerateThreshold ?? 0.35,
+      blowout_mild_threshold: jsConfig.blowoutMildThreshold ?? 0.3,
+      blowout_moderate_threshold: jsConfig.blowoutModerateThreshold ?? 0.45,
       blowout_severe_threshold: jsConfig.blowoutSevereThreshold ?? 0.6,
       skill_learning_rate: jsConfig.skillLearningRate ?? 0.01,
       performance_noise_std: jsConfig.performanceNoiseStd ?? 0.15,
@@ -29,8 +29,8 @@ const getBaseConfig = () => ({
   gamma: 2.0,
   blowoutSkillCoefficient: 0.4,
   blowoutImbalanceCoefficient: 0.3,
-  blowoutMildThreshold: 0.15,
-  blowoutModerateThreshold: 0.35,
+  blowoutMildThreshold: 0.3,
+  blowoutModerateThreshold: 0.45,
   blowoutSevereThreshold: 0.6,
   skillLearningRate: 0.01,
   performanceNoiseStd: 0.15,